@@ -6,8 +6,11 @@ use lakestream::Config;
 use tokio::runtime::Builder;
 
 use crate::subcommands::cp::*;
+use crate::subcommands::du::*;
+use crate::subcommands::grep::*;
 use crate::subcommands::ls::*;
 use crate::subcommands::request::*;
+use crate::subcommands::tree::*;
 
 const PROGRAM_NAME: &str = "lakestream";
 
@@ -29,7 +32,10 @@ pub fn run_cli(args: Vec<String>) {
         )
         .subcommand(request_subcommand()) // "-X/--request [GET,PUT]"
         .subcommand(ls_subcommand()) // "ls [URI]"
-        .subcommand(cp_subcommand()); // "cp" [SOURCE] [TARGET]
+        .subcommand(cp_subcommand()) // "cp" [SOURCE] [TARGET]
+        .subcommand(grep_subcommand()) // "grep" [PATTERN] [URI]
+        .subcommand(du_subcommand()) // "du" [URI]
+        .subcommand(tree_subcommand()); // "tree" [URI]
 
     let matches = app.try_get_matches_from(args).unwrap_or_else(|e| {
         e.exit();
@@ -48,6 +54,15 @@ pub fn run_cli(args: Vec<String>) {
         Some(("cp", matches)) => {
             rt.block_on(handle_cp(matches, &mut config));
         }
+        Some(("grep", matches)) => {
+            rt.block_on(handle_grep(matches, &mut config));
+        }
+        Some(("du", matches)) => {
+            rt.block_on(handle_du(matches, &mut config));
+        }
+        Some(("tree", matches)) => {
+            rt.block_on(handle_tree(matches, &mut config));
+        }
         _ => {
             eprintln!("No valid subcommand provided");
         }
@@ -0,0 +1,39 @@
+use clap::{Arg, Command};
+
+pub use super::du_handler::handle_du;
+use super::filter::with_filter_args;
+
+pub fn du_subcommand() -> Command {
+    let command = Command::new("du")
+        .about(
+            "Summarize the size and number of objects per prefix (virtual \
+             directory), e.g. to find the datasets that take up the most \
+             storage",
+        )
+        .arg(
+            Arg::new("uri").index(1).required(true).help(
+                "Bucket or prefix to summarize. E.g. s3://bucket-name/data/",
+            ),
+        )
+        .arg(
+            Arg::new("depth")
+                .long("depth")
+                .short('d')
+                .value_parser(clap::value_parser!(usize))
+                .help(
+                    "Summarize prefixes down to this many levels below the \
+                     URI, deeper objects count towards their prefix at that \
+                     level. 0 prints the total only [default: all levels]",
+                ),
+        )
+        .arg(
+            Arg::new("size_units")
+                .long("size-units")
+                .value_parser(["iec", "si"])
+                .help(
+                    "Print sizes in powers of 1024 (KiB, MiB, ...) or of 1000 \
+                     (kB, MB, ...) [default: iec]",
+                ),
+        );
+    with_filter_args(command)
+}
@@ -0,0 +1,79 @@
+use std::sync::{Arc, Mutex};
+
+use lakestream::{
+    format_bytes, CallbackWrapper, Config, FileObject, ObjectStoreHandler,
+    ParsedUri, PrefixTotals, SizeUnits,
+};
+
+use super::filter::filter_from_matches;
+
+pub async fn handle_du(du_matches: &clap::ArgMatches, config: &mut Config) {
+    let uri = du_matches.get_one::<String>("uri").unwrap();
+    let size_units = size_units_from_matches(du_matches);
+    let Some(totals) = list_prefix_totals(du_matches, config).await else {
+        return;
+    };
+
+    let uri_prefix = format!("{}/", uri.trim_end_matches('/'));
+    for (prefix, prefix_totals) in totals.iter() {
+        println!(
+            "{:>9} {:>9} {}{}",
+            format_bytes(prefix_totals.size, size_units),
+            prefix_totals.objects,
+            uri_prefix,
+            prefix
+        );
+    }
+}
+
+// lists the objects under the "uri" argument recursively, into totals per
+// prefix relative to it, down to the "depth" argument. Also handles the
+// filter arguments. None if the listing failed.
+pub(super) async fn list_prefix_totals(
+    matches: &clap::ArgMatches,
+    config: &Config,
+) -> Option<PrefixTotals> {
+    let uri = matches.get_one::<String>("uri").unwrap();
+    let depth = matches.get_one::<usize>("depth").copied();
+    let filter = filter_from_matches(matches);
+
+    // object keys start with the path of the URI
+    let mut base = ParsedUri::from_uri(uri, true).path.unwrap_or_default();
+    if !base.is_empty() && !base.ends_with('/') {
+        base.push('/');
+    }
+    let totals = Arc::new(Mutex::new(PrefixTotals::new(&base, depth)));
+
+    let handler = ObjectStoreHandler::new(None);
+    let callback = {
+        let totals = Arc::clone(&totals);
+        Some(CallbackWrapper::create_sync(
+            move |file_objects: &[FileObject]| {
+                totals.lock().unwrap().extend(file_objects);
+            },
+        ))
+    };
+
+    if let Err(err) = handler
+        .list_objects(uri, config, true, None, &filter, callback)
+        .await
+    {
+        eprintln!("Error: {:?}", err);
+        return None;
+    }
+
+    // the callback, and its reference, is dropped by now
+    let totals = Arc::try_unwrap(totals)
+        .ok()
+        .expect("listing callback still referenced");
+    Some(totals.into_inner().unwrap())
+}
+
+// the "size_units" argument, IEC by default
+pub(super) fn size_units_from_matches(matches: &clap::ArgMatches) -> SizeUnits {
+    matches
+        .get_one::<String>("size_units")
+        .map_or(SizeUnits::Iec, |size_units| {
+            SizeUnits::from_name(size_units).unwrap()
+        })
+}
@@ -0,0 +1,197 @@
+use clap::{Arg, ArgAction, Command};
+use lakestream::{FileObjectFilter, NameMode};
+
+/// Arguments that select objects, shared by the subcommands that list.
+pub fn with_filter_args(command: Command) -> Command {
+    command
+        .arg(
+            Arg::new("name").long("name").short('n').help(
+                "Filter objects based on name. E.g. 'foo', 'foo.*', '.*bar'",
+            ),
+        )
+        .arg(
+            Arg::new("name_mode")
+                .long("name-mode")
+                .value_parser(["regex", "glob"])
+                .help(
+                    "Syntax of --name: a regex, or a glob such as \
+                     '*.parquet' or 'year=2023/**/part-*.csv' [default: \
+                     regex]",
+                ),
+        )
+        .arg(
+            Arg::new("size")
+                .long("size")
+                .short('s')
+                .num_args(1)
+                .allow_hyphen_values(true)
+                .help(
+                    "Filter objects based on size. E.g. '-1K', '+4M', '+1G', \
+                     '-1G', '5G', '1G-2G'. K, M, G, T, P and E (or KiB, MiB, \
+                     ...) are powers of 1024, KB, MB, ... powers of 1000. \
+                     Fractions such as '+2.5GB' are allowed",
+                ),
+        )
+        .arg(
+            Arg::new("mtime")
+                .long("mtime")
+                .short('t')
+                .num_args(1)
+                .allow_hyphen_values(true)
+                .help(
+                    "Filter objects based on the time offset. E.g. '-60s', \
+                     '+5m', '-1h', '+2D', '-3W', '+1M', '-1Y'. Or on a date \
+                     (range): '2023-01-01', '2023-01-01..2023-01-31', \
+                     '..2023-06-30T12:00:00Z'",
+                ),
+        )
+        .arg(Arg::new("where").long("where").short('w').help(
+            "Filter objects on an expression of name, size, mtime and \
+                 tag.<key>. E.g. \"(name ~ '\\.csv$' or name like '*.tsv') \
+                 and size > 10M and not mtime < 7D\"",
+        ))
+        .arg(
+            Arg::new("owner").long("owner").help(
+                "Filter objects on owner, by name or uid (Local Filesystem)",
+            ),
+        )
+        .arg(
+            Arg::new("group").long("group").help(
+                "Filter objects on group, by name or gid (Local Filesystem)",
+            ),
+        )
+        .arg(
+            Arg::new("perm")
+                .long("perm")
+                .num_args(1)
+                .allow_hyphen_values(true)
+                .help(
+                    "Filter objects on permission bits (Local Filesystem). \
+                     E.g. '644' (exactly), '-600' (at least), '/022' (any of)",
+                ),
+        )
+        .arg(
+            Arg::new("exclude")
+                .long("exclude")
+                .action(ArgAction::Append)
+                .help(
+                    "Skip objects and directories matching a glob, \
+                     repeatable. E.g. '_temporary/', '.git/', '_SUCCESS'",
+                ),
+        )
+        .arg(
+            Arg::new("include")
+                .long("include")
+                .action(ArgAction::Append)
+                .help(
+                    "Only list objects matching a glob, repeatable. E.g. \
+                     '*.parquet'",
+                ),
+        )
+        .arg(
+            Arg::new("ignore_file").long("ignore-file").help(
+                "Read exclude patterns from a file with gitignore syntax",
+            ),
+        )
+}
+
+/// The filter from the arguments of `with_filter_args`, or None if no
+/// filter is given. Exits on invalid filter arguments.
+pub fn filter_from_matches(
+    matches: &clap::ArgMatches,
+) -> Option<FileObjectFilter> {
+    let filter_name =
+        matches.get_one::<String>("name").map(ToString::to_string);
+    let filter_size =
+        matches.get_one::<String>("size").map(ToString::to_string);
+    let filter_mtime =
+        matches.get_one::<String>("mtime").map(ToString::to_string);
+
+    let name_mode = match matches.get_one::<String>("name_mode") {
+        Some(name_mode) => NameMode::from_name(name_mode).unwrap(),
+        None => NameMode::Regex,
+    };
+    let filter_owner = matches.get_one::<String>("owner");
+    let filter_group = matches.get_one::<String>("group");
+    let filter_perm = matches.get_one::<String>("perm");
+    let filter_where = matches.get_one::<String>("where");
+    let excludes: Vec<&String> = matches
+        .get_many::<String>("exclude")
+        .map(Iterator::collect)
+        .unwrap_or_default();
+    let includes: Vec<&String> = matches
+        .get_many::<String>("include")
+        .map(Iterator::collect)
+        .unwrap_or_default();
+    let ignore_file = matches.get_one::<String>("ignore_file");
+
+    match (
+        &filter_name,
+        &filter_size,
+        &filter_mtime,
+        filter_owner,
+        filter_group,
+        filter_perm,
+        filter_where,
+    ) {
+        (None, None, None, None, None, None, None)
+            if excludes.is_empty()
+                && includes.is_empty()
+                && ignore_file.is_none() =>
+        {
+            None
+        }
+        _ => {
+            let filter_result = FileObjectFilter::new(
+                None,
+                filter_size.as_deref(),
+                filter_mtime.as_deref(),
+            )
+            .and_then(|filter| match &filter_name {
+                Some(name) => filter.with_name(name, name_mode),
+                None => Ok(filter),
+            })
+            .and_then(|filter| match filter_perm {
+                Some(perm) => filter.with_mode(perm),
+                None => Ok(filter),
+            })
+            .and_then(|filter| match filter_where {
+                Some(expression) => filter.with_expression(expression),
+                None => Ok(filter),
+            })
+            // patterns on the command line come after (and so override)
+            // those in the ignore file
+            .and_then(|filter| match ignore_file {
+                Some(path) => std::fs::read_to_string(path)
+                    .map_err(|err| format!("{}: {}", path, err))
+                    .and_then(|contents| filter.with_ignore_file(&contents)),
+                None => Ok(filter),
+            })
+            .and_then(|filter| {
+                excludes.iter().try_fold(filter, |filter, pattern| {
+                    filter.with_exclude(pattern)
+                })
+            })
+            .and_then(|filter| {
+                includes.iter().try_fold(filter, |filter, pattern| {
+                    filter.with_include(pattern)
+                })
+            })
+            .map(|filter| match filter_owner {
+                Some(owner) => filter.with_owner(owner),
+                None => filter,
+            })
+            .map(|filter| match filter_group {
+                Some(group) => filter.with_group(group),
+                None => filter,
+            });
+            match filter_result {
+                Ok(filter) => Some(filter),
+                Err(err) => {
+                    eprintln!("Error: {}", err);
+                    std::process::exit(1);
+                }
+            }
+        }
+    }
+}
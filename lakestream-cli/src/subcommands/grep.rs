@@ -0,0 +1,28 @@
+use clap::{Arg, Command};
+
+use super::filter::with_filter_args;
+pub use super::grep_handler::handle_grep;
+
+pub fn grep_subcommand() -> Command {
+    let command =
+        Command::new("grep")
+            .about(
+                "Search the contents of objects for lines matching a regex, \
+             e.g. to find the log that contains a request id",
+            )
+            .arg(Arg::new("pattern").index(1).required(true).help(
+                "Regex to search for. E.g. 'request_id=abc', '(?i)error'",
+            ))
+            .arg(Arg::new("uri").index(2).required(true).help(
+                "Object, or prefix to search all objects under. E.g. \
+                     s3://bucket-name/logs/",
+            ));
+    with_filter_args(command).arg(
+        Arg::new("concurrency")
+            .long("concurrency")
+            .short('j')
+            .default_value("8")
+            .value_parser(clap::value_parser!(usize))
+            .help("Number of objects to fetch and search at the same time"),
+    )
+}
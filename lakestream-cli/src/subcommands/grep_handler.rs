@@ -0,0 +1,28 @@
+use lakestream::{
+    CallbackItem, CallbackWrapper, Config, LineMatch, ObjectStoreHandler,
+};
+
+use super::filter::filter_from_matches;
+
+pub async fn handle_grep(grep_matches: &clap::ArgMatches, config: &mut Config) {
+    let pattern = grep_matches.get_one::<String>("pattern").unwrap();
+    let uri = grep_matches.get_one::<String>("uri").unwrap();
+    let concurrency = *grep_matches.get_one::<usize>("concurrency").unwrap();
+    let filter = filter_from_matches(grep_matches);
+
+    let handler = ObjectStoreHandler::new(None);
+    let callback = Some(CallbackWrapper::create_sync(print_line_matches));
+
+    if let Err(err) = handler
+        .grep_objects(uri, config, pattern, &filter, concurrency, callback)
+        .await
+    {
+        eprintln!("Error: {:?}", err);
+    }
+}
+
+fn print_line_matches(line_matches: &[LineMatch]) {
+    for line_match in line_matches {
+        println!("{}", line_match.println_path());
+    }
+}
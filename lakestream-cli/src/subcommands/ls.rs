@@ -1,49 +1,137 @@
 use clap::{Arg, ArgAction, Command};
 
+use super::filter::with_filter_args;
 pub use super::ls_handler::handle_ls;
 
 pub fn ls_subcommand() -> Command {
-    Command::new("ls")
-        .about("List objects on Local Filesystem or an S3 bucket")
+    let command = Command::new("ls")
+        .about(
+            "List objects on Local Filesystem, an S3 bucket, an Azure \
+             container or a web server directory index",
+        )
         .arg(
             Arg::new("uri")
                 .index(1)
                 .required(true)
                 .help("URI to list objects from. E.g. s3://bucket-name/"),
+        );
+    with_filter_args(command)
+        .arg(
+            Arg::new("no_ignore")
+                .long("no-ignore")
+                .action(ArgAction::SetTrue)
+                .help(
+                    "Do not read .lakestreamignore in the listed directory \
+                     (Local Filesystem)",
+                ),
         )
         .arg(
-            Arg::new("name").long("name").short('n').help(
-                "Filter objects based on name. E.g. 'foo', 'foo.*', '.*bar'",
-            ),
+            Arg::new("recursive")
+                .long("recursive")
+                .short('r')
+                .action(ArgAction::SetTrue)
+                .help("List (virtual) subdirectories recursively"),
         )
         .arg(
-            Arg::new("size")
-                .long("size")
-                .short('s')
-                .num_args(1)
-                .allow_hyphen_values(true)
+            Arg::new("symlinks")
+                .long("symlinks")
+                .value_parser(["follow", "skip", "list"])
                 .help(
-                    "Filter objects based on size. E.g. '-1K', '+4M', '+1G', \
-                     '-1G', '5G', '1G-2G'",
+                    "How to treat symlinks on Local Filesystem (follow, skip \
+                     or list the link itself) [default: follow]",
                 ),
         )
         .arg(
-            Arg::new("mtime")
-                .long("mtime")
-                .short('t')
-                .num_args(1)
-                .allow_hyphen_values(true)
+            Arg::new("one_file_system")
+                .long("one-file-system")
+                .action(ArgAction::SetTrue)
                 .help(
-                    "Filter objects based on the time offset. E.g. '-60s', \
-                     '+5m', '-1h', '+2D', '-3W', '+1M', '-1Y'",
+                    "Do not descend into directories on other filesystems \
+                     (Local Filesystem)",
                 ),
         )
         .arg(
-            Arg::new("recursive")
-                .long("recursive")
-                .short('r')
+            Arg::new("keep_going")
+                .long("keep-going")
                 .action(ArgAction::SetTrue)
-                .help("List (virtual) subdirectories recursively"),
+                .help(
+                    "Report directories that cannot be read and continue \
+                     (Local Filesystem)",
+                ),
+        )
+        .arg(
+            Arg::new("metadata")
+                .long("metadata")
+                .action(ArgAction::SetTrue)
+                .help(
+                    "Add owner, group, mode and inode as tags (Local \
+                     Filesystem)",
+                ),
+        )
+        .arg(
+            Arg::new("xattrs")
+                .long("xattrs")
+                .action(ArgAction::SetTrue)
+                .help(
+                    "Add extended attributes (user.*) as tags (Local \
+                     Filesystem)",
+                ),
+        )
+        .arg(
+            Arg::new("sort")
+                .long("sort")
+                .value_parser(["name", "size", "mtime"])
+                .help(
+                    "Sort the listing on name, size or modification time \
+                     (ascending). Prints once the listing is complete",
+                ),
+        )
+        .arg(
+            Arg::new("reverse")
+                .long("reverse")
+                .action(ArgAction::SetTrue)
+                .requires("sort")
+                .help("Sort in descending order"),
+        )
+        .arg(
+            Arg::new("largest")
+                .long("largest")
+                .value_name("N")
+                .value_parser(clap::value_parser!(usize))
+                .conflicts_with_all(["sort", "newest"])
+                .help(
+                    "Print only the N largest files. Keeps N files in \
+                     memory, not the listing, and is not limited to \
+                     --max-files unless it is given",
+                ),
+        )
+        .arg(
+            Arg::new("newest")
+                .long("newest")
+                .value_name("N")
+                .value_parser(clap::value_parser!(usize))
+                .conflicts_with("sort")
+                .help("Print only the N most recently modified files"),
+        )
+        .arg(
+            Arg::new("output")
+                .long("output")
+                .short('o')
+                .value_parser(["text", "json", "ndjson", "csv"])
+                .default_value("text")
+                .help(
+                    "Output format. json, ndjson and csv print bucket, key, \
+                     size, mtime (epoch and RFC3339), ETag and tags",
+                ),
+        )
+        .arg(
+            Arg::new("size_units")
+                .long("size-units")
+                .value_parser(["iec", "si"])
+                .help(
+                    "Print sizes in powers of 1024 (KiB, MiB, ...) or of 1000 \
+                     (kB, MB, ...) [default: iec]",
+                ),
         )
         .arg(
             Arg::new("max_files")
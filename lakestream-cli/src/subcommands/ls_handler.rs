@@ -1,37 +1,129 @@
+use std::sync::{Arc, Mutex};
+
+use clap::parser::ValueSource;
 use lakestream::{
-    CallbackItem, CallbackWrapper, Config, FileObjectFilter, LakestreamError,
-    ListObjectsResult, ObjectStoreHandler,
+    CallbackWrapper, Config, FileObject, FileObjectFilter, LakestreamError,
+    ListObjectsResult, ObjectStore, ObjectStoreHandler, ParsedUri, SizeUnits,
+    SortKey, TopFileObjects,
 };
 use log::info;
 
+use super::du_handler::size_units_from_matches;
+use super::filter::filter_from_matches;
+use super::output::{ObjectPrinter, OutputFormat};
+
 pub async fn handle_ls(ls_matches: &clap::ArgMatches, config: &mut Config) {
     let (uri, recursive, max_files, filter) =
         prepare_handle_ls_arguments(ls_matches);
 
+    if let Some(symlinks) = ls_matches.get_one::<String>("symlinks") {
+        config.insert(
+            "LAKESTREAM_LOCALFS_SYMLINKS".to_string(),
+            symlinks.to_string(),
+        );
+    }
+    if ls_matches.get_flag("one_file_system") {
+        config.insert(
+            "LAKESTREAM_LOCALFS_ONE_FILESYSTEM".to_string(),
+            "true".to_string(),
+        );
+    }
+    if ls_matches.get_flag("keep_going") {
+        config.insert(
+            "LAKESTREAM_LOCALFS_KEEP_GOING".to_string(),
+            "true".to_string(),
+        );
+    }
+    if ls_matches.get_flag("metadata") {
+        config.insert(
+            "LAKESTREAM_LOCALFS_METADATA".to_string(),
+            "true".to_string(),
+        );
+    }
+    if ls_matches.get_flag("xattrs") {
+        config.insert(
+            "LAKESTREAM_LOCALFS_XATTRS".to_string(),
+            "true".to_string(),
+        );
+    }
+    if ls_matches.get_flag("no_ignore") {
+        config.insert(
+            "LAKESTREAM_LOCALFS_NO_IGNORE".to_string(),
+            "true".to_string(),
+        );
+    }
+
+    let size_units = size_units_from_matches(ls_matches);
+    let sort = ls_matches
+        .get_one::<String>("sort")
+        .map(|sort| SortKey::from_name(sort).unwrap());
+    let reverse = ls_matches.get_flag("reverse");
+    let top = top_file_objects(ls_matches);
+    // a top-N listing goes through all objects, unless limited explicitly
+    let max_files = match ls_matches.value_source("max_files") {
+        Some(ValueSource::DefaultValue) if top.is_some() => None,
+        _ => Some(max_files),
+    };
+
+    let format = OutputFormat::from_name(
+        ls_matches.get_one::<String>("output").unwrap(),
+    )
+    .unwrap();
+    // without a bucket, the buckets are listed instead
+    let bucket = ParsedUri::from_uri(&uri, true).bucket;
+    let printer = Arc::new(ObjectPrinter::new(
+        format,
+        size_units,
+        bucket.as_deref().unwrap_or_default(),
+    ));
+
     let handler = ObjectStoreHandler::new(None);
 
-    let callback =
-        Some(CallbackWrapper::create_async(print_callback_items_async));
+    let callback = match (&top, sort) {
+        (Some(top), _) => {
+            let top = Arc::clone(top);
+            Some(CallbackWrapper::create_sync(
+                move |file_objects: &[FileObject]| {
+                    top.lock().unwrap().extend(file_objects.iter().cloned());
+                },
+            ))
+        }
+        // sorting needs the complete listing
+        (None, Some(_)) => None,
+        (None, None) => {
+            let printer = Arc::clone(&printer);
+            Some(CallbackWrapper::create_sync(
+                move |file_objects: &[FileObject]| printer.print(file_objects),
+            ))
+        }
+    };
 
     match handler
-        .list_objects(
-            &uri,
-            config,
-            recursive,
-            Some(max_files),
-            &filter,
-            callback,
-        )
+        .list_objects(&uri, config, recursive, max_files, &filter, callback)
         .await
     {
+        Ok(Some(ListObjectsResult::FileObjects(mut file_objects))) => {
+            if let Some(sort) = sort {
+                sort.sort(&mut file_objects, reverse);
+            }
+            printer.print(&file_objects);
+            printer.end();
+        }
         Ok(Some(list_objects_result)) => {
-            handle_list_objects_result(list_objects_result).await;
+            handle_list_objects_result(list_objects_result, size_units).await;
         }
         Ok(None) => {
-            println!("Done");
+            if let Some(top) = top {
+                // the callback, and its reference, is dropped by now
+                let top = Arc::try_unwrap(top)
+                    .ok()
+                    .expect("listing callback still referenced");
+                printer.print(&top.into_inner().unwrap().into_sorted_vec());
+            }
+            printer.end();
         }
         Err(LakestreamError::NoBucketInUri(_)) => {
-            handle_list_buckets(&uri, config).await;
+            handle_list_buckets(&uri, config, format, size_units).await;
         }
         Err(err) => {
             eprintln!("Error: {:?}", err);
@@ -39,7 +131,10 @@ pub async fn handle_ls(ls_matches: &clap::ArgMatches, config: &mut Config) {
     }
 }
 
-async fn handle_list_objects_result(list_objects_result: ListObjectsResult) {
+async fn handle_list_objects_result(
+    list_objects_result: ListObjectsResult,
+    size_units: SizeUnits,
+) {
     match list_objects_result {
         ListObjectsResult::Buckets(buckets) => {
             // Print buckets to stdout
@@ -52,22 +147,35 @@ async fn handle_list_objects_result(list_objects_result: ListObjectsResult) {
             // Print file objects to stdout
             info!("Found {} file objects:", file_objects.len());
             for fo in file_objects {
-                println!("{}", fo.println_path());
+                println!("{}", fo.println_path_with_units(size_units));
             }
         }
     }
 }
 
-async fn handle_list_buckets(uri: &str, config: &Config) {
+async fn handle_list_buckets(
+    uri: &str,
+    config: &Config,
+    format: OutputFormat,
+    size_units: SizeUnits,
+) {
     log::info!("Calling list_buckets");
     let handler = ObjectStoreHandler::new(None);
-    let callback =
-        Some(CallbackWrapper::create_async(print_callback_items_async));
+    let printer = Arc::new(ObjectPrinter::buckets(format, size_units));
+    let callback = {
+        let printer = Arc::clone(&printer);
+        Some(CallbackWrapper::create_sync(
+            move |object_stores: &[ObjectStore]| {
+                printer.print_buckets(object_stores)
+            },
+        ))
+    };
     match handler.list_buckets(uri, config, callback).await {
         Ok(Some(list_objects_result)) => {
-            handle_list_objects_result(list_objects_result).await;
+            handle_list_objects_result(list_objects_result, size_units).await;
         }
         Ok(None) => {
+            printer.end();
             log::info!("Done");
         }
         Err(err) => {
@@ -76,39 +184,25 @@ async fn handle_list_buckets(uri: &str, config: &Config) {
     }
 }
 
+// --largest or --newest
+fn top_file_objects(
+    ls_matches: &clap::ArgMatches,
+) -> Option<Arc<Mutex<TopFileObjects>>> {
+    let (key, limit) = if let Some(limit) = ls_matches.get_one("largest") {
+        (SortKey::Size, *limit)
+    } else {
+        (SortKey::Mtime, *ls_matches.get_one("newest")?)
+    };
+    Some(Arc::new(Mutex::new(TopFileObjects::new(key, limit))))
+}
+
 fn prepare_handle_ls_arguments(
     ls_matches: &clap::ArgMatches,
 ) -> (String, bool, u32, Option<FileObjectFilter>) {
     let recursive = *ls_matches.get_one::<bool>("recursive").unwrap_or(&false);
     let uri = ls_matches.get_one::<String>("uri").unwrap().to_string();
 
-    let filter_name = ls_matches
-        .get_one::<String>("name")
-        .map(ToString::to_string);
-    let filter_size = ls_matches
-        .get_one::<String>("size")
-        .map(ToString::to_string);
-    let filter_mtime = ls_matches
-        .get_one::<String>("mtime")
-        .map(ToString::to_string);
-
-    let filter = match (&filter_name, &filter_size, &filter_mtime) {
-        (None, None, None) => None,
-        _ => {
-            let filter_result = FileObjectFilter::new(
-                filter_name.as_deref(),
-                filter_size.as_deref(),
-                filter_mtime.as_deref(),
-            );
-            match filter_result {
-                Ok(filter) => Some(filter),
-                Err(err) => {
-                    eprintln!("Error: {}", err);
-                    std::process::exit(1);
-                }
-            }
-        }
-    };
+    let filter = filter_from_matches(ls_matches);
 
     let max_files = ls_matches
         .get_one::<String>("max_files")
@@ -118,10 +212,3 @@ fn prepare_handle_ls_arguments(
 
     (uri, recursive, max_files, filter)
 }
-
-async fn print_callback_items_async<T: CallbackItem>(items: Vec<T>) {
-    info!("Found {} items:", items.len());
-    for item in &items {
-        println!("{}", item.println_path());
-    }
-}
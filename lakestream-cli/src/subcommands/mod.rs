@@ -1,6 +1,14 @@
 pub mod cp;
 mod cp_handler;
+pub mod du;
+mod du_handler;
+mod filter;
+pub mod grep;
+mod grep_handler;
 pub mod ls;
 mod ls_handler;
+mod output;
 pub mod request;
 mod request_handler;
+pub mod tree;
+mod tree_handler;
@@ -0,0 +1,250 @@
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use lakestream::{FileObject, ObjectStore, SizeUnits};
+use serde::Serialize;
+
+/// How listings are printed.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OutputFormat {
+    /// the aligned columns of `println_path`
+    Text,
+    /// a single JSON array
+    Json,
+    /// a JSON object per line
+    Ndjson,
+    Csv,
+}
+
+impl OutputFormat {
+    pub fn from_name(name: &str) -> Option<OutputFormat> {
+        match name {
+            "text" => Some(OutputFormat::Text),
+            "json" => Some(OutputFormat::Json),
+            "ndjson" => Some(OutputFormat::Ndjson),
+            "csv" => Some(OutputFormat::Csv),
+            _ => None,
+        }
+    }
+}
+
+const CSV_HEADER: &str = "bucket,key,size,mtime,mtime_rfc3339,etag,tags";
+
+// a file object as printed by the machine readable formats
+#[derive(Serialize)]
+struct Record<'a> {
+    bucket: &'a str,
+    key: &'a str,
+    size: u64,
+    /// seconds since the epoch
+    mtime: Option<u64>,
+    mtime_rfc3339: Option<String>,
+    etag: Option<&'a str>,
+    // sorted, so output is stable
+    tags: Option<BTreeMap<&'a str, &'a str>>,
+}
+
+impl<'a> Record<'a> {
+    fn new(bucket: &'a str, file_object: &'a FileObject) -> Record<'a> {
+        let tags = file_object.tags().as_ref();
+        Record {
+            bucket,
+            key: file_object.name(),
+            size: file_object.size(),
+            mtime: file_object.modified(),
+            mtime_rfc3339: file_object.modified_rfc3339(),
+            etag: tags.and_then(|tags| tags.get("ETag")).map(String::as_str),
+            tags: tags.map(|tags| {
+                tags.iter()
+                    .map(|(key, value)| (key.as_str(), value.as_str()))
+                    .collect()
+            }),
+        }
+    }
+
+    fn to_csv(&self) -> String {
+        let tags = self
+            .tags
+            .as_ref()
+            .map(|tags| serde_json::to_string(tags).unwrap())
+            .unwrap_or_default();
+        [
+            csv_field(self.bucket),
+            csv_field(self.key),
+            self.size.to_string(),
+            self.mtime
+                .map(|mtime| mtime.to_string())
+                .unwrap_or_default(),
+            self.mtime_rfc3339.clone().unwrap_or_default(),
+            csv_field(self.etag.unwrap_or_default()),
+            csv_field(&tags),
+        ]
+        .join(",")
+    }
+}
+
+// quoted if it contains a separator, quote or line break (RFC 4180)
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+// the header and field of a bucket listing in CSV
+const CSV_BUCKETS_HEADER: &str = "bucket";
+
+// a bucket as printed by the machine readable formats
+#[derive(Serialize)]
+struct BucketRecord<'a> {
+    bucket: &'a str,
+}
+
+// what a listing is of
+enum Listing {
+    Objects { bucket: String },
+    Buckets,
+}
+
+/// Prints file objects, or buckets, as they are listed, batch by batch.
+/// What comes before, such as a CSV header or the opening bracket of a
+/// JSON array, is printed with the first batch, so nothing is printed when
+/// the listing fails before. `end` prints what comes after.
+pub struct ObjectPrinter {
+    format: OutputFormat,
+    // of the sizes in the text format
+    size_units: SizeUnits,
+    listing: Listing,
+    begun: AtomicBool,
+    // whether a JSON array element was printed, for the separators
+    printed: AtomicBool,
+}
+
+impl ObjectPrinter {
+    pub fn new(
+        format: OutputFormat,
+        size_units: SizeUnits,
+        bucket: &str,
+    ) -> ObjectPrinter {
+        ObjectPrinter::with_listing(
+            format,
+            size_units,
+            Listing::Objects {
+                bucket: bucket.to_string(),
+            },
+        )
+    }
+
+    pub fn buckets(
+        format: OutputFormat,
+        size_units: SizeUnits,
+    ) -> ObjectPrinter {
+        ObjectPrinter::with_listing(format, size_units, Listing::Buckets)
+    }
+
+    fn with_listing(
+        format: OutputFormat,
+        size_units: SizeUnits,
+        listing: Listing,
+    ) -> ObjectPrinter {
+        ObjectPrinter {
+            format,
+            size_units,
+            listing,
+            begun: AtomicBool::new(false),
+            printed: AtomicBool::new(false),
+        }
+    }
+
+    fn begin(&self) {
+        if self.begun.swap(true, Ordering::Relaxed) {
+            return;
+        }
+        match (self.format, &self.listing) {
+            (OutputFormat::Json, _) => print!("["),
+            (OutputFormat::Csv, Listing::Objects { .. }) => {
+                println!("{}", CSV_HEADER)
+            }
+            (OutputFormat::Csv, Listing::Buckets) => {
+                println!("{}", CSV_BUCKETS_HEADER)
+            }
+            (OutputFormat::Text | OutputFormat::Ndjson, _) => {}
+        }
+    }
+
+    pub fn print(&self, file_objects: &[FileObject]) {
+        let Listing::Objects { bucket } = &self.listing else {
+            return;
+        };
+        self.print_entries(file_objects.iter().map(|file_object| {
+            let record = || Record::new(bucket, file_object);
+            match self.format {
+                OutputFormat::Text => {
+                    file_object.println_path_with_units(self.size_units)
+                }
+                OutputFormat::Csv => record().to_csv(),
+                OutputFormat::Json | OutputFormat::Ndjson => {
+                    serde_json::to_string(&record()).unwrap()
+                }
+            }
+        }));
+    }
+
+    pub fn print_buckets(&self, object_stores: &[ObjectStore]) {
+        self.print_entries(object_stores.iter().map(|object_store| {
+            let bucket = object_store.name();
+            match self.format {
+                OutputFormat::Text => {
+                    object_store.println_path_with_units(self.size_units)
+                }
+                OutputFormat::Csv => csv_field(bucket),
+                OutputFormat::Json | OutputFormat::Ndjson => {
+                    serde_json::to_string(&BucketRecord { bucket }).unwrap()
+                }
+            }
+        }));
+    }
+
+    // entries formatted as a line, or JSON array element
+    fn print_entries(&self, entries: impl Iterator<Item = String>) {
+        self.begin();
+        let mut stdout = io::stdout().lock();
+        for entry in entries {
+            let result = if self.format == OutputFormat::Json {
+                let separator = if self.printed.swap(true, Ordering::Relaxed) {
+                    ","
+                } else {
+                    ""
+                };
+                write!(stdout, "{}\n  {}", separator, entry)
+            } else {
+                writeln!(stdout, "{}", entry)
+            };
+            if let Err(err) = result {
+                // e.g. piped into head, which has seen enough
+                if err.kind() == io::ErrorKind::BrokenPipe {
+                    std::process::exit(0);
+                }
+                eprintln!("Error writing to stdout: {:?}", err);
+                return;
+            }
+        }
+        // NDJSON consumers read per line, so do not hold back a batch
+        let _ = stdout.flush();
+    }
+
+    /// Ends a listing that succeeded, which may have had no objects.
+    pub fn end(&self) {
+        self.begin();
+        match (self.format, &self.listing) {
+            (OutputFormat::Json, _) if self.printed.load(Ordering::Relaxed) => {
+                println!("\n]")
+            }
+            (OutputFormat::Json, _) => println!("]"),
+            (OutputFormat::Text, Listing::Objects { .. }) => println!("Done"),
+            _ => {}
+        }
+    }
+}
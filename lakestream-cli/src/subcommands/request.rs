@@ -9,9 +9,9 @@ pub fn request_subcommand() -> Command {
         .arg(
             Arg::new("method")
                 .index(1)
-                .value_parser(["GET"])
+                .value_parser(["GET", "PUT"])
                 .required(true)
-                .help("HTTP verb for the request (GET)"),
+                .help("HTTP verb for the request (GET, PUT reads from stdin)"),
         )
         .arg(
             Arg::new("uri")
@@ -19,4 +19,28 @@ pub fn request_subcommand() -> Command {
                 .required(true)
                 .help("File for the HTTP request"),
         )
+        .arg(
+            Arg::new("decompress")
+                .long("decompress")
+                .num_args(0..=1)
+                .require_equals(true)
+                .default_missing_value("auto")
+                .value_name("CODEC")
+                .help(
+                    "Decompress on GET (gzip, zstd, bzip2 or auto-detect \
+                     from Content-Encoding, extension or contents)",
+                ),
+        )
+        .arg(
+            Arg::new("compress")
+                .long("compress")
+                .num_args(0..=1)
+                .require_equals(true)
+                .default_missing_value("auto")
+                .value_name("CODEC")
+                .help(
+                    "Compress on PUT (gzip, zstd, bzip2 or auto-detect from \
+                     extension)",
+                ),
+        )
 }
@@ -1,8 +1,7 @@
 use std::fs::File;
-use std::io::{self, Write};
-use std::sync::{Arc, Mutex};
+use std::io::{self, Read, Write};
 
-use lakestream::{BinaryCallbackWrapper, Config, ObjectStoreHandler};
+use lakestream::{Codec, Config, LakestreamError, ObjectStoreHandler};
 
 pub async fn handle_request(matches: &clap::ArgMatches, config: &mut Config) {
     let method = matches.get_one::<String>("method").unwrap();
@@ -13,12 +12,28 @@ pub async fn handle_request(matches: &clap::ArgMatches, config: &mut Config) {
     // -o output_file as a main cli option applicable to all commands
     let output_file = None;
 
+    // None: no (de)compression, Some(None): detect the codec
+    let decompress = match codec_option(matches, "decompress") {
+        Ok(decompress) => decompress,
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            return;
+        }
+    };
+    let compress = match codec_option(matches, "compress") {
+        Ok(compress) => compress,
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            return;
+        }
+    };
+
     match method.as_str() {
         "GET" => {
-            handle_get_request(uri, config, output_file).await;
+            handle_get_request(uri, config, output_file, decompress).await;
         }
         "PUT" => {
-            println!("PUT request");
+            handle_put_request(uri, config, compress).await;
         }
         "DELETE" => {
             println!("DELETE request");
@@ -39,29 +54,68 @@ async fn handle_get_request(
     uri: &str,
     config: &Config,
     output_path: Option<&str>,
+    decompress: Option<Option<Codec>>,
 ) {
     let handler = ObjectStoreHandler::new(None);
 
-    let callback = if let Some(output_path) = output_path {
-        let file = Arc::new(Mutex::new(File::create(output_path).unwrap()));
-        Some(BinaryCallbackWrapper::create_async(move |data: Vec<u8>| {
-            let mut file = file.lock().unwrap();
-            if let Err(e) = file.write_all(&data) {
-                eprintln!("Error writing to file: {:?}", e);
+    let mut writer: Box<dyn Write> = if let Some(output_path) = output_path {
+        match File::create(output_path) {
+            Ok(file) => Box::new(file),
+            Err(err) => {
+                eprintln!("Error creating {}: {:?}", output_path, err);
+                return;
             }
-            async {}
-        }))
+        }
     } else {
-        Some(BinaryCallbackWrapper::create_async(move |data: Vec<u8>| {
-            let mut stdout = io::stdout();
-            if let Err(e) = stdout.write_all(&data) {
-                eprintln!("Error writing to stdout: {:?}", e);
-            }
-            async {}
-        }))
+        Box::new(io::stdout().lock())
     };
 
-    if let Err(err) = handler.get_object(uri, config, callback).await {
+    let result = if let Some(codec) = decompress {
+        // decompressed as it arrives
+        handler
+            .get_object_decompressed_to_writer(uri, config, codec, &mut writer)
+            .await
+    } else {
+        // written as it arrives
+        handler.get_object_to_writer(uri, config, &mut writer).await
+    };
+    if let Err(err) = result.and_then(|()| Ok(writer.flush()?)) {
         eprintln!("Error: {:?}", err);
     }
 }
+
+async fn handle_put_request(
+    uri: &str,
+    config: &Config,
+    compress: Option<Option<Codec>>,
+) {
+    let handler = ObjectStoreHandler::new(None);
+
+    let mut data = Vec::new();
+    if let Err(e) = io::stdin().read_to_end(&mut data) {
+        eprintln!("Error reading from stdin: {:?}", e);
+        return;
+    }
+
+    let result = if let Some(codec) = compress {
+        handler
+            .put_object_compressed(uri, config, codec, &data)
+            .await
+    } else {
+        handler.put_object(uri, config, &data).await
+    };
+    if let Err(err) = result {
+        eprintln!("Error: {:?}", err);
+    }
+}
+
+fn codec_option(
+    matches: &clap::ArgMatches,
+    name: &str,
+) -> Result<Option<Option<Codec>>, LakestreamError> {
+    match matches.get_one::<String>(name).map(String::as_str) {
+        None => Ok(None),
+        Some("auto") => Ok(Some(None)),
+        Some(codec) => Ok(Some(Some(Codec::from_name(codec)?))),
+    }
+}
@@ -0,0 +1,47 @@
+use clap::{Arg, ArgAction, Command};
+
+use super::filter::with_filter_args;
+pub use super::tree_handler::handle_tree;
+
+pub fn tree_subcommand() -> Command {
+    let command = Command::new("tree")
+        .about(
+            "Print the prefixes (virtual directories) under a URI as a tree, \
+             e.g. to get to know the layout of a bucket",
+        )
+        .arg(Arg::new("uri").index(1).required(true).help(
+            "Bucket or prefix to print the tree of. E.g. s3://bucket-name/",
+        ))
+        .arg(
+            Arg::new("depth")
+                .long("depth")
+                .short('d')
+                .value_parser(clap::value_parser!(usize))
+                .help(
+                    "Print prefixes down to this many levels below the URI \
+                     [default: all levels]",
+                ),
+        )
+        .arg(
+            Arg::new("sizes")
+                .long("sizes")
+                .action(ArgAction::SetTrue)
+                .help("Print the size of the objects in and below each prefix"),
+        )
+        .arg(
+            Arg::new("counts")
+                .long("counts")
+                .action(ArgAction::SetTrue)
+                .help("Print the number of objects in and below each prefix"),
+        )
+        .arg(
+            Arg::new("size_units")
+                .long("size-units")
+                .value_parser(["iec", "si"])
+                .help(
+                    "Print sizes in powers of 1024 (KiB, MiB, ...) or of 1000 \
+                     (kB, MB, ...) [default: iec]",
+                ),
+        );
+    with_filter_args(command)
+}
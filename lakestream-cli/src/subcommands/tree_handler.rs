@@ -0,0 +1,77 @@
+use std::collections::HashSet;
+
+use lakestream::{format_bytes, Config, Totals};
+
+use super::du_handler::{list_prefix_totals, size_units_from_matches};
+
+pub async fn handle_tree(tree_matches: &clap::ArgMatches, config: &mut Config) {
+    let uri = tree_matches.get_one::<String>("uri").unwrap();
+    let sizes = tree_matches.get_flag("sizes");
+    let counts = tree_matches.get_flag("counts");
+    let size_units = size_units_from_matches(tree_matches);
+    let Some(totals) = list_prefix_totals(tree_matches, config).await else {
+        return;
+    };
+
+    // in lexicographic order, the prefixes below a prefix follow it
+    // directly, so this is the order of the tree from top to bottom
+    let entries: Vec<(&str, &Totals)> = totals.iter().collect();
+    let last_children = last_children(&entries);
+
+    let label = |name: &str, totals: &Totals| {
+        let mut details = Vec::new();
+        if sizes {
+            details.push(format_bytes(totals.size, size_units));
+        }
+        if counts {
+            details.push(match totals.objects {
+                1 => "1 object".to_string(),
+                objects => format!("{} objects", objects),
+            });
+        }
+        if details.is_empty() {
+            name.to_string()
+        } else {
+            format!("{} ({})", name, details.join(", "))
+        }
+    };
+
+    println!("{}", label(uri, entries[0].1));
+    // per level above the current prefix, whether it was a last child
+    let mut levels: Vec<bool> = Vec::new();
+    for (index, (prefix, prefix_totals)) in entries.iter().enumerate().skip(1) {
+        let parent = parent_prefix(prefix);
+        levels.truncate(parent.matches('/').count());
+        let indent: String = levels
+            .iter()
+            .map(|&last| if last { "    " } else { "|   " })
+            .collect();
+        let is_last = last_children.contains(&index);
+        println!(
+            "{}{}{}",
+            indent,
+            if is_last { "`-- " } else { "|-- " },
+            label(&prefix[parent.len()..], prefix_totals)
+        );
+        levels.push(is_last);
+    }
+}
+
+// "a/b/" -> "a/", "a/" -> ""
+fn parent_prefix(prefix: &str) -> &str {
+    let prefix = prefix.strip_suffix('/').unwrap_or(prefix);
+    prefix.rfind('/').map_or("", |index| &prefix[..=index])
+}
+
+// indexes of the entries that have no sibling after them
+fn last_children(entries: &[(&str, &Totals)]) -> HashSet<usize> {
+    let mut parents = HashSet::new();
+    entries
+        .iter()
+        .enumerate()
+        .skip(1)
+        .rev()
+        .filter(|(_, (prefix, _))| parents.insert(parent_prefix(prefix)))
+        .map(|(index, _)| index)
+        .collect()
+}
@@ -17,10 +17,17 @@ class Client:
         :type recursive: bool, optional
         :param max_files: The maximum number of files to list. Default is None.
         :type max_files: int, optional
-        :param filter_dict: A dictionary containing filters for name, size, and mtime.
+        :param filter_dict: A dictionary containing filters for name, size, mtime,
+                            and on Local Filesystem owner, group and perm.
+                            The name is a regex, or a glob with "name_mode": "glob".
+                            "where" takes an expression that combines these with
+                            and, or and not, e.g. "size > 10M and not mtime < 7D".
+                            "exclude" and "include" take glob patterns, e.g.
+                            ["_temporary/", ".git/", "_SUCCESS"], to skip or only list
+                            matching objects.
                             The dictionary values can be a string, a list of strings, or None.
-                            Note that only the first value in the list will be used at this moment.
-                            Multiple strings may be supported in the future.
+                            Except for "exclude" and "include", only the first value in
+                            the list is used.
         :type filter_dict: dict, optional
         :return: A list of objects or buckets.
         :rtype: list
@@ -70,12 +77,14 @@ class Client:
         return self._client.list_buckets(uri)
 
 
-    def get_object(self, uri):
+    def get_object(self, uri, decompress=False):
         """
         Get the content of the specified object.
 
         :param uri: The URI of the object in the storage.
         :type uri: str
+        :param decompress: If True, decompress gzip, zstd or bzip2 compressed objects. Default is False.
+        :type decompress: bool, optional
         :return: The content of the object as a string.
         :rtype: str
 
@@ -91,5 +100,8 @@ class Client:
             result = client.get_object("s3://your-bucket/object-key")
 
             print(result)
+
+            # Get the decompressed content of a gzip compressed object
+            result = client.get_object("s3://your-bucket/logs/x.json.gz", decompress=True)
         """
-        return self._client.get_object(uri)
+        return self._client.get_object(uri, decompress)
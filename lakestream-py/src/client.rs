@@ -144,13 +144,18 @@ impl _Client {
         }
     }
 
-    fn get_object(&self, py: Python, uri: String) -> PyResult<PyObject> {
+    #[pyo3(signature = (uri, decompress=false))]
+    fn get_object(&self, py: Python, uri: String, decompress: bool) -> PyResult<PyObject> {
         // Create a new Tokio runtime
         let rt = Runtime::new().unwrap();
 
         // Call the async function and block on it to get the result
         let handler = ObjectStoreHandler::new(None);
-        let result = rt.block_on(handler.get_object(&uri, &self.config, None));
+        let result = if decompress {
+            rt.block_on(handler.get_object_decompressed(&uri, &self.config, None, None))
+        } else {
+            rt.block_on(handler.get_object(&uri, &self.config, None))
+        };
 
         match result {
             Ok(Some(data)) => Ok(PyBytes::new(py, &data).to_object(py)),
@@ -2,7 +2,7 @@
 use pyo3::prelude::*;
 use pyo3::types::{PyDict, PyAny};
 use pyo3::exceptions;
-use ::lakestream::FileObjectFilter;
+use ::lakestream::{FileObjectFilter, NameMode};
 
 
 pub fn create_filter(py: Python, filter_dict: Option<&PyDict>) -> PyResult<Option<FileObjectFilter>> {
@@ -11,12 +11,45 @@ pub fn create_filter(py: Python, filter_dict: Option<&PyDict>) -> PyResult<Optio
         let filter_name = extract_first_value(py, filter_dict.get_item("name"));
         let filter_size = extract_first_value(py, filter_dict.get_item("size"));
         let filter_mtime = extract_first_value(py, filter_dict.get_item("mtime"));
+        let filter_owner = extract_first_value(py, filter_dict.get_item("owner"));
+        let filter_group = extract_first_value(py, filter_dict.get_item("group"));
+        let filter_perm = extract_first_value(py, filter_dict.get_item("perm"));
+        let name_mode = extract_first_value(py, filter_dict.get_item("name_mode"));
+        let filter_where = extract_first_value(py, filter_dict.get_item("where"));
+        let excludes = extract_values(py, filter_dict.get_item("exclude"));
+        let includes = extract_values(py, filter_dict.get_item("include"));
 
-        FileObjectFilter::new(
-            filter_name.as_deref(),
+        let mut filter = FileObjectFilter::new(
+            None,
             filter_size.as_deref(),
             filter_mtime.as_deref(),
-        )
+        )?;
+        if let Some(name) = filter_name {
+            let name_mode = match name_mode {
+                Some(name_mode) => NameMode::from_name(&name_mode)?,
+                None => NameMode::Regex,
+            };
+            filter = filter.with_name(&name, name_mode)?;
+        }
+        if let Some(owner) = filter_owner {
+            filter = filter.with_owner(&owner);
+        }
+        if let Some(group) = filter_group {
+            filter = filter.with_group(&group);
+        }
+        if let Some(perm) = filter_perm {
+            filter = filter.with_mode(&perm)?;
+        }
+        if let Some(expression) = filter_where {
+            filter = filter.with_expression(&expression)?;
+        }
+        for pattern in excludes {
+            filter = filter.with_exclude(&pattern)?;
+        }
+        for pattern in includes {
+            filter = filter.with_include(&pattern)?;
+        }
+        Ok::<_, String>(filter)
     });
 
     let filter = match filter {
@@ -46,3 +79,16 @@ fn extract_first_value(_py: Python, value: Option<&PyAny>) -> Option<String> {
     }
 }
 
+fn extract_values(_py: Python, value: Option<&PyAny>) -> Vec<String> {
+    match value {
+        Some(value) => {
+            if let Ok(s) = value.extract::<String>() {
+                vec![s]
+            } else {
+                value.extract::<Vec<String>>().unwrap_or_default()
+            }
+        }
+        None => Vec::new(),
+    }
+}
+
@@ -1,21 +1,66 @@
+use std::future::Future;
+use std::io::Write;
+use std::task::Poll;
+
 use async_trait::async_trait;
-use log::info;
+use futures::channel::mpsc;
+use futures::future;
+use futures::stream::{self, Stream, StreamExt};
+use futures::SinkExt;
+use log::{error, info};
+use regex::bytes::Regex;
 
-use crate::base::object_store::object_stores_from_config;
+use crate::base::grep::{LineMatch, ObjectGrep};
+use crate::base::object_store::object_stores_from_config_with_registry;
+use crate::base::object_store_registry::{
+    ObjectStoreFactory, ObjectStoreRegistry,
+};
+use crate::compression::decoder::DetectingDecoder;
 use crate::utils::uri_parse::ParsedUri;
 use crate::{
-    BinaryCallbackWrapper, CallbackWrapper, Config, FileObject,
+    BinaryCallbackWrapper, CallbackWrapper, Codec, Config, FileObject,
     FileObjectFilter, LakestreamError, ListObjectsResult, ObjectStore,
     ObjectStoreVec,
 };
 
 #[derive(Clone)]
-pub struct ObjectStoreHandler {}
+pub struct ObjectStoreHandler {
+    registry: ObjectStoreRegistry,
+}
 
 impl ObjectStoreHandler {
     pub fn new(_configs: Option<Vec<Config>>) -> Self {
         // creating with config will be used in future
-        ObjectStoreHandler {}
+        ObjectStoreHandler {
+            registry: ObjectStoreRegistry::new(),
+        }
+    }
+
+    pub fn registry(&self) -> &ObjectStoreRegistry {
+        &self.registry
+    }
+
+    /// Register a factory for a URI scheme, replacing any existing factory
+    /// (including built-in ones) for that scheme.
+    pub fn register<F>(&mut self, scheme: &str, factory: F)
+    where
+        F: ObjectStoreFactory + 'static,
+    {
+        self.registry.register(scheme, factory);
+    }
+
+    /// Register an `ObjectStoreBackend` for a URI scheme, with a function
+    /// that creates the object store for a bucket name.
+    pub fn register_backend<B, F>(&mut self, scheme: &str, bucket_factory: F)
+    where
+        B: ObjectStoreBackend + 'static,
+        F: Fn(&str, Config) -> Result<ObjectStore, LakestreamError>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.registry
+            .register_backend::<B, F>(scheme, bucket_factory);
     }
 
     pub async fn list_objects(
@@ -46,6 +91,39 @@ impl ObjectStoreHandler {
         }
     }
 
+    /// Like `list_objects` with a callback, but the file objects come as a
+    /// stream. The listing is driven by the stream, and at most one object is
+    /// buffered ahead of the consumer, so the next batch (e.g. the next page
+    /// of an S3 listing) is only requested once the consumer has caught up
+    /// with the previous one.
+    /// Dropping the stream stops the listing. An error ends the stream,
+    /// after the objects listed before it.
+    pub fn list_objects_stream(
+        &self,
+        uri: &str,
+        config: &Config,
+        recursive: bool,
+        max_files: Option<u32>,
+        filter: &Option<FileObjectFilter>,
+    ) -> impl Stream<Item = Result<FileObject, LakestreamError>> {
+        let handler = self.clone();
+        let (uri, config, filter) =
+            (uri.to_string(), config.clone(), filter.clone());
+        listing_stream(move |callback| async move {
+            handler
+                .list_objects(
+                    &uri,
+                    &config,
+                    recursive,
+                    max_files,
+                    &filter,
+                    Some(callback),
+                )
+                .await
+                .map(|_| ())
+        })
+    }
+
     pub async fn list_buckets(
         &self,
         uri: &str,
@@ -66,8 +144,12 @@ impl ObjectStoreHandler {
                 format!("{}://", parsed_uri.scheme.unwrap()),
             );
 
-            let object_stores =
-                object_stores_from_config(updated_config, &callback).await?;
+            let object_stores = object_stores_from_config_with_registry(
+                &self.registry,
+                updated_config,
+                &callback,
+            )
+            .await?;
 
             if callback.is_some() {
                 // callback used, so can just return None
@@ -85,39 +167,236 @@ impl ObjectStoreHandler {
         config: &Config,
         callback: Option<BinaryCallbackWrapper>,
     ) -> Result<Option<Vec<u8>>, LakestreamError> {
+        let (object_store, key) = self.object_store_for_key(uri, config)?;
+
+        let mut data = Vec::new();
+        object_store.get_object(&key, &mut data).await?;
+        Self::return_object(data, callback).await
+    }
+
+    /// Get an object and write it to `writer` as it arrives, so it does not
+    /// have to fit in memory.
+    pub async fn get_object_to_writer(
+        &self,
+        uri: &str,
+        config: &Config,
+        writer: &mut dyn Write,
+    ) -> Result<(), LakestreamError> {
+        let (object_store, key) = self.object_store_for_key(uri, config)?;
+        object_store.get_object_to_writer(&key, writer).await
+    }
+
+    /// Get an object and decompress it with `codec`. Without a codec, it is
+    /// detected from the key extension or the first bytes of the object,
+    /// and objects that are not compressed are returned as is.
+    pub async fn get_object_decompressed(
+        &self,
+        uri: &str,
+        config: &Config,
+        codec: Option<Codec>,
+        callback: Option<BinaryCallbackWrapper>,
+    ) -> Result<Option<Vec<u8>>, LakestreamError> {
+        let mut data = Vec::new();
+        self.get_object_decompressed_to_writer(uri, config, codec, &mut data)
+            .await?;
+        Self::return_object(data, callback).await
+    }
+
+    /// Like `get_object_decompressed`, but the object is decompressed as it
+    /// arrives and written to `writer`, so it does not have to fit in
+    /// memory.
+    pub async fn get_object_decompressed_to_writer(
+        &self,
+        uri: &str,
+        config: &Config,
+        codec: Option<Codec>,
+        writer: &mut dyn Write,
+    ) -> Result<(), LakestreamError> {
+        let (object_store, key) = self.object_store_for_key(uri, config)?;
+
+        let codec = codec.or_else(|| Codec::from_extension(&key));
+        if let Some(codec) = codec {
+            info!("Decompressing {} with {}", key, codec.name());
+        }
+        let mut decoder = DetectingDecoder::new(codec, writer);
+        object_store
+            .get_object_to_writer(&key, &mut decoder)
+            .await?;
+        decoder.finish()?.flush()?;
+        Ok(())
+    }
+
+    /// Search the objects under a URI, recursively, for lines that match a
+    /// regex. The filter is applied to the listing first, so only the
+    /// remaining objects are fetched, `concurrency` at a time, while the
+    /// listing goes on. Objects are matched line by line as they arrive,
+    /// and compressed objects (gzip, zstd or bzip2) are decompressed on the
+    /// way. Objects that cannot be read are logged and skipped.
+    pub async fn grep_objects(
+        &self,
+        uri: &str,
+        config: &Config,
+        pattern: &str,
+        filter: &Option<FileObjectFilter>,
+        concurrency: usize,
+        callback: Option<CallbackWrapper<LineMatch>>,
+    ) -> Result<Option<Vec<LineMatch>>, LakestreamError> {
+        let pattern = Regex::new(pattern).map_err(|err| {
+            LakestreamError::ConfigError(format!(
+                "Invalid pattern '{}': {}",
+                pattern, err
+            ))
+        })?;
         let parsed_uri = ParsedUri::from_uri(uri, false);
+        if parsed_uri.bucket.is_none() {
+            return Err(LakestreamError::NoBucketInUri(uri.to_string()));
+        }
+        let object_store = self.object_store_for_bucket(&parsed_uri, config)?;
+        let object_store = &object_store;
+        let pattern = &pattern;
+        let file_objects = listing_stream(|callback| {
+            object_store.list_files_with_callback(
+                parsed_uri.path.as_deref(),
+                true,
+                None,
+                filter,
+                callback,
+            )
+        });
+        // objects are searched concurrently, but reported in listing order.
+        // An error of the listing ends the search, objects that cannot be
+        // read are skipped.
+        let mut results = file_objects
+            .filter(|result| {
+                future::ready(!matches!(
+                    result,
+                    Ok(file_object) if file_object.name().ends_with('/')
+                ))
+            })
+            .map(|result| async move {
+                let file_object = result?;
+                let key = file_object.name();
+                let mut grep = ObjectGrep::new(key, pattern);
+                let matches = object_store
+                    .get_object_to_writer(key, &mut grep)
+                    .await
+                    .and_then(|()| Ok(grep.finish()?));
+                Ok::<_, LakestreamError>(matches)
+            })
+            .buffered(concurrency.max(1));
 
-        if let Some(bucket) = &parsed_uri.bucket {
-            // Get the object from the bucket
+        let mut line_matches = Vec::new();
+        while let Some(result) = results.next().await {
+            let matches = match result? {
+                Ok(matches) => matches,
+                Err(err) => {
+                    error!("Skipping object: {}", err);
+                    continue;
+                }
+            };
+            match &callback {
+                Some(CallbackWrapper::Sync(callback)) => callback(&matches),
+                Some(CallbackWrapper::Async(callback)) => {
+                    callback(matches).await
+                }
+                None => line_matches.extend(matches),
+            }
+        }
+        Ok(callback.is_none().then_some(line_matches))
+    }
+
+    pub async fn put_object(
+        &self,
+        uri: &str,
+        config: &Config,
+        data: &[u8],
+    ) -> Result<(), LakestreamError> {
+        let (object_store, key) = self.object_store_for_key(uri, config)?;
+        object_store.put_object(&key, data).await
+    }
+
+    /// Compress an object with `codec`, or with the codec that matches the
+    /// key extension (.gz, .zst or .bz2), and put it.
+    pub async fn put_object_compressed(
+        &self,
+        uri: &str,
+        config: &Config,
+        codec: Option<Codec>,
+        data: &[u8],
+    ) -> Result<(), LakestreamError> {
+        let (object_store, key) = self.object_store_for_key(uri, config)?;
+
+        let codec =
+            codec
+                .or_else(|| Codec::from_extension(&key))
+                .ok_or_else(|| {
+                    LakestreamError::ConfigError(format!(
+                        "No compression for {}, use a .gz, .zst or .bz2 \
+                     extension or set it explicitly",
+                        key
+                    ))
+                })?;
+        info!("Compressing {} with {}", key, codec.name());
+        object_store.put_object(&key, &codec.compress(data)?).await
+    }
+
+    // object store and key of an object URI
+    fn object_store_for_key(
+        &self,
+        uri: &str,
+        config: &Config,
+    ) -> Result<(ObjectStore, String), LakestreamError> {
+        let parsed_uri = ParsedUri::from_uri(uri, false);
+
+        if let (Some(bucket), Some(key)) =
+            (&parsed_uri.bucket, &parsed_uri.path)
+        {
             let bucket_uri = if let Some(scheme) = &parsed_uri.scheme {
                 format!("{}://{}", scheme, bucket)
             } else {
                 format!("localfs://{}", bucket)
             };
 
-            let key = parsed_uri.path.as_deref().unwrap();
-            let object_store =
-                ObjectStore::new(&bucket_uri, config.clone()).unwrap();
-
-            // NOTE: initial callback implementation for get_object. In future updates the callback
-            // mechanism will be pushed to the underlying object store methods, so we can add
-            // chunking as well for increased performance and ability to handle big files that not
-            // fit in memory
-            if let Some(callback) = callback {
-                let mut data = Vec::new();
-                object_store.get_object(key, &mut data).await?;
-                callback.call(data);
-                Ok(None)
-            } else {
-                let mut data = Vec::new();
-                object_store.get_object(key, &mut data).await?;
-                Ok(Some(data))
-            }
+            let object_store = self
+                .registry
+                .create_object_store(&bucket_uri, config.clone())?;
+            Ok((object_store, key.clone()))
         } else {
             Err(LakestreamError::NoBucketInUri(uri.to_string()))
         }
     }
 
+    // NOTE: initial callback implementation for get_object. In future updates the callback
+    // mechanism will be pushed to the underlying object store methods, so we can add
+    // chunking as well for increased performance and ability to handle big files that not
+    // fit in memory
+    async fn return_object(
+        data: Vec<u8>,
+        callback: Option<BinaryCallbackWrapper>,
+    ) -> Result<Option<Vec<u8>>, LakestreamError> {
+        if let Some(callback) = callback {
+            callback.call(data).await;
+            Ok(None)
+        } else {
+            Ok(Some(data))
+        }
+    }
+
+    // object store of the bucket in a URI that has one
+    fn object_store_for_bucket(
+        &self,
+        parsed_uri: &ParsedUri,
+        config: &Config,
+    ) -> Result<ObjectStore, LakestreamError> {
+        let bucket_uri = if let Some(scheme) = &parsed_uri.scheme {
+            format!("{}://{}", scheme, parsed_uri.bucket.as_ref().unwrap())
+        } else {
+            format!("localfs://{}", parsed_uri.bucket.as_ref().unwrap())
+        };
+        self.registry
+            .create_object_store(&bucket_uri, config.clone())
+    }
+
     async fn list_files_in_bucket(
         &self,
         parsed_uri: ParsedUri,
@@ -127,12 +406,8 @@ impl ObjectStoreHandler {
         filter: &Option<FileObjectFilter>,
         callback: Option<CallbackWrapper<FileObject>>,
     ) -> Result<Option<ListObjectsResult>, LakestreamError> {
-        let bucket_uri = if let Some(scheme) = &parsed_uri.scheme {
-            format!("{}://{}", scheme, parsed_uri.bucket.as_ref().unwrap())
-        } else {
-            format!("localfs://{}", parsed_uri.bucket.as_ref().unwrap())
-        };
-        let object_store = ObjectStore::new(&bucket_uri, config).unwrap();
+        let object_store =
+            self.object_store_for_bucket(&parsed_uri, &config)?;
 
         if let Some(callback) = callback {
             object_store
@@ -170,3 +445,52 @@ pub trait ObjectStoreBackend {
         object_stores: &mut ObjectStoreVec,
     ) -> Result<(), LakestreamError>;
 }
+
+// file objects that a listing passes to its callback, as a stream that
+// drives the listing. At most one object is buffered ahead of the consumer,
+// so the listing pauses while the stream is not polled, and stops when the
+// stream is dropped. An error of the listing ends the stream, after the
+// objects listed before it.
+fn listing_stream<'a, F, Fut>(
+    list: F,
+) -> impl Stream<Item = Result<FileObject, LakestreamError>> + 'a
+where
+    F: FnOnce(CallbackWrapper<FileObject>) -> Fut,
+    Fut: Future<Output = Result<(), LakestreamError>> + 'a,
+{
+    // without a buffer, the sender's one guaranteed slot holds at most one
+    // object ahead of the consumer
+    let (sender, mut receiver) = mpsc::channel::<FileObject>(0);
+    let callback = CallbackWrapper::create_async(move |file_objects| {
+        let mut sender = sender.clone();
+        async move {
+            for file_object in file_objects {
+                // the stream is dropped
+                if sender.send(file_object).await.is_err() {
+                    return;
+                }
+            }
+        }
+    });
+    let mut listing = Some(Box::pin(list(callback)));
+    let mut error = None;
+
+    stream::poll_fn(move |cx| {
+        if let Some(future) = listing.as_mut() {
+            if let Poll::Ready(result) = future.as_mut().poll(cx) {
+                // drops the callback, and its senders, too
+                listing = None;
+                error = result.err();
+            }
+        }
+        match receiver.poll_next_unpin(cx) {
+            Poll::Ready(Some(file_object)) => {
+                Poll::Ready(Some(Ok(file_object)))
+            }
+            // every send completes on receipt, so once the listing is
+            // done, all objects are taken
+            _ if listing.is_none() => Poll::Ready(error.take().map(Err)),
+            _ => Poll::Pending,
+        }
+    })
+}
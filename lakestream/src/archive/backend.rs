@@ -0,0 +1,26 @@
+use async_trait::async_trait;
+
+pub use super::bucket::ArchiveBucket;
+pub use super::reader::split_archive_path;
+use crate::{Config, LakestreamError, ObjectStoreBackend, ObjectStoreVec};
+
+pub struct ArchiveBackend;
+
+#[async_trait(?Send)]
+impl ObjectStoreBackend for ArchiveBackend {
+    fn new(_config: Config) -> Result<Self, LakestreamError> {
+        Ok(Self)
+    }
+
+    async fn list_buckets(
+        _config: Config,
+        _object_stores: &mut ObjectStoreVec,
+    ) -> Result<(), LakestreamError> {
+        // each archive file is a bucket, there is nothing to enumerate
+        Err(LakestreamError::ConfigError(
+            "Listing buckets is not supported for archives, use a URI \
+             such as archive://path/data.zip/"
+                .to_string(),
+        ))
+    }
+}
@@ -0,0 +1,133 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use futures::lock::Mutex;
+
+use super::get::get_object;
+use super::list::list_files;
+use super::reader::{
+    archive_error, fetch_archive, ArchiveFile, ArchiveFormat, OpenArchive,
+};
+use crate::base::config::Config;
+use crate::base::object_store_registry::ObjectStoreRegistry;
+use crate::utils::blocking::spawn_blocking;
+use crate::utils::uri_parse::ParsedUri;
+use crate::{
+    FileObjectFilter, FileObjectVec, LakestreamError, ObjectStore,
+    ObjectStoreTrait,
+};
+
+#[derive(Clone)]
+pub struct ArchiveBucket {
+    // path of a local archive, e.g. "data/delivery.zip", or URI of a remote
+    // archive, e.g. "s3://bucket-name/delivery.tar.gz"
+    name: String,
+    format: ArchiveFormat,
+    config: Config,
+    // object store and key of a remote archive
+    remote: Option<(Box<ObjectStore>, String)>,
+    // opened on first use, and shared with clones
+    archive: Arc<Mutex<Option<Arc<OpenArchive>>>>,
+}
+
+impl ArchiveBucket {
+    /// The object store of a remote archive is created via `registry`.
+    pub fn new(
+        name: &str,
+        config: Config,
+        registry: &ObjectStoreRegistry,
+    ) -> Result<ArchiveBucket, LakestreamError> {
+        let format = ArchiveFormat::from_name(name).ok_or_else(|| {
+            LakestreamError::ConfigError(format!(
+                "Unsupported archive format: '{}', expected a .zip, .tar, \
+                 .tar.gz or .tgz file",
+                name
+            ))
+        })?;
+        let remote = if name.contains("://") {
+            Some(remote_archive(name, &config, registry)?)
+        } else {
+            None
+        };
+        Ok(ArchiveBucket {
+            name: name.to_string(),
+            format,
+            config,
+            remote,
+            archive: Arc::new(Mutex::new(None)),
+        })
+    }
+
+    /// Opens the archive and reads its index, once. Remote archives are
+    /// fetched first.
+    pub async fn open(&self) -> Result<Arc<OpenArchive>, LakestreamError> {
+        let mut archive = self.archive.lock().await;
+        if let Some(archive) = archive.as_ref() {
+            return Ok(Arc::clone(archive));
+        }
+        let file = match &self.remote {
+            Some((object_store, key)) => {
+                fetch_archive(object_store, key).await?
+            }
+            None => ArchiveFile::Path(PathBuf::from(&self.name)),
+        };
+        let format = self.format;
+        let opened = spawn_blocking(move || OpenArchive::open(format, file))
+            .await
+            .map_err(archive_error)?;
+        Ok(Arc::clone(archive.insert(Arc::new(opened))))
+    }
+}
+
+fn remote_archive(
+    name: &str,
+    config: &Config,
+    registry: &ObjectStoreRegistry,
+) -> Result<(Box<ObjectStore>, String), LakestreamError> {
+    let parsed_uri = ParsedUri::from_uri(name, false);
+    let (Some(scheme), Some(bucket), Some(key)) =
+        (parsed_uri.scheme, parsed_uri.bucket, parsed_uri.path)
+    else {
+        return Err(LakestreamError::ConfigError(format!(
+            "Invalid archive URI: {}",
+            name
+        )));
+    };
+    let object_store = registry.create_object_store(
+        &format!("{}://{}", scheme, bucket),
+        config.clone(),
+    )?;
+    Ok((Box::new(object_store), key))
+}
+
+#[async_trait(?Send)]
+impl ObjectStoreTrait for ArchiveBucket {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn config(&self) -> &Config {
+        &self.config
+    }
+
+    async fn list_files(
+        &self,
+        prefix: Option<&str>,
+        recursive: bool,
+        max_keys: Option<u32>,
+        filter: &Option<FileObjectFilter>,
+        file_objects: &mut FileObjectVec,
+    ) -> Result<(), LakestreamError> {
+        list_files(self, prefix, recursive, max_keys, filter, file_objects)
+            .await
+    }
+
+    async fn get_object(
+        &self,
+        key: &str,
+        data: &mut Vec<u8>,
+    ) -> Result<(), LakestreamError> {
+        get_object(self, key, data).await
+    }
+}
@@ -0,0 +1,25 @@
+use log::info;
+
+use super::bucket::ArchiveBucket;
+use super::reader::archive_error;
+use crate::utils::blocking::spawn_blocking;
+use crate::{LakestreamError, ObjectStoreTrait};
+
+pub async fn get_object(
+    archive_bucket: &ArchiveBucket,
+    object_key: &str,
+    data: &mut Vec<u8>,
+) -> Result<(), LakestreamError> {
+    info!("Extracting {} from {}", object_key, archive_bucket.name());
+    let archive = archive_bucket.open().await?;
+
+    let key = object_key.to_string();
+    *data = spawn_blocking(move || {
+        let mut data = Vec::new();
+        archive.extract(&key, &mut data)?;
+        Ok(data)
+    })
+    .await
+    .map_err(archive_error)?;
+    Ok(())
+}
@@ -0,0 +1,37 @@
+use super::bucket::ArchiveBucket;
+use crate::base::sorted_listing;
+use crate::{FileObject, FileObjectFilter, FileObjectVec, LakestreamError};
+
+pub async fn list_files(
+    archive_bucket: &ArchiveBucket,
+    prefix: Option<&str>,
+    recursive: bool,
+    max_keys: Option<u32>,
+    filter: &Option<FileObjectFilter>,
+    file_objects: &mut FileObjectVec,
+) -> Result<(), LakestreamError> {
+    let archive = archive_bucket.open().await?;
+
+    sorted_listing::list_files(
+        prefix,
+        recursive,
+        max_keys,
+        filter,
+        file_objects,
+        |prefix| {
+            Ok(sorted_listing::list_prefix(
+                &archive.entries,
+                prefix,
+                |name, entry| {
+                    FileObject::new(
+                        name.clone(),
+                        entry.size,
+                        entry.modified,
+                        None,
+                    )
+                },
+            ))
+        },
+    )
+    .await
+}
@@ -0,0 +1,5 @@
+pub mod backend;
+mod bucket;
+mod get;
+mod list;
+mod reader;
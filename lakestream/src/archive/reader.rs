@@ -0,0 +1,317 @@
+use std::collections::BTreeMap;
+use std::fs::File;
+#[cfg(not(target_arch = "wasm32"))]
+use std::io::BufWriter;
+#[cfg(target_arch = "wasm32")]
+use std::io::Cursor;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::PathBuf;
+#[cfg(target_arch = "wasm32")]
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use flate2::read::GzDecoder;
+use zip::result::ZipError;
+use zip::ZipArchive;
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::utils::blocking::spawn_blocking;
+use crate::utils::time::days_from_civil;
+use crate::{LakestreamError, ObjectStore};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ArchiveFormat {
+    Zip,
+    Tar,
+    TarGz,
+}
+
+const ARCHIVE_EXTENSIONS: [(&str, ArchiveFormat); 4] = [
+    (".zip", ArchiveFormat::Zip),
+    (".tar", ArchiveFormat::Tar),
+    (".tar.gz", ArchiveFormat::TarGz),
+    (".tgz", ArchiveFormat::TarGz),
+];
+
+impl ArchiveFormat {
+    pub fn from_name(name: &str) -> Option<ArchiveFormat> {
+        let name = name.to_lowercase();
+        ARCHIVE_EXTENSIONS
+            .iter()
+            .find(|(extension, _)| {
+                name.len() > extension.len() && name.ends_with(extension)
+            })
+            .map(|(_, format)| *format)
+    }
+}
+
+// Split a path such as "path/data.zip/inner/prefix" in the archive
+// ("path/data.zip") and the path within the archive ("inner/prefix").
+// The first path component with an archive extension is the archive.
+pub fn split_archive_path(path: &str) -> Option<(&str, &str)> {
+    path.match_indices('/')
+        .map(|(index, _)| index)
+        .chain(std::iter::once(path.len()))
+        .find(|index| ArchiveFormat::from_name(&path[..*index]).is_some())
+        .map(|index| (&path[..index], path[index..].trim_start_matches('/')))
+}
+
+pub struct ArchiveEntry {
+    pub size: u64,
+    pub modified: Option<u64>,
+    // index of a zip or compressed tar member, or offset of the data of a
+    // tar member
+    position: u64,
+}
+
+/// Where an archive is read from.
+pub enum ArchiveFile {
+    Path(PathBuf),
+    /// a remote archive, fetched once
+    #[cfg(not(target_arch = "wasm32"))]
+    Temp(tempfile::NamedTempFile),
+    #[cfg(target_arch = "wasm32")]
+    Memory(Arc<[u8]>),
+}
+
+impl ArchiveFile {
+    // a reader of its own, so readers do not share a position
+    fn reader(&self) -> io::Result<ArchiveReader> {
+        match self {
+            ArchiveFile::Path(path) => File::open(path)
+                .map(ArchiveReader::File)
+                .map_err(|err| match err.kind() {
+                    io::ErrorKind::NotFound => io::Error::new(
+                        err.kind(),
+                        format!("Archive not found: {}", path.display()),
+                    ),
+                    _ => err,
+                }),
+            #[cfg(not(target_arch = "wasm32"))]
+            ArchiveFile::Temp(file) => file.reopen().map(ArchiveReader::File),
+            #[cfg(target_arch = "wasm32")]
+            ArchiveFile::Memory(data) => {
+                Ok(ArchiveReader::Memory(Cursor::new(Arc::clone(data))))
+            }
+        }
+    }
+}
+
+pub enum ArchiveReader {
+    File(File),
+    #[cfg(target_arch = "wasm32")]
+    Memory(Cursor<Arc<[u8]>>),
+}
+
+impl Read for ArchiveReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            ArchiveReader::File(file) => file.read(buf),
+            #[cfg(target_arch = "wasm32")]
+            ArchiveReader::Memory(cursor) => cursor.read(buf),
+        }
+    }
+}
+
+impl Seek for ArchiveReader {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        match self {
+            ArchiveReader::File(file) => file.seek(pos),
+            #[cfg(target_arch = "wasm32")]
+            ArchiveReader::Memory(cursor) => cursor.seek(pos),
+        }
+    }
+}
+
+// Fetches a remote archive, e.g. "s3://bucket-name/data.zip", from its
+// object store. Object stores have no range reads, so the archive is
+// fetched as a whole, into a temporary file rather than memory.
+#[cfg(not(target_arch = "wasm32"))]
+pub async fn fetch_archive(
+    object_store: &ObjectStore,
+    key: &str,
+) -> Result<ArchiveFile, LakestreamError> {
+    let temp_file = spawn_blocking(tempfile::NamedTempFile::new).await?;
+    let mut writer = BufWriter::with_capacity(1 << 20, temp_file);
+    object_store.get_object_to_writer(key, &mut writer).await?;
+    let temp_file = writer.into_inner().map_err(|err| err.into_error())?;
+    Ok(ArchiveFile::Temp(temp_file))
+}
+
+// no file system on wasm32
+#[cfg(target_arch = "wasm32")]
+pub async fn fetch_archive(
+    object_store: &ObjectStore,
+    key: &str,
+) -> Result<ArchiveFile, LakestreamError> {
+    let mut data = Vec::new();
+    object_store.get_object(key, &mut data).await?;
+    Ok(ArchiveFile::Memory(data.into()))
+}
+
+// how members are found
+enum Members {
+    // via the central directory, parsed once
+    Zip(Mutex<ZipArchive<ArchiveReader>>),
+    Tar { compressed: bool },
+}
+
+/// An archive with the index of its regular files, by name. Directories
+/// are implied by the names of the files. Reading is blocking I/O.
+pub struct OpenArchive {
+    file: ArchiveFile,
+    members: Members,
+    pub entries: BTreeMap<String, ArchiveEntry>,
+}
+
+impl OpenArchive {
+    pub fn open(
+        format: ArchiveFormat,
+        file: ArchiveFile,
+    ) -> io::Result<OpenArchive> {
+        let reader = file.reader()?;
+        let mut entries = BTreeMap::new();
+
+        let members = match format {
+            ArchiveFormat::Zip => {
+                let mut archive = ZipArchive::new(reader).map_err(zip_error)?;
+                for index in 0..archive.len() {
+                    let file = archive.by_index(index).map_err(zip_error)?;
+                    if !file.is_file() {
+                        continue;
+                    }
+                    let modified = file.last_modified();
+                    entries.insert(
+                        entry_name(file.name()),
+                        ArchiveEntry {
+                            size: file.size(),
+                            // zip stores local time without timezone, read
+                            // as UTC
+                            modified: days_from_civil(
+                                modified.year() as i64,
+                                modified.month() as u32,
+                                modified.day() as u32,
+                            )
+                            .map(|days| {
+                                days * 86400
+                                    + modified.hour() as u64 * 3600
+                                    + modified.minute() as u64 * 60
+                                    + modified.second() as u64
+                            }),
+                            position: index as u64,
+                        },
+                    );
+                }
+                Members::Zip(Mutex::new(archive))
+            }
+            ArchiveFormat::Tar | ArchiveFormat::TarGz => {
+                let compressed = format == ArchiveFormat::TarGz;
+                let mut archive = tar_archive(compressed, reader);
+                for (index, entry) in archive.entries()?.enumerate() {
+                    let entry = entry?;
+                    let header = entry.header();
+                    if !header.entry_type().is_file() {
+                        continue;
+                    }
+                    entries.insert(
+                        entry_name(&String::from_utf8_lossy(
+                            &entry.path_bytes(),
+                        )),
+                        ArchiveEntry {
+                            size: header.size()?,
+                            modified: header.mtime().ok(),
+                            position: if compressed {
+                                index as u64
+                            } else {
+                                entry.raw_file_position()
+                            },
+                        },
+                    );
+                }
+                Members::Tar { compressed }
+            }
+        };
+        Ok(OpenArchive {
+            file,
+            members,
+            entries,
+        })
+    }
+
+    pub fn extract(&self, key: &str, data: &mut Vec<u8>) -> io::Result<()> {
+        let entry = self.entries.get(key).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("Object not found for key: {}", key),
+            )
+        })?;
+        match &self.members {
+            Members::Zip(archive) => {
+                let mut archive = archive.lock().unwrap();
+                let mut file = archive
+                    .by_index(entry.position as usize)
+                    .map_err(zip_error)?;
+                file.read_to_end(data)?;
+            }
+            // the data of a member is stored as is
+            Members::Tar { compressed: false } => {
+                let mut reader = self.file.reader()?;
+                reader.seek(SeekFrom::Start(entry.position))?;
+                reader.take(entry.size).read_to_end(data)?;
+            }
+            // a compressed archive can only be read up to the member
+            Members::Tar { compressed: true } => {
+                let mut archive = tar_archive(true, self.file.reader()?);
+                let mut member = archive
+                    .entries()?
+                    .nth(entry.position as usize)
+                    .ok_or_else(|| {
+                        io::Error::new(
+                            io::ErrorKind::UnexpectedEof,
+                            "Archive changed while reading",
+                        )
+                    })??;
+                member.read_to_end(data)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Errors of reading an archive, where a missing archive or member is
+/// NotFound.
+pub fn archive_error(err: io::Error) -> LakestreamError {
+    match err.kind() {
+        io::ErrorKind::NotFound => LakestreamError::NotFound(err.to_string()),
+        _ => LakestreamError::Io(err),
+    }
+}
+
+fn tar_archive(
+    compressed: bool,
+    reader: ArchiveReader,
+) -> tar::Archive<Box<dyn Read>> {
+    let reader: Box<dyn Read> = if compressed {
+        Box::new(GzDecoder::new(reader))
+    } else {
+        Box::new(reader)
+    };
+    tar::Archive::new(reader)
+}
+
+// archive members may be stored as "./name" or "/name"
+fn entry_name(name: &str) -> String {
+    name.trim_start_matches("./")
+        .trim_start_matches('/')
+        .to_string()
+}
+
+fn zip_error(err: ZipError) -> io::Error {
+    match err {
+        ZipError::Io(err) => err,
+        err => io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("Failed to read zip archive: {}", err),
+        ),
+    }
+}
@@ -0,0 +1,28 @@
+use async_trait::async_trait;
+use log::error;
+
+pub use super::bucket::AzureBucket;
+pub use super::config::validate_config;
+pub use super::list::list_buckets;
+use crate::{Config, LakestreamError, ObjectStoreBackend, ObjectStoreVec};
+
+pub struct AzureBackend;
+
+#[async_trait(?Send)]
+impl ObjectStoreBackend for AzureBackend {
+    fn new(_config: Config) -> Result<Self, LakestreamError> {
+        Ok(Self)
+    }
+
+    async fn list_buckets(
+        config: Config,
+        object_stores: &mut ObjectStoreVec,
+    ) -> Result<(), LakestreamError> {
+        let mut config_instance = config.clone();
+        if let Err(e) = validate_config(&mut config_instance) {
+            error!("Error validating the config: {}", e);
+            return Err(e);
+        }
+        list_buckets(&config_instance, object_stores).await
+    }
+}
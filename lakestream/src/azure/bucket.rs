@@ -0,0 +1,104 @@
+use async_trait::async_trait;
+
+use super::get::{
+    get_object, get_object_if_none_match, get_object_with_content_encoding,
+};
+use super::list::list_files;
+use super::put::put_object;
+use crate::azure::config::validate_config;
+use crate::base::config::Config;
+use crate::{
+    ConditionalGet, FileObjectFilter, FileObjectVec, LakestreamError,
+    ObjectStoreTrait,
+};
+
+#[derive(Clone)]
+pub struct AzureBucket {
+    name: String,
+    config: Config,
+}
+
+impl AzureBucket {
+    pub fn new(
+        name: &str,
+        mut config: Config,
+    ) -> Result<AzureBucket, LakestreamError> {
+        // abfs style names carry the account, e.g.:
+        // container@account.dfs.core.windows.net
+        let name = match name.split_once('@') {
+            Some((container, host)) => {
+                if !config.contains_key("AZURE_STORAGE_ACCOUNT") {
+                    let account = host.split('.').next().unwrap_or(host);
+                    config.insert(
+                        "AZURE_STORAGE_ACCOUNT".to_string(),
+                        account.to_string(),
+                    );
+                }
+                container
+            }
+            None => name,
+        };
+        validate_config(&mut config)?;
+
+        Ok(AzureBucket {
+            name: name.to_string(),
+            config,
+        })
+    }
+}
+
+#[async_trait(?Send)]
+impl ObjectStoreTrait for AzureBucket {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn config(&self) -> &Config {
+        &self.config
+    }
+
+    async fn list_files(
+        &self,
+        prefix: Option<&str>,
+        recursive: bool,
+        max_keys: Option<u32>,
+        filter: &Option<FileObjectFilter>,
+        file_objects: &mut FileObjectVec,
+    ) -> Result<(), LakestreamError> {
+        list_files(self, prefix, recursive, max_keys, filter, file_objects)
+            .await
+    }
+
+    async fn get_object(
+        &self,
+        key: &str,
+        data: &mut Vec<u8>,
+    ) -> Result<(), LakestreamError> {
+        get_object(self, key, data).await
+    }
+
+    async fn put_object(
+        &self,
+        key: &str,
+        data: &[u8],
+    ) -> Result<(), LakestreamError> {
+        put_object(self, key, data).await
+    }
+
+    async fn get_object_if_none_match(
+        &self,
+        key: &str,
+        etag: Option<&str>,
+        data: &mut Vec<u8>,
+    ) -> Result<ConditionalGet, LakestreamError> {
+        get_object_if_none_match(self, key, etag, data).await
+    }
+
+    async fn get_object_with_content_encoding(
+        &self,
+        key: &str,
+        data: &mut Vec<u8>,
+    ) -> Result<Option<String>, LakestreamError> {
+        get_object_with_content_encoding(self, key, data).await
+    }
+}
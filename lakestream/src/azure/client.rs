@@ -0,0 +1,217 @@
+use std::collections::HashMap;
+
+use bytes::Bytes;
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use url::Url;
+
+use super::request_builder::RequestBuilder;
+use crate::http::requests::{http_get_request_with_headers, http_put_request};
+use crate::{Config, LakestreamError};
+
+// encode everything except unreserved characters, keep '/' as separator
+const PATH_ENCODE_SET: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~')
+    .remove(b'/');
+
+#[derive(Clone)]
+pub struct AzureClientConfig {
+    account_name: String,
+    account_key: Option<String>,
+    sas_token: Option<String>,
+    endpoint_url: Option<String>,
+}
+
+impl AzureClientConfig {
+    pub fn new(
+        account_name: &str,
+        account_key: Option<&str>,
+        sas_token: Option<&str>,
+        endpoint_url: Option<&str>,
+    ) -> AzureClientConfig {
+        AzureClientConfig {
+            account_name: account_name.to_string(),
+            account_key: account_key.map(str::to_string),
+            sas_token: sas_token
+                .map(|token| token.trim_start_matches('?').to_string()),
+            endpoint_url: endpoint_url.map(str::to_string),
+        }
+    }
+
+    pub fn account_name(&self) -> &str {
+        &self.account_name
+    }
+
+    pub fn account_key(&self) -> Option<&str> {
+        self.account_key.as_deref()
+    }
+
+    pub fn sas_token(&self) -> Option<&str> {
+        self.sas_token.as_deref()
+    }
+
+    pub fn blob_endpoint(&self) -> String {
+        match &self.endpoint_url {
+            Some(url) => url.trim_end_matches('/').to_string(),
+            None => {
+                format!("https://{}.blob.core.windows.net", self.account_name)
+            }
+        }
+    }
+}
+
+pub struct AzureClient {
+    config: AzureClientConfig,
+    request_builder: RequestBuilder,
+}
+
+impl AzureClient {
+    pub fn new(config: AzureClientConfig) -> AzureClient {
+        log::info!(
+            "AzureClient created with endpoint_url: {}",
+            config.blob_endpoint()
+        );
+        AzureClient {
+            config,
+            request_builder: RequestBuilder::new(),
+        }
+    }
+
+    pub fn url(
+        &self,
+        container: Option<&str>,
+        blob: Option<&str>,
+        query: &[(&str, &str)],
+    ) -> Result<Url, LakestreamError> {
+        let mut path = String::new();
+        if let Some(container) = container {
+            path.push('/');
+            path.push_str(
+                &utf8_percent_encode(container, PATH_ENCODE_SET).to_string(),
+            );
+        }
+        if let Some(blob) = blob {
+            path.push('/');
+            path.push_str(
+                &utf8_percent_encode(
+                    blob.trim_start_matches('/'),
+                    PATH_ENCODE_SET,
+                )
+                .to_string(),
+            );
+        }
+        if path.is_empty() {
+            path.push('/');
+        }
+
+        let mut url =
+            Url::parse(&format!("{}{}", self.config.blob_endpoint(), path))?;
+        {
+            let mut query_pairs = url.query_pairs_mut();
+            for (key, value) in query {
+                query_pairs.append_pair(key, value);
+            }
+        }
+
+        // SAS is only used when there is no account key to sign with
+        if self.config.account_key().is_none() {
+            if let Some(token) = self.config.sas_token() {
+                let query = match url.query() {
+                    Some(q) if !q.is_empty() => format!("{}&{}", q, token),
+                    _ => token.to_string(),
+                };
+                url.set_query(Some(&query));
+            }
+        }
+        if url.query() == Some("") {
+            url.set_query(None);
+        }
+        Ok(url)
+    }
+
+    pub async fn get(&self, url: &Url) -> Result<Bytes, LakestreamError> {
+        let (body_bytes, _, _) = self.get_with_headers(url, &[]).await?;
+        Ok(body_bytes)
+    }
+
+    // GET with extra request headers, returns the body, status and response
+    // headers. A 304 (Not Modified) is returned as is, with an empty body.
+    pub async fn get_with_headers(
+        &self,
+        url: &Url,
+        extra_headers: &[(&str, &str)],
+    ) -> Result<(Bytes, u16, HashMap<String, String>), LakestreamError> {
+        let headers = self.request_builder.generate_headers(
+            &self.config,
+            "GET",
+            url,
+            extra_headers,
+        )?;
+
+        let (body_bytes, status, response_headers) =
+            http_get_request_with_headers(url.as_str(), &headers)
+                .await
+                .map_err(LakestreamError::from)?;
+
+        match status {
+            200..=299 | 304 => Ok((body_bytes, status, response_headers)),
+            404 => Err(LakestreamError::NotFound(url.path().to_string())),
+            _ => Err(LakestreamError::InternalError(format!(
+                "Azure request to {} failed with status {}",
+                url.path(),
+                status
+            ))),
+        }
+    }
+
+    pub async fn put(
+        &self,
+        url: &Url,
+        data: &[u8],
+        extra_headers: &[(&str, &str)],
+    ) -> Result<(), LakestreamError> {
+        let content_length = data.len().to_string();
+        let mut extra_headers = extra_headers.to_vec();
+        extra_headers.push(("Content-Length", &content_length));
+        let headers = self.request_builder.generate_headers(
+            &self.config,
+            "PUT",
+            url,
+            &extra_headers,
+        )?;
+
+        let (status, _) =
+            http_put_request(url.as_str(), &headers, data.to_vec())
+                .await
+                .map_err(LakestreamError::from)?;
+
+        match status {
+            200..=299 => Ok(()),
+            404 => Err(LakestreamError::NotFound(url.path().to_string())),
+            _ => Err(LakestreamError::InternalError(format!(
+                "Azure request to {} failed with status {}",
+                url.path(),
+                status
+            ))),
+        }
+    }
+}
+
+pub fn create_azure_client(config: &Config) -> AzureClient {
+    let account_name = config
+        .get("AZURE_STORAGE_ACCOUNT")
+        .expect("Missing account name in the configuration");
+    let account_key = config.get("AZURE_STORAGE_KEY").map(String::as_str);
+    let sas_token = config.get("AZURE_STORAGE_SAS_TOKEN").map(String::as_str);
+    let endpoint_url =
+        config.get("AZURE_STORAGE_ENDPOINT_URL").map(String::as_str);
+
+    AzureClient::new(AzureClientConfig::new(
+        account_name,
+        account_key,
+        sas_token,
+        endpoint_url,
+    ))
+}
@@ -0,0 +1,115 @@
+use std::collections::HashMap;
+use std::env;
+
+use crate::{Config, LakestreamError};
+
+// Well-known development account of the Azurite emulator
+const AZURITE_ACCOUNT_NAME: &str = "devstoreaccount1";
+const AZURITE_ACCOUNT_KEY: &str = "Eby8vdM02xNOcqFlqUwJPLlmEtlCDXJ1OUzFT50uSRZ6\
+                                   IFsuFq2UVErCz4I6tq/K1SZFPTOtr/KBHBeksoGMGw==";
+const AZURITE_BLOB_ENDPOINT: &str = "http://127.0.0.1:10000/devstoreaccount1";
+
+const ENV_KEYS: &[&str] = &[
+    "AZURE_STORAGE_ACCOUNT",
+    "AZURE_STORAGE_KEY",
+    "AZURE_STORAGE_SAS_TOKEN",
+    "AZURE_STORAGE_ENDPOINT_URL",
+];
+
+pub fn validate_config(config: &mut Config) -> Result<(), LakestreamError> {
+    // Explicit settings take precedence over the environment
+    for key in ENV_KEYS {
+        if !config.contains_key(key) {
+            if let Ok(value) = env::var(key) {
+                config.insert(key.to_string(), value);
+            }
+        }
+    }
+
+    // A connection string only fills in what is not set explicitly
+    let connection_string = config
+        .get("AZURE_STORAGE_CONNECTION_STRING")
+        .cloned()
+        .or_else(|| env::var("AZURE_STORAGE_CONNECTION_STRING").ok());
+    if let Some(connection_string) = connection_string {
+        apply_connection_string(config, &connection_string)?;
+    }
+
+    if !config.contains_key("AZURE_STORAGE_ACCOUNT") {
+        return Err(LakestreamError::ConfigError(
+            "AZURE_STORAGE_ACCOUNT not found in the config and environment"
+                .to_string(),
+        ));
+    }
+
+    if !config.contains_key("AZURE_STORAGE_KEY")
+        && !config.contains_key("AZURE_STORAGE_SAS_TOKEN")
+    {
+        return Err(LakestreamError::ConfigError(
+            "AZURE_STORAGE_KEY or AZURE_STORAGE_SAS_TOKEN not found in the \
+             config and environment"
+                .to_string(),
+        ));
+    }
+    Ok(())
+}
+
+fn apply_connection_string(
+    config: &mut Config,
+    connection_string: &str,
+) -> Result<(), LakestreamError> {
+    let mut parts = HashMap::new();
+    for part in connection_string.split(';').map(str::trim) {
+        if part.is_empty() {
+            continue;
+        }
+        let (key, value) = part.split_once('=').ok_or_else(|| {
+            LakestreamError::ConfigError(format!(
+                "Invalid connection string element: {}",
+                part
+            ))
+        })?;
+        parts.insert(key.trim().to_string(), value.trim().to_string());
+    }
+
+    if parts.get("UseDevelopmentStorage").map(String::as_str) == Some("true") {
+        parts
+            .entry("AccountName".to_string())
+            .or_insert_with(|| AZURITE_ACCOUNT_NAME.to_string());
+        parts
+            .entry("AccountKey".to_string())
+            .or_insert_with(|| AZURITE_ACCOUNT_KEY.to_string());
+        parts
+            .entry("BlobEndpoint".to_string())
+            .or_insert_with(|| AZURITE_BLOB_ENDPOINT.to_string());
+    }
+
+    if !parts.contains_key("BlobEndpoint") {
+        if let (Some(account), Some(suffix)) =
+            (parts.get("AccountName"), parts.get("EndpointSuffix"))
+        {
+            let protocol = parts
+                .get("DefaultEndpointsProtocol")
+                .map(String::as_str)
+                .unwrap_or("https");
+            let endpoint =
+                format!("{}://{}.blob.{}", protocol, account, suffix);
+            parts.insert("BlobEndpoint".to_string(), endpoint);
+        }
+    }
+
+    let mapping = [
+        ("AccountName", "AZURE_STORAGE_ACCOUNT"),
+        ("AccountKey", "AZURE_STORAGE_KEY"),
+        ("SharedAccessSignature", "AZURE_STORAGE_SAS_TOKEN"),
+        ("BlobEndpoint", "AZURE_STORAGE_ENDPOINT_URL"),
+    ];
+    for (part_key, config_key) in mapping {
+        if let Some(value) = parts.get(part_key) {
+            if !config.contains_key(config_key) {
+                config.insert(config_key.to_string(), value.clone());
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,74 @@
+use std::collections::HashMap;
+
+use log::info;
+
+use super::bucket::AzureBucket;
+use super::client::create_azure_client;
+use crate::{ConditionalGet, LakestreamError, ObjectStoreTrait};
+
+pub async fn get_object(
+    azure_bucket: &AzureBucket,
+    object_key: &str,
+    data: &mut Vec<u8>,
+) -> Result<(), LakestreamError> {
+    get_object_if_none_match(azure_bucket, object_key, None, data).await?;
+    Ok(())
+}
+
+pub async fn get_object_if_none_match(
+    azure_bucket: &AzureBucket,
+    object_key: &str,
+    etag: Option<&str>,
+    data: &mut Vec<u8>,
+) -> Result<ConditionalGet, LakestreamError> {
+    let headers =
+        get_object_with_headers(azure_bucket, object_key, etag, data).await?;
+    Ok(match headers {
+        Some(headers) => ConditionalGet::Modified(headers.get("etag").cloned()),
+        None => ConditionalGet::NotModified,
+    })
+}
+
+pub async fn get_object_with_content_encoding(
+    azure_bucket: &AzureBucket,
+    object_key: &str,
+    data: &mut Vec<u8>,
+) -> Result<Option<String>, LakestreamError> {
+    let headers =
+        get_object_with_headers(azure_bucket, object_key, None, data).await?;
+    Ok(headers.and_then(|headers| headers.get("content-encoding").cloned()))
+}
+
+// returns the response headers, or None if the object still matches etag
+async fn get_object_with_headers(
+    azure_bucket: &AzureBucket,
+    object_key: &str,
+    etag: Option<&str>,
+    data: &mut Vec<u8>,
+) -> Result<Option<HashMap<String, String>>, LakestreamError> {
+    let azure_client = create_azure_client(azure_bucket.config());
+    let url =
+        azure_client.url(Some(azure_bucket.name()), Some(object_key), &[])?;
+
+    info!("Getting object: {}", object_key);
+    let extra_headers: Vec<(&str, &str)> = etag
+        .map(|etag| ("If-None-Match", etag))
+        .into_iter()
+        .collect();
+    let (body_bytes, status, headers) =
+        azure_client.get_with_headers(&url, &extra_headers).await?;
+
+    if status == 304 {
+        info!("Object not modified: {}", object_key);
+        return Ok(None);
+    }
+    info!(
+        "Got object: {} of size {} bytes",
+        object_key,
+        body_bytes.len()
+    );
+
+    data.clear();
+    data.extend_from_slice(&body_bytes);
+    Ok(Some(headers))
+}
@@ -0,0 +1,171 @@
+use std::collections::VecDeque;
+
+use super::bucket::AzureBucket;
+use super::client::{create_azure_client, AzureClient};
+use super::parse_http_response::{parse_blob_objects, parse_container_objects};
+use crate::base::config::Config;
+use crate::{
+    FileObject, FileObjectFilter, FileObjectVec, LakestreamError,
+    ObjectStoreTrait, ObjectStoreVec, AZURE_MAX_LIST_OBJECTS,
+};
+
+pub async fn list_files(
+    azure_bucket: &AzureBucket,
+    prefix: Option<&str>,
+    recursive: bool,
+    max_keys: Option<u32>,
+    filter: &Option<FileObjectFilter>,
+    file_objects: &mut FileObjectVec,
+) -> Result<(), LakestreamError> {
+    let azure_client = create_azure_client(azure_bucket.config());
+    let max_keys = max_keys.map(|keys| keys as usize);
+
+    let mut directory_stack = VecDeque::new();
+    directory_stack.push_back(prefix.map(str::to_owned));
+
+    while let Some(prefix) = directory_stack.pop_front() {
+        let mut virtual_directories = Vec::<String>::new();
+        let mut marker: Option<String> = None;
+
+        loop {
+            let remaining = max_keys
+                .map(|max_keys| max_keys.saturating_sub(file_objects.len()));
+            let page_size = get_page_size(filter, remaining);
+
+            let (page, next_marker) = list_blobs_page(
+                &azure_client,
+                azure_bucket.name(),
+                prefix.as_deref(),
+                page_size,
+                marker.as_deref(),
+            )
+            .await?;
+
+            let mut temp_file_objects = Vec::new();
+            for file_object in page {
+                process_file_object(
+                    file_object,
+                    recursive,
+                    filter,
+                    &mut virtual_directories,
+                    &mut temp_file_objects,
+                );
+            }
+            if let Some(remaining) = remaining {
+                temp_file_objects.truncate(remaining);
+            }
+            file_objects.extend_async(temp_file_objects).await;
+
+            marker = next_marker;
+            if marker.is_none() || limit_reached(file_objects, max_keys) {
+                break;
+            }
+        }
+
+        if limit_reached(file_objects, max_keys) {
+            break;
+        }
+        directory_stack.extend(virtual_directories.into_iter().map(Some));
+    }
+    Ok(())
+}
+
+async fn list_blobs_page(
+    azure_client: &AzureClient,
+    container: &str,
+    prefix: Option<&str>,
+    page_size: u32,
+    marker: Option<&str>,
+) -> Result<(Vec<FileObject>, Option<String>), LakestreamError> {
+    let page_size = page_size.to_string();
+    let mut query = vec![
+        ("restype", "container"),
+        ("comp", "list"),
+        ("delimiter", "/"),
+        ("maxresults", page_size.as_str()),
+    ];
+    if let Some(prefix) = prefix {
+        query.push(("prefix", prefix));
+    }
+    if let Some(marker) = marker {
+        query.push(("marker", marker));
+    }
+
+    let url = azure_client.url(Some(container), None, &query)?;
+    let body_bytes = azure_client.get(&url).await?;
+    let body = String::from_utf8_lossy(&body_bytes);
+    parse_blob_objects(&body).map_err(LakestreamError::from)
+}
+
+fn process_file_object(
+    file_object: FileObject,
+    recursive: bool,
+    filter: &Option<FileObjectFilter>,
+    virtual_directories: &mut Vec<String>,
+    temp_file_objects: &mut Vec<FileObject>,
+) {
+    if file_object.name().ends_with('/') {
+        if recursive {
+            virtual_directories.push(file_object.name().to_owned());
+        }
+        if filter.is_none() {
+            temp_file_objects.push(file_object);
+        }
+    } else {
+        if let Some(ref filter) = filter {
+            if !filter.matches(&file_object) {
+                return;
+            }
+        }
+        temp_file_objects.push(file_object);
+    }
+}
+
+pub async fn list_buckets(
+    config: &Config,
+    object_stores: &mut ObjectStoreVec,
+) -> Result<(), LakestreamError> {
+    let azure_client = create_azure_client(config);
+    let mut marker: Option<String> = None;
+
+    loop {
+        let mut query = vec![("comp", "list")];
+        if let Some(marker) = marker.as_deref() {
+            query.push(("marker", marker));
+        }
+        let url = azure_client.url(None, None, &query)?;
+        let body_bytes = azure_client.get(&url).await?;
+        let body = String::from_utf8_lossy(&body_bytes);
+
+        let (containers, next_marker) =
+            parse_container_objects(&body, Some(config.clone()))
+                .map_err(LakestreamError::from)?;
+        object_stores.extend_async(containers).await;
+
+        marker = next_marker;
+        if marker.is_none() {
+            break;
+        }
+    }
+    Ok(())
+}
+
+fn get_page_size(
+    filter: &Option<FileObjectFilter>,
+    remaining: Option<usize>,
+) -> u32 {
+    match remaining {
+        // with a filter, the number of matches per page is unknown
+        Some(remaining) if filter.is_none() => {
+            (remaining as u32).clamp(1, AZURE_MAX_LIST_OBJECTS)
+        }
+        _ => AZURE_MAX_LIST_OBJECTS,
+    }
+}
+
+fn limit_reached(
+    file_objects: &FileObjectVec,
+    max_keys: Option<usize>,
+) -> bool {
+    max_keys.is_some_and(|max_keys| file_objects.len() >= max_keys)
+}
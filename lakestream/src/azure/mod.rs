@@ -0,0 +1,9 @@
+pub mod backend;
+mod bucket;
+mod client;
+mod config;
+mod get;
+mod list;
+mod parse_http_response;
+mod put;
+mod request_builder;
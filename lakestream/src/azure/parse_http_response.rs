@@ -0,0 +1,192 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+use super::bucket::AzureBucket;
+use crate::utils::time::http_date_to_epoch;
+use crate::{Config, FileObject, LakestreamError, ObjectStore};
+
+// allow non snake case for the XML response
+#[allow(non_snake_case)]
+#[derive(Debug, Deserialize)]
+struct EnumerationResults {
+    Containers: Option<Containers>,
+    Blobs: Option<Blobs>,
+    NextMarker: Option<String>,
+}
+
+// allow non snake case for the XML response
+#[allow(non_snake_case)]
+#[derive(Debug, Deserialize)]
+struct Containers {
+    Container: Option<Vec<Container>>,
+}
+
+// allow non snake case for the XML response
+#[allow(non_snake_case)]
+#[derive(Debug, Deserialize)]
+struct Container {
+    Name: String,
+}
+
+// Blob and BlobPrefix elements are interleaved in name order
+#[derive(Debug, Deserialize)]
+struct Blobs {
+    #[serde(rename = "$value", default)]
+    items: Vec<BlobItem>,
+}
+
+#[derive(Debug, Deserialize)]
+enum BlobItem {
+    Blob(Blob),
+    BlobPrefix(BlobPrefix),
+}
+
+// allow non snake case for the XML response
+#[allow(non_snake_case)]
+#[derive(Debug, Deserialize)]
+struct Blob {
+    Name: String,
+    Properties: BlobProperties,
+}
+
+#[derive(Debug, Deserialize)]
+struct BlobProperties {
+    #[serde(rename = "Last-Modified")]
+    last_modified: String,
+    #[serde(rename = "Content-Length")]
+    content_length: u64,
+    #[serde(rename = "Etag")]
+    etag: Option<String>,
+}
+
+// allow non snake case for the XML response
+#[allow(non_snake_case)]
+#[derive(Debug, Deserialize)]
+struct BlobPrefix {
+    Name: String,
+}
+
+fn next_marker(marker: Option<String>) -> Option<String> {
+    // the last page contains an empty NextMarker element
+    marker.filter(|marker| !marker.is_empty())
+}
+
+pub fn parse_container_objects(
+    body: &str,
+    config: Option<Config>,
+) -> Result<(Vec<ObjectStore>, Option<String>), Box<dyn std::error::Error>> {
+    let result: EnumerationResults = serde_xml_rs::from_str(body)?;
+    let object_stores = result
+        .Containers
+        .and_then(|containers| containers.Container)
+        .unwrap_or_default()
+        .iter()
+        .map(|container| {
+            let config = config.clone().unwrap_or_default();
+            AzureBucket::new(&container.Name, config)
+                .map(ObjectStore::AzureBucket)
+        })
+        .collect::<Result<Vec<ObjectStore>, LakestreamError>>()?;
+    Ok((object_stores, next_marker(result.NextMarker)))
+}
+
+pub fn parse_blob_objects(
+    body: &str,
+) -> Result<(Vec<FileObject>, Option<String>), Box<dyn std::error::Error>> {
+    let result: EnumerationResults = serde_xml_rs::from_str(body)?;
+    let mut file_objects = Vec::new();
+
+    for item in result.Blobs.map(|blobs| blobs.items).unwrap_or_default() {
+        match item {
+            BlobItem::Blob(blob) => {
+                let tags = blob.Properties.etag.map(|etag| {
+                    [("ETag".to_string(), etag.trim_matches('"').to_string())]
+                        .into_iter()
+                        .collect::<HashMap<String, String>>()
+                });
+                file_objects.push(FileObject::new(
+                    blob.Name,
+                    blob.Properties.content_length,
+                    Some(last_modified_to_epoch(
+                        &blob.Properties.last_modified,
+                    )?),
+                    tags,
+                ));
+            }
+            BlobItem::BlobPrefix(prefix) => {
+                file_objects.push(FileObject::new(prefix.Name, 0, None, None));
+            }
+        }
+    }
+    Ok((file_objects, next_marker(result.NextMarker)))
+}
+
+// parse errors differ per target (time::Error or JsValue)
+fn last_modified_to_epoch(last_modified: &str) -> Result<u64, String> {
+    http_date_to_epoch(last_modified)
+        .map_err(|_| format!("Invalid Last-Modified: {}", last_modified))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_blob_objects() {
+        let body = r#"<?xml version="1.0" encoding="utf-8"?>
+<EnumerationResults ServiceEndpoint="http://127.0.0.1:10000/devstoreaccount1" ContainerName="data">
+  <Prefix>logs/</Prefix>
+  <MaxResults>2</MaxResults>
+  <Delimiter>/</Delimiter>
+  <Blobs>
+    <Blob>
+      <Name>logs/a.json</Name>
+      <Properties>
+        <Creation-Time>Tue, 02 May 2023 10:00:00 GMT</Creation-Time>
+        <Last-Modified>Tue, 02 May 2023 10:00:00 GMT</Last-Modified>
+        <Etag>0x8DB4AF6B1B4E3C0</Etag>
+        <Content-Length>42</Content-Length>
+        <BlobType>BlockBlob</BlobType>
+      </Properties>
+    </Blob>
+    <BlobPrefix>
+      <Name>logs/archive/</Name>
+    </BlobPrefix>
+    <Blob>
+      <Name>logs/b.json</Name>
+      <Properties>
+        <Last-Modified>Wed, 03 May 2023 10:00:00 GMT</Last-Modified>
+        <Etag>"0x8DB4AF6B1B4E3C1"</Etag>
+        <Content-Length>7</Content-Length>
+      </Properties>
+    </Blob>
+  </Blobs>
+  <NextMarker>2!88!MDAwMDE</NextMarker>
+</EnumerationResults>"#;
+
+        let (file_objects, marker) = parse_blob_objects(body).unwrap();
+        let names: Vec<&str> =
+            file_objects.iter().map(|fo| fo.name()).collect();
+        assert_eq!(names, vec!["logs/a.json", "logs/archive/", "logs/b.json"]);
+        assert_eq!(file_objects[0].size(), 42);
+        assert_eq!(file_objects[0].modified(), Some(1683021600));
+        assert_eq!(file_objects[1].modified(), None);
+        assert_eq!(
+            file_objects[2]
+                .tags()
+                .as_ref()
+                .unwrap()
+                .get("ETag")
+                .unwrap(),
+            "0x8DB4AF6B1B4E3C1"
+        );
+        assert_eq!(marker.as_deref(), Some("2!88!MDAwMDE"));
+
+        let body = r#"<?xml version="1.0" encoding="utf-8"?>
+<EnumerationResults ContainerName="data"><Blobs /><NextMarker /></EnumerationResults>"#;
+        let (file_objects, marker) = parse_blob_objects(body).unwrap();
+        assert!(file_objects.is_empty());
+        assert!(marker.is_none());
+    }
+}
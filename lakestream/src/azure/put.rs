@@ -0,0 +1,24 @@
+use log::info;
+
+use super::bucket::AzureBucket;
+use super::client::create_azure_client;
+use crate::{LakestreamError, ObjectStoreTrait};
+
+pub async fn put_object(
+    azure_bucket: &AzureBucket,
+    object_key: &str,
+    data: &[u8],
+) -> Result<(), LakestreamError> {
+    let azure_client = create_azure_client(azure_bucket.config());
+    let url =
+        azure_client.url(Some(azure_bucket.name()), Some(object_key), &[])?;
+
+    info!(
+        "Putting object: {} of size {} bytes",
+        object_key,
+        data.len()
+    );
+    azure_client
+        .put(&url, data, &[("x-ms-blob-type", "BlockBlob")])
+        .await
+}
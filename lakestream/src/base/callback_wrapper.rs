@@ -125,11 +125,13 @@ impl BinaryCallbackWrapper {
         }
     }
 
-    pub fn call(&self, data: Vec<u8>) {
+    /// Returns the future of the callback, which runs when it is awaited.
+    pub fn call(
+        &self,
+        data: Vec<u8>,
+    ) -> Pin<Box<dyn Future<Output = ()> + Send + 'static>> {
         match self {
-            BinaryCallbackWrapper::Async(callback) => {
-                callback(data);
-            }
+            BinaryCallbackWrapper::Async(callback) => callback(data),
         }
     }
 }
@@ -5,7 +5,8 @@ use std::pin::Pin;
 use futures::Future;
 
 use crate::base::callback_wrapper::CallbackItem;
-use crate::utils::formatters::{bytes_human_readable, time_human_readable};
+use crate::utils::formatters::{format_bytes, time_human_readable, SizeUnits};
+use crate::utils::time::epoch_to_rfc3339_utc;
 
 type BoxedAsyncCallback = Box<
     dyn Fn(&[FileObject]) -> Pin<Box<dyn Future<Output = ()> + Send + 'static>>
@@ -14,9 +15,13 @@ type BoxedAsyncCallback = Box<
         + 'static,
 >;
 
+/// File objects of a listing. With a callback, the objects are handed to
+/// the callback and only counted, so a listing does not grow in memory.
 pub struct FileObjectVec {
     file_objects: Vec<FileObject>,
     callback: Option<BoxedAsyncCallback>,
+    // number of file objects passed to the callback
+    passed_to_callback: usize,
 }
 
 impl FileObjectVec {
@@ -24,12 +29,23 @@ impl FileObjectVec {
         Self {
             file_objects: Vec::new(),
             callback,
+            passed_to_callback: 0,
         }
     }
     pub fn into_inner(self) -> Vec<FileObject> {
         self.file_objects
     }
 
+    /// Number of file objects listed so far, including those passed to
+    /// the callback.
+    pub fn len(&self) -> usize {
+        self.file_objects.len() + self.passed_to_callback
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
     pub async fn extend_async<T: IntoIterator<Item = FileObject>>(
         &mut self,
         iter: T,
@@ -38,9 +54,10 @@ impl FileObjectVec {
         if let Some(callback) = &self.callback {
             let fut = (callback)(&new_file_objects);
             fut.await;
+            self.passed_to_callback += new_file_objects.len();
+        } else {
+            self.file_objects.extend(new_file_objects);
         }
-
-        self.file_objects.extend(new_file_objects);
     }
 }
 
@@ -93,15 +110,25 @@ impl FileObject {
         self.modified
     }
 
+    /// Modification time as RFC3339 timestamp in UTC, e.g.
+    /// "2023-05-02T10:00:00Z".
+    pub fn modified_rfc3339(&self) -> Option<String> {
+        self.modified.map(epoch_to_rfc3339_utc)
+    }
+
     pub fn tags(&self) -> &Option<HashMap<String, String>> {
         &self.tags
     }
 
     pub fn println_path(&self) -> String {
-        self.printable(true)
+        self.println_path_with_units(SizeUnits::Iec)
     }
 
-    fn printable(&self, full_path: bool) -> String {
+    pub fn println_path_with_units(&self, size_units: SizeUnits) -> String {
+        self.printable(true, size_units)
+    }
+
+    fn printable(&self, full_path: bool, size_units: SizeUnits) -> String {
         let name_without_trailing_slash = self.name.trim_end_matches('/');
 
         let mut name_to_print = if full_path {
@@ -109,7 +136,7 @@ impl FileObject {
         } else {
             name_without_trailing_slash
                 .split('/')
-                .last()
+                .next_back()
                 .unwrap_or(name_without_trailing_slash)
                 .to_string()
         };
@@ -118,9 +145,24 @@ impl FileObject {
             name_to_print.push('/');
         }
 
+        // mode, owner and group when listed with POSIX metadata, like ls -l
+        let permissions = self
+            .tags
+            .as_ref()
+            .and_then(|tags| {
+                Some(format!(
+                    "{} {:8} {:8} ",
+                    tags.get("Mode")?,
+                    tags.get("Owner")?,
+                    tags.get("Group")?
+                ))
+            })
+            .unwrap_or_default();
+
         format!(
-            "{:8} {} {}",
-            bytes_human_readable(self.size()),
+            "{}{:9} {} {}",
+            permissions,
+            format_bytes(self.size(), size_units),
             if let Some(modified) = self.modified() {
                 time_human_readable(modified)
             } else {
@@ -0,0 +1,463 @@
+use regex::Regex;
+
+use super::filters::parse_bytes;
+use crate::utils::glob::glob_to_regex;
+use crate::utils::time::system_time_in_seconds;
+use crate::utils::time_parse::calculate_time_offset_seconds;
+use crate::FileObject;
+
+/// Boolean filter expression, e.g.
+/// `(name ~ '\.csv$' or name like '*.tsv') and size > 10M and not mtime < 7D`
+///
+/// Fields are `name`, `size` (with a unit as in `--size`, e.g. 10M, 1.5GiB
+/// or 2GB), `mtime` (the age
+/// of an object, e.g. 7D or 1D8h) and `tag.<key>`. `~` matches a regex and
+/// `like` a glob pattern. Conditions on a missing tag or mtime are false.
+#[derive(Debug, Clone)]
+pub enum Expression {
+    And(Box<Expression>, Box<Expression>),
+    Or(Box<Expression>, Box<Expression>),
+    Not(Box<Expression>),
+    Condition(Condition),
+}
+
+#[derive(Debug, Clone)]
+pub enum Condition {
+    Name(TextMatch),
+    Tag(String, TextMatch),
+    Size(Comparison, u64),
+    /// age in seconds, relative to the time the expression was parsed
+    Age(Comparison, u64, u64),
+}
+
+#[derive(Debug, Clone)]
+pub enum TextMatch {
+    Equals(String),
+    Matches(Regex),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Comparison {
+    Equal,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+}
+
+impl Expression {
+    pub fn parse(expression: &str) -> Result<Expression, String> {
+        let invalid = |reason: String| {
+            format!("Invalid filter expression '{}': {}", expression, reason)
+        };
+        let tokens = tokenize(expression).map_err(invalid)?;
+        let mut parser = Parser {
+            tokens,
+            position: 0,
+            now: system_time_in_seconds(),
+        };
+        let parsed = parser.parse_or().map_err(invalid)?;
+        match parser.tokens.get(parser.position) {
+            None => Ok(parsed),
+            Some(token) => Err(invalid(format!("unexpected {}", token))),
+        }
+    }
+
+    pub fn matches(&self, file_object: &FileObject) -> bool {
+        match self {
+            Expression::And(left, right) => {
+                left.matches(file_object) && right.matches(file_object)
+            }
+            Expression::Or(left, right) => {
+                left.matches(file_object) || right.matches(file_object)
+            }
+            Expression::Not(expression) => !expression.matches(file_object),
+            Expression::Condition(condition) => condition.matches(file_object),
+        }
+    }
+
+    /// Whether any condition is on a tag.
+    pub fn uses_tags(&self) -> bool {
+        match self {
+            Expression::And(left, right) | Expression::Or(left, right) => {
+                left.uses_tags() || right.uses_tags()
+            }
+            Expression::Not(expression) => expression.uses_tags(),
+            Expression::Condition(condition) => {
+                matches!(condition, Condition::Tag(..))
+            }
+        }
+    }
+}
+
+impl Condition {
+    fn matches(&self, file_object: &FileObject) -> bool {
+        match self {
+            Condition::Name(text_match) => {
+                text_match.matches(file_object.name())
+            }
+            Condition::Tag(key, text_match) => file_object
+                .tags()
+                .as_ref()
+                .and_then(|tags| tags.get(key))
+                .is_some_and(|value| text_match.matches(value)),
+            Condition::Size(comparison, size) => {
+                comparison.compare(file_object.size(), *size)
+            }
+            Condition::Age(comparison, age, now) => {
+                file_object.modified().is_some_and(|modified| {
+                    comparison.compare(now.saturating_sub(modified), *age)
+                })
+            }
+        }
+    }
+}
+
+impl TextMatch {
+    fn matches(&self, value: &str) -> bool {
+        match self {
+            TextMatch::Equals(text) => value == text,
+            TextMatch::Matches(regex) => regex.is_match(value),
+        }
+    }
+}
+
+impl Comparison {
+    fn compare(&self, value: u64, other: u64) -> bool {
+        match self {
+            Comparison::Equal => value == other,
+            Comparison::Less => value < other,
+            Comparison::LessOrEqual => value <= other,
+            Comparison::Greater => value > other,
+            Comparison::GreaterOrEqual => value >= other,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    LeftParen,
+    RightParen,
+    Operator(String),
+    Word(String),
+    Quoted(String),
+}
+
+impl std::fmt::Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Token::LeftParen => write!(f, "'('"),
+            Token::RightParen => write!(f, "')'"),
+            Token::Operator(operator) => write!(f, "'{}'", operator),
+            Token::Word(word) => write!(f, "'{}'", word),
+            Token::Quoted(text) => write!(f, "string '{}'", text),
+        }
+    }
+}
+
+const OPERATOR_CHARS: &str = "=!<>~";
+
+fn tokenize(expression: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = expression.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c == '(' {
+            chars.next();
+            tokens.push(Token::LeftParen);
+        } else if c == ')' {
+            chars.next();
+            tokens.push(Token::RightParen);
+        } else if c == '\'' || c == '"' {
+            chars.next();
+            let mut text = String::new();
+            loop {
+                match chars.next() {
+                    None => return Err("unclosed quote".to_string()),
+                    Some(next) if next == c => break,
+                    // only the quote and backslash itself are escaped, so
+                    // regexes such as '\.csv$' can be written as is
+                    Some('\\') => {
+                        let escaped = chars.next_if(|&n| n == c || n == '\\');
+                        text.push(escaped.unwrap_or('\\'));
+                    }
+                    Some(next) => text.push(next),
+                }
+            }
+            tokens.push(Token::Quoted(text));
+        } else if OPERATOR_CHARS.contains(c) {
+            let mut operator = String::new();
+            while let Some(&next) = chars.peek() {
+                if !OPERATOR_CHARS.contains(next) {
+                    break;
+                }
+                operator.push(next);
+                chars.next();
+            }
+            tokens.push(Token::Operator(operator));
+        } else {
+            let mut word = String::new();
+            while let Some(&next) = chars.peek() {
+                if next.is_whitespace()
+                    || "()'\"".contains(next)
+                    || OPERATOR_CHARS.contains(next)
+                {
+                    break;
+                }
+                word.push(next);
+                chars.next();
+            }
+            tokens.push(Token::Word(word));
+        }
+    }
+    Ok(tokens)
+}
+
+// recursive descent, with "not" binding tighter than "and", and "and"
+// tighter than "or"
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+    now: u64,
+}
+
+impl Parser {
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    // consumes the keyword if it is next
+    fn keyword(&mut self, keyword: &str) -> bool {
+        match self.tokens.get(self.position) {
+            Some(Token::Word(word)) if word.eq_ignore_ascii_case(keyword) => {
+                self.position += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn parse_or(&mut self) -> Result<Expression, String> {
+        let mut expression = self.parse_and()?;
+        while self.keyword("or") {
+            let right = self.parse_and()?;
+            expression = Expression::Or(Box::new(expression), Box::new(right));
+        }
+        Ok(expression)
+    }
+
+    fn parse_and(&mut self) -> Result<Expression, String> {
+        let mut expression = self.parse_not()?;
+        while self.keyword("and") {
+            let right = self.parse_not()?;
+            expression = Expression::And(Box::new(expression), Box::new(right));
+        }
+        Ok(expression)
+    }
+
+    fn parse_not(&mut self) -> Result<Expression, String> {
+        if self.keyword("not") {
+            Ok(Expression::Not(Box::new(self.parse_not()?)))
+        } else {
+            self.parse_primary()
+        }
+    }
+
+    fn parse_primary(&mut self) -> Result<Expression, String> {
+        match self.next() {
+            Some(Token::LeftParen) => {
+                let expression = self.parse_or()?;
+                match self.next() {
+                    Some(Token::RightParen) => Ok(expression),
+                    _ => Err("expected ')'".to_string()),
+                }
+            }
+            Some(Token::Word(field)) => self.parse_condition(&field),
+            Some(token) => Err(format!("expected a field, found {}", token)),
+            None => Err("unexpected end".to_string()),
+        }
+    }
+
+    fn parse_condition(&mut self, field: &str) -> Result<Expression, String> {
+        let operator = match self.next() {
+            Some(Token::Operator(operator)) => operator,
+            Some(Token::Word(word)) if word.eq_ignore_ascii_case("like") => {
+                "like".to_string()
+            }
+            Some(token) => {
+                return Err(format!(
+                    "expected an operator after '{}', found {}",
+                    field, token
+                ))
+            }
+            None => {
+                return Err(format!("expected an operator after '{}'", field))
+            }
+        };
+        let value = match self.next() {
+            Some(Token::Word(value)) | Some(Token::Quoted(value)) => value,
+            _ => {
+                return Err(format!(
+                    "expected a value after '{} {}'",
+                    field, operator
+                ))
+            }
+        };
+
+        // "!=" and "!~" are the negation of "=" and "~"
+        let (negate, operator) = match operator.as_str() {
+            "!=" => (true, "="),
+            "!~" => (true, "~"),
+            "==" => (false, "="),
+            operator => (false, operator),
+        };
+        let condition = match field {
+            "name" => Condition::Name(text_match(operator, &value)?),
+            "size" => {
+                Condition::Size(comparison(operator)?, parse_bytes(&value)?)
+            }
+            "mtime" => Condition::Age(
+                comparison(operator)?,
+                calculate_time_offset_seconds(&value)?,
+                self.now,
+            ),
+            _ => match field.strip_prefix("tag.") {
+                Some(key) if !key.is_empty() => Condition::Tag(
+                    key.to_string(),
+                    text_match(operator, &value)?,
+                ),
+                _ => {
+                    return Err(format!(
+                        "unknown field '{}' (expected name, size, mtime or \
+                         tag.<key>)",
+                        field
+                    ))
+                }
+            },
+        };
+
+        let expression = Expression::Condition(condition);
+        if negate {
+            Ok(Expression::Not(Box::new(expression)))
+        } else {
+            Ok(expression)
+        }
+    }
+}
+
+fn text_match(operator: &str, value: &str) -> Result<TextMatch, String> {
+    let regex = match operator {
+        "=" => return Ok(TextMatch::Equals(value.to_string())),
+        "~" => value.to_string(),
+        "like" => glob_to_regex(value)?,
+        _ => {
+            return Err(format!(
+                "operator '{}' does not apply to text (expected =, !=, ~, \
+                 !~ or like)",
+                operator
+            ))
+        }
+    };
+    Regex::new(&regex)
+        .map(TextMatch::Matches)
+        .map_err(|err| format!("invalid pattern '{}': {}", value, err))
+}
+
+fn comparison(operator: &str) -> Result<Comparison, String> {
+    match operator {
+        "=" => Ok(Comparison::Equal),
+        "<" => Ok(Comparison::Less),
+        "<=" => Ok(Comparison::LessOrEqual),
+        ">" => Ok(Comparison::Greater),
+        ">=" => Ok(Comparison::GreaterOrEqual),
+        _ => Err(format!(
+            "operator '{}' does not apply to numbers (expected =, !=, <, \
+             <=, > or >=)",
+            operator
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    fn file_object(name: &str, size: u64, age: u64) -> FileObject {
+        let tags: HashMap<String, String> =
+            [("Owner".to_string(), "etl".to_string())]
+                .into_iter()
+                .collect();
+        FileObject::new(
+            name.to_string(),
+            size,
+            Some(system_time_in_seconds() - age),
+            Some(tags),
+        )
+    }
+
+    #[test]
+    fn test_expression() {
+        const DAY: u64 = 86400;
+        let csv = file_object("data/a.csv", 20 * 1024 * 1024, 30 * DAY);
+        let tsv = file_object("data/b.tsv", 1024, 30 * DAY);
+        let new_csv = file_object("data/c.csv", 20 * 1024 * 1024, DAY);
+
+        let expression = Expression::parse(
+            "(name ~ '\\.csv$' or name ~ '\\.tsv$') and size > 10M and \
+             not mtime < 7D",
+        )
+        .unwrap();
+        assert!(expression.matches(&csv));
+        assert!(!expression.matches(&tsv));
+        assert!(!expression.matches(&new_csv));
+
+        let cases = [
+            ("name like '*.tsv'", [false, true, false]),
+            ("name = data/b.tsv", [false, true, false]),
+            ("name != \"data/b.tsv\"", [true, false, true]),
+            ("size<=1k OR mtime<2D", [false, true, true]),
+            ("not (size = 1k)", [true, false, true]),
+            ("tag.Owner = etl and name !~ b", [true, false, true]),
+            ("tag.Group = etl", [false, false, false]),
+            (
+                "tag.Owner like 'e*' AND NOT mtime > 1W",
+                [false, false, true],
+            ),
+        ];
+        for (input, expected) in cases {
+            let expression = Expression::parse(input).unwrap();
+            let results = [&csv, &tsv, &new_csv]
+                .map(|file_object| expression.matches(file_object));
+            assert_eq!(results, expected, "expression: {}", input);
+        }
+        assert!(Expression::parse("tag.Owner = etl").unwrap().uses_tags());
+        assert!(!Expression::parse("size > 1k").unwrap().uses_tags());
+
+        let invalid_cases = [
+            "",
+            "name",
+            "name ~",
+            "name < a",
+            "size ~ 10M",
+            "size > 10X",
+            "mtime > 2.5D",
+            "owner = etl",
+            "tag. = etl",
+            "(size > 1k",
+            "size > 1k)",
+            "size > 1k size < 2k",
+            "name ~ '[a-'",
+            "name = 'unclosed",
+            "name !== a",
+        ];
+        for input in invalid_cases {
+            assert!(Expression::parse(input).is_err(), "expression: {}", input);
+        }
+    }
+}
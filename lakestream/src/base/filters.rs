@@ -1,9 +1,36 @@
+use std::sync::OnceLock;
+
 use regex::Regex;
 
-use crate::utils::time::system_time_in_seconds;
+use super::filter_expression::Expression;
+use super::ignore_rules::IgnoreRules;
+use crate::utils::glob::glob_to_regex;
+use crate::utils::time::{rfc3339_to_epoch, system_time_in_seconds};
 use crate::utils::time_parse::calculate_time_offset_seconds;
 use crate::FileObject;
 
+/// Syntax of the name pattern of a filter.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum NameMode {
+    /// regular expression, matched anywhere in the name
+    Regex,
+    /// glob pattern, e.g. '*.parquet' or 'year=2023/**/part-*.csv'
+    Glob,
+}
+
+impl NameMode {
+    pub fn from_name(name: &str) -> Result<NameMode, String> {
+        match name {
+            "regex" => Ok(NameMode::Regex),
+            "glob" => Ok(NameMode::Glob),
+            _ => Err(format!(
+                "Invalid name mode: {} (expected regex or glob)",
+                name
+            )),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct FileObjectFilter {
     name_regex: Option<Regex>,
@@ -11,6 +38,33 @@ pub struct FileObjectFilter {
     max_size: Option<u64>,
     min_mtime: Option<u64>,
     max_mtime: Option<u64>,
+    owner: Option<String>,
+    group: Option<String>,
+    mode: Option<ModeFilter>,
+    expression: Option<Expression>,
+    excludes: IgnoreRules,
+    includes: Vec<Regex>,
+}
+
+// permission bits to check, like the -perm option of find
+#[derive(Debug, Clone, PartialEq)]
+enum ModeFilter {
+    /// exactly these bits
+    Exact(u32),
+    /// at least all of these bits
+    All(u32),
+    /// any of these bits
+    Any(u32),
+}
+
+impl ModeFilter {
+    fn matches(&self, mode: u32) -> bool {
+        match *self {
+            ModeFilter::Exact(bits) => mode == bits,
+            ModeFilter::All(bits) => mode & bits == bits,
+            ModeFilter::Any(bits) => bits == 0 || mode & bits != 0,
+        }
+    }
 }
 
 impl FileObjectFilter {
@@ -19,7 +73,9 @@ impl FileObjectFilter {
         size: Option<&str>,
         mtime: Option<&str>,
     ) -> Result<Self, String> {
-        let name_regex = name.map(|pattern| Regex::new(pattern).unwrap());
+        let name_regex = name
+            .map(|pattern| name_regex(pattern, NameMode::Regex))
+            .transpose()?;
 
         let (min_size, max_size) = match size {
             Some(s) => parse_size(s)?,
@@ -37,9 +93,112 @@ impl FileObjectFilter {
             max_size,
             min_mtime,
             max_mtime,
+            owner: None,
+            group: None,
+            mode: None,
+            expression: None,
+            excludes: IgnoreRules::default(),
+            includes: Vec::new(),
         })
     }
 
+    /// Skip objects matching a glob pattern, and everything below matching
+    /// directories. A trailing '/' matches only directories, e.g.
+    /// '_temporary/'; a leading '!' re-includes what an earlier pattern
+    /// excluded. Patterns with a '/' in the middle match from the start of
+    /// the key.
+    pub fn with_exclude(mut self, pattern: &str) -> Result<Self, String> {
+        self.excludes.add(pattern)?;
+        Ok(self)
+    }
+
+    /// Exclude patterns from the contents of an ignore file such as
+    /// .lakestreamignore, with gitignore semantics.
+    pub fn with_ignore_file(mut self, contents: &str) -> Result<Self, String> {
+        self.excludes.extend(IgnoreRules::parse(contents)?);
+        Ok(self)
+    }
+
+    /// Only match objects matching one of the include patterns (globs).
+    /// Exclude patterns still apply.
+    pub fn with_include(mut self, pattern: &str) -> Result<Self, String> {
+        self.includes.push(name_regex(pattern, NameMode::Glob)?);
+        Ok(self)
+    }
+
+    /// Filter with only the exclude patterns of this one, for a listing
+    /// that is matched on the other criteria afterwards.
+    pub(crate) fn excludes_only(&self) -> FileObjectFilter {
+        FileObjectFilter {
+            name_regex: None,
+            min_size: None,
+            max_size: None,
+            min_mtime: None,
+            max_mtime: None,
+            owner: None,
+            group: None,
+            mode: None,
+            expression: None,
+            excludes: self.excludes.clone(),
+            includes: Vec::new(),
+        }
+    }
+
+    /// Whether a directory is excluded, so a listing does not need to
+    /// descend into it.
+    pub fn excludes_directory(&self, key: &str) -> bool {
+        self.excludes.is_excluded(key, true)
+    }
+
+    /// Match a boolean expression on top of the other criteria, e.g.
+    /// `(name ~ '\.csv$' or name like '*.tsv') and not mtime < 7D`.
+    /// See [`Expression`] for the syntax.
+    pub fn with_expression(mut self, expression: &str) -> Result<Self, String> {
+        self.expression = Some(Expression::parse(expression)?);
+        Ok(self)
+    }
+
+    /// Match the name on a pattern in the given syntax, replacing the name
+    /// pattern passed to new().
+    pub fn with_name(
+        mut self,
+        pattern: &str,
+        mode: NameMode,
+    ) -> Result<Self, String> {
+        self.name_regex = Some(name_regex(pattern, mode)?);
+        Ok(self)
+    }
+
+    /// Match objects owned by a user, given by name or uid. Objects without
+    /// an "Owner" tag (e.g. from an object store) do not match.
+    pub fn with_owner(mut self, owner: &str) -> Self {
+        self.owner = Some(owner.to_string());
+        self
+    }
+
+    /// Match objects of a group, given by name or gid.
+    pub fn with_group(mut self, group: &str) -> Self {
+        self.group = Some(group.to_string());
+        self
+    }
+
+    /// Match objects on their permission bits (octal). E.g. '644' for
+    /// exactly these bits, '-600' for at least these bits and '/022' for
+    /// any of these bits.
+    pub fn with_mode(mut self, mode: &str) -> Result<Self, String> {
+        self.mode = Some(parse_mode(mode)?);
+        Ok(self)
+    }
+
+    /// Whether the filter checks owner, group, mode or other tags, which a
+    /// listing then needs to add.
+    pub fn uses_posix_metadata(&self) -> bool {
+        self.owner.is_some()
+            || self.group.is_some()
+            || self.mode.is_some()
+            || self.expression.as_ref().is_some_and(Expression::uses_tags)
+    }
+
     pub fn matches(&self, file_object: &FileObject) -> bool {
         let name_match = match &self.name_regex {
             Some(re) => re.is_match(file_object.name()),
@@ -47,94 +206,160 @@ impl FileObjectFilter {
         };
 
         let size_match = {
-            (self.min_size.map_or(true, |min| file_object.size() >= min))
-                && (self.max_size.map_or(true, |max| file_object.size() <= max))
+            (self.min_size.is_none_or(|min| file_object.size() >= min))
+                && (self.max_size.is_none_or(|max| file_object.size() <= max))
         };
 
         let mtime_match = {
-            (self.min_mtime.map_or(true, |min| {
-                file_object.modified().map_or(false, |mtime| mtime >= min)
-            })) && (self.max_mtime.map_or(true, |max| {
-                file_object.modified().map_or(false, |mtime| mtime <= max)
+            (self.min_mtime.is_none_or(|min| {
+                file_object.modified().is_some_and(|mtime| mtime >= min)
+            })) && (self.max_mtime.is_none_or(|max| {
+                file_object.modified().is_some_and(|mtime| mtime <= max)
             }))
         };
 
-        name_match && size_match && mtime_match
+        let tag = |key: &str| {
+            file_object.tags().as_ref().and_then(|tags| tags.get(key))
+        };
+        let posix_match = self.owner.as_ref().is_none_or(|owner| {
+            tag("Owner") == Some(owner) || tag("Uid") == Some(owner)
+        }) && self.group.as_ref().is_none_or(|group| {
+            tag("Group") == Some(group) || tag("Gid") == Some(group)
+        }) && self.mode.as_ref().is_none_or(|mode_filter| {
+            tag("Mode")
+                .and_then(|mode| u32::from_str_radix(mode, 8).ok())
+                .is_some_and(|mode| mode_filter.matches(mode))
+        });
+
+        let expression_match = self
+            .expression
+            .as_ref()
+            .is_none_or(|expression| expression.matches(file_object));
+
+        let name = file_object.name();
+        let include_match = !self.excludes.excludes(name)
+            && (self.includes.is_empty()
+                || self.includes.iter().any(|regex| regex.is_match(name)));
+
+        name_match
+            && size_match
+            && mtime_match
+            && posix_match
+            && expression_match
+            && include_match
     }
 }
 
-fn parse_size(size: &str) -> Result<(Option<u64>, Option<u64>), String> {
-    const BYTE_UNITS: &[(&str, u64)] = &[
-        ("b", 1u64),
-        ("k", 1024u64),
-        ("M", 1024u64 * 1024u64),
-        ("G", 1024u64 * 1024u64 * 1024u64),
-        ("T", 1024u64 * 1024u64 * 1024u64 * 1024u64),
-    ];
-    const PERCENTAGE: f64 = 0.05;
+fn name_regex(pattern: &str, mode: NameMode) -> Result<Regex, String> {
+    let regex = match mode {
+        NameMode::Regex => pattern.to_string(),
+        NameMode::Glob => glob_to_regex(pattern)?,
+    };
+    Regex::new(&regex)
+        .map_err(|err| format!("Invalid name pattern '{}': {}", pattern, err))
+}
 
-    let re = Regex::new(
-        r"^(?P<modifier>[+\-=]?)(?P<value>\d+)(?P<unit>[bBkKmMGtT]?)$",
-    )
-    .unwrap();
-    let range_re = Regex::new(r"^(?P<min_value>\d+)(?P<unit1>[bBkKmMGtT]?)-(?P<max_value>\d+)(?P<unit2>[bBkKmMGtT]?)$").unwrap();
-
-    if let Some(caps) = range_re.captures(size) {
-        // Handle range case
-        let min_value: u64 =
-            caps["min_value"].parse().expect("Invalid numeric value");
-        let max_value: u64 =
-            caps["max_value"].parse().expect("Invalid numeric value");
-        let unit1 = caps["unit1"].to_ascii_lowercase();
-        let unit2 = caps["unit2"].to_ascii_lowercase();
-
-        let multiplier1 = BYTE_UNITS
-            .iter()
-            .find(|(u, _)| u.to_lowercase() == unit1)
-            .map(|(_, m)| m)
-            .unwrap_or(&1u64);
+fn parse_mode(mode: &str) -> Result<ModeFilter, String> {
+    let (filter, bits): (fn(u32) -> ModeFilter, &str) =
+        if let Some(bits) = mode.strip_prefix('-') {
+            (ModeFilter::All, bits)
+        } else if let Some(bits) = mode.strip_prefix('/') {
+            (ModeFilter::Any, bits)
+        } else {
+            (ModeFilter::Exact, mode)
+        };
+    if bits.is_empty() || !bits.chars().all(|c| ('0'..='7').contains(&c)) {
+        return Err(format!("Invalid mode string: {}", mode));
+    }
+    match u32::from_str_radix(bits, 8) {
+        Ok(bits) if bits <= 0o7777 => Ok(filter(bits)),
+        _ => Err(format!("Invalid mode string: {}", mode)),
+    }
+}
 
-        let multiplier2 = BYTE_UNITS
-            .iter()
-            .find(|(u, _)| u.to_lowercase() == unit2)
-            .map(|(_, m)| m)
-            .unwrap_or(&1u64);
+// binary units are powers of 1024, decimal (SI) units powers of 1000. A
+// single letter is binary, as it has always been.
+const BYTE_UNITS: &[(&str, u64)] = &[
+    ("", 1),
+    ("b", 1),
+    ("k", 1 << 10),
+    ("m", 1 << 20),
+    ("g", 1 << 30),
+    ("t", 1 << 40),
+    ("p", 1 << 50),
+    ("e", 1 << 60),
+    ("kib", 1 << 10),
+    ("mib", 1 << 20),
+    ("gib", 1 << 30),
+    ("tib", 1 << 40),
+    ("pib", 1 << 50),
+    ("eib", 1 << 60),
+    ("kb", 1_000),
+    ("mb", 1_000_000),
+    ("gb", 1_000_000_000),
+    ("tb", 1_000_000_000_000),
+    ("pb", 1_000_000_000_000_000),
+    ("eb", 1_000_000_000_000_000_000),
+];
+
+// a single size with an optional unit, e.g. "10M", "2.5GB" or "1.5GiB"
+pub(crate) fn parse_bytes(value: &str) -> Result<u64, String> {
+    static SIZE: OnceLock<Regex> = OnceLock::new();
+
+    let re = SIZE.get_or_init(|| {
+        Regex::new(r"^(?P<value>\d+(?:\.\d+)?)(?P<unit>[a-zA-Z]*)$").unwrap()
+    });
+    let invalid = || format!("Invalid size string: {}", value);
+    let caps = re.captures(value).ok_or_else(invalid)?;
+    let unit = caps["unit"].to_ascii_lowercase();
+    let multiplier = BYTE_UNITS
+        .iter()
+        .find(|(u, _)| *u == unit)
+        .map(|(_, m)| *m)
+        .ok_or_else(invalid)?;
+
+    let number = &caps["value"];
+    if let Ok(number) = number.parse::<u64>() {
+        return number.checked_mul(multiplier).ok_or_else(invalid);
+    }
+    // fractional sizes are rounded to whole bytes
+    let size = (number.parse::<f64>().map_err(|_| invalid())?
+        * multiplier as f64)
+        .round();
+    if size >= u64::MAX as f64 {
+        return Err(invalid());
+    }
+    Ok(size as u64)
+}
+
+fn parse_size(size: &str) -> Result<(Option<u64>, Option<u64>), String> {
+    const PERCENTAGE: f64 = 0.05;
 
-        let min_size = min_value * multiplier1;
-        let max_size = max_value * multiplier2;
+    if let Some(value) = size.strip_prefix('+') {
+        return Ok((Some(parse_bytes(value)?), None));
+    }
+    if let Some(value) = size.strip_prefix('-') {
+        return Ok((None, Some(parse_bytes(value)?)));
+    }
+    if let Some(value) = size.strip_prefix('=') {
+        let size = parse_bytes(value)?;
+        return Ok((Some(size), Some(size)));
+    }
 
+    if let Some((min_value, max_value)) = size.split_once('-') {
+        let min_size = parse_bytes(min_value)?;
+        let max_size = parse_bytes(max_value)?;
         if min_size > max_size {
             Err("Minimum size is greater than maximum size.".to_string())
         } else {
             Ok((Some(min_size), Some(max_size)))
         }
-    } else if let Some(caps) = re.captures(size) {
-        let modifier = &caps["modifier"];
-        let value: u64 = caps["value"].parse().expect("Invalid numeric value");
-        let unit = caps["unit"].to_ascii_lowercase();
-
-        let multiplier = BYTE_UNITS
-            .iter()
-            .find(|(u, _)| u.to_lowercase() == unit)
-            .map(|(_, m)| m)
-            .unwrap_or(&1u64);
-
-        let size = value * multiplier;
-
-        match modifier {
-            "+" => Ok((Some(size), None)),
-            "-" => Ok((None, Some(size))),
-            "=" => Ok((Some(size), Some(size))),
-            "" => {
-                let min_size = (size as f64 * (1.0 - PERCENTAGE)).ceil() as u64;
-                let max_size =
-                    (size as f64 * (1.0 + PERCENTAGE)).floor() as u64;
-                Ok((Some(min_size), Some(max_size)))
-            }
-            _ => Err(format!("Invalid modifier: {}", modifier)),
-        }
     } else {
-        Err(format!("Invalid size string: {}", size))
+        // about the given size
+        let size = parse_bytes(size)?;
+        let min_size = (size as f64 * (1.0 - PERCENTAGE)).ceil() as u64;
+        let max_size = (size as f64 * (1.0 + PERCENTAGE)).floor() as u64;
+        Ok((Some(min_size), Some(max_size)))
     }
 }
 
@@ -142,6 +367,14 @@ fn parse_time(
     time_offset_str: &str,
     current_time: u64,
 ) -> Result<(Option<u64>, Option<u64>), String> {
+    if let Some((start, end)) = time_offset_str.split_once("..") {
+        return parse_time_range(start, end);
+    }
+    if is_timestamp(time_offset_str) {
+        let (start, end) = parse_timestamp(time_offset_str)?;
+        return Ok((Some(start), Some(end)));
+    }
+
     let is_negative = time_offset_str.starts_with('-');
     let is_positive = time_offset_str.starts_with('+');
 
@@ -180,14 +413,78 @@ fn parse_time(
     Ok((min_time, max_time))
 }
 
+// "start..end", where either side may be left open. The end is inclusive,
+// so "..2023-06-30" includes all of June 30.
+fn parse_time_range(
+    start: &str,
+    end: &str,
+) -> Result<(Option<u64>, Option<u64>), String> {
+    if start.is_empty() && end.is_empty() {
+        return Err("Invalid time range: '..' needs a start or end".to_string());
+    }
+    let min_time = match start {
+        "" => None,
+        start => Some(parse_timestamp(start)?.0),
+    };
+    let max_time = match end {
+        "" => None,
+        end => Some(parse_timestamp(end)?.1),
+    };
+    if let (Some(min_time), Some(max_time)) = (min_time, max_time) {
+        if min_time > max_time {
+            return Err(format!(
+                "Invalid time range: {} is after {}",
+                start, end
+            ));
+        }
+    }
+    Ok((min_time, max_time))
+}
+
+// absolute timestamps start with a date, relative offsets do not
+fn is_timestamp(time_str: &str) -> bool {
+    static TIMESTAMP: OnceLock<Regex> = OnceLock::new();
+
+    TIMESTAMP
+        .get_or_init(|| Regex::new(r"^\d{4}-\d{2}-\d{2}").unwrap())
+        .is_match(time_str)
+}
+
+// first and last second of a YYYY-MM-DD date or an RFC3339 timestamp
+fn parse_timestamp(timestamp: &str) -> Result<(u64, u64), String> {
+    static DATE: OnceLock<Regex> = OnceLock::new();
+
+    let is_date = DATE
+        .get_or_init(|| Regex::new(r"^\d{4}-\d{2}-\d{2}$").unwrap())
+        .is_match(timestamp);
+    let rfc3339 = if is_date {
+        format!("{}T00:00:00Z", timestamp)
+    } else {
+        timestamp.to_string()
+    };
+    // check the format as well, as the wasm32 parser is lenient; times
+    // before 1970 wrap around to values beyond i64::MAX
+    let start = rfc3339_to_epoch(&rfc3339)
+        .ok()
+        .filter(|&start| is_timestamp(timestamp) && start <= i64::MAX as u64)
+        .ok_or_else(|| format!("Invalid timestamp: {}", timestamp))?;
+    if is_date {
+        Ok((start, start + 86400 - 1))
+    } else {
+        Ok((start, start))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    type TimeCase<'a> = (&'a str, Option<u64>, Option<u64>);
+
     fn generate_valid_cases<'a>(
         current_time: u64,
         inputs: &'a [&'a str],
-    ) -> Result<Vec<(&'a str, Option<u64>, Option<u64>)>, String> {
+    ) -> Result<Vec<TimeCase<'a>>, String> {
         inputs
             .iter()
             .map(|&input| {
@@ -278,6 +575,79 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_mode() {
+        assert_eq!(parse_mode("644"), Ok(ModeFilter::Exact(0o644)));
+        assert_eq!(parse_mode("-600"), Ok(ModeFilter::All(0o600)));
+        assert_eq!(parse_mode("/022"), Ok(ModeFilter::Any(0o022)));
+
+        assert!(ModeFilter::All(0o600).matches(0o640));
+        assert!(!ModeFilter::All(0o600).matches(0o440));
+        assert!(ModeFilter::Any(0o022).matches(0o664));
+        assert!(!ModeFilter::Any(0o022).matches(0o644));
+
+        for input in ["", "-", "+644", "648", "rw-r--r--", "17777"] {
+            assert!(parse_mode(input).is_err(), "input: {}", input);
+        }
+    }
+
+    #[test]
+    fn test_parse_time_absolute() {
+        // 2023-01-01T00:00:00Z
+        const JAN_1: u64 = 1672531200;
+        const DAY: u64 = 86400;
+        let current_time = system_time_in_seconds();
+
+        let cases = [
+            ("2023-01-01", Some(JAN_1), Some(JAN_1 + DAY - 1)),
+            (
+                "2023-01-01T12:00:00Z",
+                Some(JAN_1 + DAY / 2),
+                Some(JAN_1 + DAY / 2),
+            ),
+            ("2023-01-01T02:00:00+02:00", Some(JAN_1), Some(JAN_1)),
+            (
+                "2023-01-01..2023-01-31",
+                Some(JAN_1),
+                Some(JAN_1 + 31 * DAY - 1),
+            ),
+            ("2023-01-01..", Some(JAN_1), None),
+            ("..2023-01-01", None, Some(JAN_1 + DAY - 1)),
+            (
+                "2023-01-01T06:00:00Z..2023-01-01T18:00:00Z",
+                Some(JAN_1 + DAY / 4),
+                Some(JAN_1 + 3 * DAY / 4),
+            ),
+        ];
+        for (input, min_time, max_time) in cases {
+            assert_eq!(
+                parse_time(input, current_time).unwrap(),
+                (min_time, max_time),
+                "input: {}",
+                input
+            );
+        }
+
+        let invalid_cases = [
+            "..",
+            "2023-02-30",
+            "2023-13-01",
+            "2023-1-1",
+            "2023-01-01T12:00:00",
+            "2023-02-01..2023-01-01",
+            "2023-01-01..-3D",
+            "01/01/2023",
+            "1969-12-31",
+        ];
+        for input in invalid_cases {
+            assert!(
+                parse_time(input, current_time).is_err(),
+                "input: {}",
+                input
+            );
+        }
+    }
+
     #[test]
     fn test_parse_size() {
         // Test valid inputs
@@ -290,6 +660,18 @@ mod tests {
             ("-5k", None, Some(5120)),
             ("100", Some(95), Some(105)),
             ("100B", Some(95), Some(105)),
+            ("+2.5G", Some(2684354560), None),
+            ("-1.5k", None, Some(1536)),
+            ("=1GB", Some(1_000_000_000), Some(1_000_000_000)),
+            ("=1GiB", Some(1073741824), Some(1073741824)),
+            ("=2kb", Some(2000), Some(2000)),
+            ("=5P", Some(5 << 50), Some(5 << 50)),
+            (
+                "=1EB",
+                Some(1_000_000_000_000_000_000),
+                Some(1_000_000_000_000_000_000),
+            ),
+            ("1MB-1.5MiB", Some(1_000_000), Some(1572864)),
         ];
 
         for (input, min_size, max_size) in cases {
@@ -299,8 +681,10 @@ mod tests {
         }
 
         // Test invalid inputs
-        let invalid_cases =
-            vec!["1G2M3k", "2g5m6b7", "1M-1K", "+3G4M", "2.5G", "5P", "5G5M"];
+        let invalid_cases = vec![
+            "1G2M3k", "2g5m6b7", "1M-1K", "+3G4M", "5G5M", "2.G", ".5G", "5Z",
+            "5KB5", "1.5iB", "20E", "1-2-3", "+-1K",
+        ];
         for input in invalid_cases {
             assert!(parse_size(input).is_err());
             println!("input: {}", input);
@@ -0,0 +1,167 @@
+use std::io::{self, Write};
+
+use regex::bytes::Regex;
+
+use super::callback_wrapper::CallbackItem;
+use crate::compression::decoder::DetectingDecoder;
+use crate::Codec;
+
+/// A line of an object that matches a grep pattern.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LineMatch {
+    pub key: String,
+    /// starts at 1
+    pub line_number: usize,
+    pub line: String,
+}
+
+impl CallbackItem for LineMatch {
+    fn println_path(&self) -> String {
+        format!("{}:{}:{}", self.key, self.line_number, self.line)
+    }
+}
+
+/// Matches the lines of an object against a pattern while the object is
+/// written to it in chunks, so only the current line is held. Compressed
+/// objects (from their key extension, or else their first bytes) are
+/// decompressed on the way.
+pub(crate) struct ObjectGrep<'a> {
+    decoder: DetectingDecoder<LineGrep<'a>>,
+}
+
+impl<'a> ObjectGrep<'a> {
+    pub fn new(key: &str, pattern: &'a Regex) -> ObjectGrep<'a> {
+        let lines = LineGrep {
+            key: key.to_string(),
+            pattern,
+            line: Vec::new(),
+            line_number: 0,
+            matches: Vec::new(),
+        };
+        let decoder = DetectingDecoder::new(Codec::from_extension(key), lines);
+        ObjectGrep { decoder }
+    }
+
+    /// Matches the last line, if it has no newline, and returns the
+    /// matching lines of the object.
+    pub fn finish(self) -> io::Result<Vec<LineMatch>> {
+        Ok(self.decoder.finish()?.finish())
+    }
+}
+
+impl Write for ObjectGrep<'_> {
+    fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+        self.decoder.write(data)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+// lines that match the pattern. Lines that are not valid UTF-8 are matched
+// as bytes and printed lossy.
+struct LineGrep<'a> {
+    key: String,
+    pattern: &'a Regex,
+    // the line that is not complete yet
+    line: Vec<u8>,
+    line_number: usize,
+    matches: Vec<LineMatch>,
+}
+
+impl LineGrep<'_> {
+    fn match_line(&mut self) {
+        self.line_number += 1;
+        let line = self.line.strip_suffix(b"\r").unwrap_or(&self.line);
+        if self.pattern.is_match(line) {
+            self.matches.push(LineMatch {
+                key: self.key.clone(),
+                line_number: self.line_number,
+                line: String::from_utf8_lossy(line).to_string(),
+            });
+        }
+        self.line.clear();
+    }
+
+    fn finish(mut self) -> Vec<LineMatch> {
+        // no empty line after the final newline
+        if !self.line.is_empty() {
+            self.match_line();
+        }
+        self.matches
+    }
+}
+
+impl Write for LineGrep<'_> {
+    fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+        let mut lines = data.split(|&byte| byte == b'\n');
+        // the last part has no newline (yet)
+        let last = lines.next_back().unwrap_or_default();
+        for line in lines {
+            self.line.extend_from_slice(line);
+            self.match_line();
+        }
+        self.line.extend_from_slice(last);
+        Ok(data.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // matches of data that is written in chunks of `size` bytes
+    fn grep_chunks(
+        key: &str,
+        data: &[u8],
+        pattern: &Regex,
+        size: usize,
+    ) -> Vec<String> {
+        let mut grep = ObjectGrep::new(key, pattern);
+        for chunk in data.chunks(size) {
+            grep.write_all(chunk).unwrap();
+        }
+        grep.finish()
+            .unwrap()
+            .iter()
+            .map(|line_match| line_match.println_path())
+            .collect()
+    }
+
+    #[test]
+    fn test_object_grep() {
+        let data =
+            b"GET /a request_id=abc\r\nGET /b request_id=def\n\xff abc\n";
+        let pattern = Regex::new("abc").unwrap();
+        let expected = [
+            "logs/app.log:1:GET /a request_id=abc",
+            "logs/app.log:3:\u{fffd} abc",
+        ];
+        for size in [1, 3, data.len()] {
+            assert_eq!(
+                grep_chunks("logs/app.log", data, &pattern, size),
+                expected
+            );
+        }
+        let empty_line = Regex::new("^$").unwrap();
+        assert!(grep_chunks("logs/app.log", data, &empty_line, 1).is_empty());
+        // the last line has no newline
+        assert_eq!(
+            grep_chunks("logs/app.log", b"a\nabc", &pattern, 2),
+            ["logs/app.log:2:abc"]
+        );
+
+        // compressed, with and without extension
+        let gzip = Codec::Gzip.compress(data).unwrap();
+        for key in ["logs/app.log.gz", "logs/app.log"] {
+            let matches = grep_chunks(key, &gzip, &pattern, 5);
+            assert_eq!(matches.len(), 2);
+            assert!(matches[0].ends_with(":1:GET /a request_id=abc"));
+        }
+    }
+}
@@ -0,0 +1,149 @@
+use regex::Regex;
+
+use crate::utils::glob::glob_to_regex;
+
+/// Exclude patterns with gitignore semantics: the last matching pattern
+/// wins, '!' re-includes what an earlier pattern excluded, a trailing '/'
+/// matches only directories and everything below an excluded directory is
+/// excluded. A pattern with a '/' at the start or in the middle is relative
+/// to the directory of the ignore file (the start of the path), others
+/// match at any depth. Patterns are globs, see [`glob_to_regex`].
+#[derive(Debug, Clone, Default)]
+pub struct IgnoreRules {
+    rules: Vec<Rule>,
+}
+
+#[derive(Debug, Clone)]
+struct Rule {
+    regex: Regex,
+    negate: bool,
+    directory_only: bool,
+}
+
+impl IgnoreRules {
+    /// Rules from the contents of an ignore file, one pattern per line.
+    /// Empty lines and lines starting with '#' are skipped.
+    pub fn parse(contents: &str) -> Result<IgnoreRules, String> {
+        let mut rules = IgnoreRules::default();
+        for line in contents.lines() {
+            let line = line.trim_end();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            rules.add(line)?;
+        }
+        Ok(rules)
+    }
+
+    /// Adds a pattern after the existing ones. "\#" and "\!" escape a
+    /// leading '#' or '!'.
+    pub fn add(&mut self, pattern: &str) -> Result<(), String> {
+        let (negate, pattern) = match pattern.strip_prefix('!') {
+            Some(pattern) => (true, pattern),
+            None => (false, pattern),
+        };
+        let (directory_only, pattern) = match pattern.strip_suffix('/') {
+            Some(pattern) => (true, pattern),
+            None => (false, pattern),
+        };
+        if pattern.is_empty() || pattern == "/" {
+            return Err(format!("Invalid exclude pattern: '{}'", pattern));
+        }
+        let pattern = if pattern.contains('/') && !pattern.starts_with('/') {
+            format!("/{}", pattern)
+        } else {
+            pattern.to_string()
+        };
+        let regex = Regex::new(&glob_to_regex(&pattern)?).map_err(|err| {
+            format!("Invalid exclude pattern '{}': {}", pattern, err)
+        })?;
+        self.rules.push(Rule {
+            regex,
+            negate,
+            directory_only,
+        });
+        Ok(())
+    }
+
+    pub fn extend(&mut self, other: IgnoreRules) {
+        self.rules.extend(other.rules);
+    }
+
+    /// Whether a path is excluded by the rules, without looking at its
+    /// parent directories. For use during a traversal, which does not
+    /// descend into excluded directories to begin with.
+    pub fn is_excluded(&self, path: &str, is_dir: bool) -> bool {
+        let path = path.trim_end_matches('/');
+        self.rules
+            .iter()
+            .rev()
+            .find(|rule| {
+                (is_dir || !rule.directory_only) && rule.regex.is_match(path)
+            })
+            .is_some_and(|rule| !rule.negate)
+    }
+
+    /// Whether a key, or any of the directories it is in, is excluded. Keys
+    /// of directories end with '/'.
+    pub fn excludes(&self, key: &str) -> bool {
+        let is_dir = key.ends_with('/');
+        let key = key.trim_end_matches('/');
+        key.match_indices('/')
+            .any(|(index, _)| self.is_excluded(&key[..index], true))
+            || self.is_excluded(key, is_dir)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ignore_rules() {
+        let rules = IgnoreRules::parse(
+            "# build output\n\
+             _temporary/\n\
+             .git/\n\
+             _SUCCESS\n\
+             *.tmp\n\
+             !keep.tmp\n\
+             \n\
+             /logs/**/debug-*\n\
+             build/out\n\
+             **/cache/\n",
+        )
+        .unwrap();
+
+        let cases = [
+            ("data/_temporary/", true),
+            ("data/_temporary/0/part-0.csv", true),
+            // only directories
+            ("data/_temporary", false),
+            (".git/config", true),
+            ("year=2023/_SUCCESS", true),
+            ("year=2023/part-0.csv", false),
+            ("a/b.tmp", true),
+            ("a/keep.tmp", false),
+            ("logs/2023/debug-1.log", true),
+            ("data/logs/2023/debug-1.log", false),
+            // a '/' in the middle anchors the pattern
+            ("build/out", true),
+            ("src/build/out", false),
+            // unless it starts with "**/"
+            ("cache/a.bin", true),
+            ("src/cache/a.bin", true),
+        ];
+        for (key, expected) in cases {
+            assert_eq!(rules.excludes(key), expected, "key: {}", key);
+        }
+
+        // the last matching pattern wins
+        let mut rules = IgnoreRules::default();
+        rules.add("!*.csv").unwrap();
+        rules.add("*").unwrap();
+        assert!(rules.is_excluded("a.csv", false));
+
+        assert!(IgnoreRules::parse("[a-\n").is_err());
+        assert!(IgnoreRules::default().add("/").is_err());
+    }
+}
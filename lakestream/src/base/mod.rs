@@ -1,7 +1,14 @@
 pub mod callback_wrapper;
 pub mod config;
 pub mod file_object;
+pub mod filter_expression;
 pub mod filters;
+pub mod grep;
+pub mod ignore_rules;
 pub mod list_objects_result;
 pub mod object_store;
 pub mod object_store_helpers;
+pub mod object_store_registry;
+pub mod prefix_totals;
+pub mod sort;
+pub mod sorted_listing;
@@ -1,14 +1,25 @@
+use std::io::Write;
 use std::ops::{Deref, DerefMut};
 use std::pin::Pin;
+use std::sync::Arc;
 
 use async_trait::async_trait;
 use futures::Future;
 
-pub use super::object_store_helpers::object_stores_from_config;
 use super::object_store_helpers::BoxedAsyncCallbackForObjectStore;
+pub use super::object_store_helpers::{
+    object_stores_from_config, object_stores_from_config_with_registry,
+};
+use super::object_store_registry::ObjectStoreRegistry;
+use crate::archive::backend::ArchiveBucket;
+use crate::azure::backend::AzureBucket;
 use crate::base::callback_wrapper::CallbackItem;
+use crate::cache::disk_cache::DiskCache;
+use crate::http::backend::HttpBucket;
 use crate::localfs::backend::LocalFsBucket;
+use crate::memory::backend::MemoryBucket;
 use crate::s3::backend::S3Bucket;
+use crate::utils::formatters::{format_bytes, SizeUnits};
 use crate::{
     CallbackWrapper, Config, FileObject, FileObjectFilter, FileObjectVec,
     LakestreamError,
@@ -63,48 +74,84 @@ impl DerefMut for ObjectStoreVec {
 
 #[derive(Clone)]
 pub enum ObjectStore {
+    ArchiveBucket(ArchiveBucket),
     S3Bucket(S3Bucket),
+    AzureBucket(AzureBucket),
+    HttpBucket(HttpBucket),
     LocalFsBucket(LocalFsBucket),
+    MemoryBucket(MemoryBucket),
+    Custom(CustomBucket),
 }
 
 impl ObjectStore {
+    /// Create an object store from a URI with the built-in backends. Use
+    /// the registry of an `ObjectStoreHandler` for registered backends.
     pub fn new(name: &str, config: Config) -> Result<ObjectStore, String> {
-        if name.starts_with("s3://") {
-            let name = name.trim_start_matches("s3://");
-            let bucket =
-                S3Bucket::new(name, config).map_err(|err| err.to_string())?;
-            Ok(ObjectStore::S3Bucket(bucket))
-        } else if name.starts_with("localfs://") {
-            let name = name.trim_start_matches("localfs://");
-            let local_fs = LocalFsBucket::new(name, config)
-                .map_err(|err| err.to_string())?;
-            Ok(ObjectStore::LocalFsBucket(local_fs))
-        } else {
-            Err("Unsupported object store.".to_string())
-        }
+        ObjectStoreRegistry::new()
+            .create_object_store(name, config)
+            .map_err(|err| err.to_string())
     }
 
-    pub fn name(&self) -> &str {
+    fn as_object_store_trait(&self) -> &dyn ObjectStoreTrait {
         match self {
-            ObjectStore::S3Bucket(bucket) => bucket.name(),
-            ObjectStore::LocalFsBucket(local_fs) => local_fs.name(),
+            ObjectStore::ArchiveBucket(bucket) => bucket,
+            ObjectStore::S3Bucket(bucket) => bucket,
+            ObjectStore::AzureBucket(bucket) => bucket,
+            ObjectStore::HttpBucket(bucket) => bucket,
+            ObjectStore::LocalFsBucket(local_fs) => local_fs,
+            ObjectStore::MemoryBucket(bucket) => bucket,
+            ObjectStore::Custom(custom) => custom.bucket.as_ref(),
         }
     }
 
+    pub fn name(&self) -> &str {
+        self.as_object_store_trait().name()
+    }
+
     pub fn config(&self) -> &Config {
-        match self {
-            ObjectStore::S3Bucket(bucket) => bucket.config(),
-            ObjectStore::LocalFsBucket(local_fs) => local_fs.config(),
-        }
+        self.as_object_store_trait().config()
     }
 
     pub fn println_path(&self) -> String {
+        self.println_path_with_units(SizeUnits::Iec)
+    }
+
+    pub fn println_path_with_units(&self, size_units: SizeUnits) -> String {
         match self {
+            ObjectStore::ArchiveBucket(bucket) => {
+                // remote archives are printed as archive+s3://...
+                if bucket.name().contains("://") {
+                    format!("archive+{}", bucket.name())
+                } else {
+                    format!("archive://{}", bucket.name())
+                }
+            }
             ObjectStore::S3Bucket(bucket) => {
                 format!("s3://{}", bucket.name())
             }
-            ObjectStore::LocalFsBucket(local_fs) => {
-                format!("{}", local_fs.name())
+            ObjectStore::AzureBucket(bucket) => {
+                format!("az://{}", bucket.name())
+            }
+            ObjectStore::HttpBucket(bucket) => {
+                format!("{}://{}", bucket.scheme(), bucket.name())
+            }
+            ObjectStore::LocalFsBucket(local_fs) => match local_fs.usage() {
+                // like df: size, used, available and type
+                Some(usage) => format!(
+                    "{:9} {:9} {:9} {:8} {}",
+                    format_bytes(usage.total, size_units),
+                    format_bytes(usage.used, size_units),
+                    format_bytes(usage.available, size_units),
+                    usage.fs_type.as_deref().unwrap_or("-"),
+                    local_fs.name()
+                ),
+                None => local_fs.name().to_string(),
+            },
+            ObjectStore::MemoryBucket(bucket) => {
+                format!("memory://{}", bucket.name())
+            }
+            ObjectStore::Custom(custom) => {
+                format!("{}://{}", custom.scheme(), custom.bucket.name())
             }
         }
     }
@@ -117,30 +164,9 @@ impl ObjectStore {
         filter: &Option<FileObjectFilter>,
     ) -> Result<Vec<FileObject>, LakestreamError> {
         let mut file_objects = FileObjectVec::new(None);
-        match self {
-            ObjectStore::S3Bucket(bucket) => {
-                bucket
-                    .list_files(
-                        prefix,
-                        recursive,
-                        max_keys,
-                        filter,
-                        &mut file_objects,
-                    )
-                    .await
-            }
-            ObjectStore::LocalFsBucket(local_fs) => {
-                local_fs
-                    .list_files(
-                        prefix,
-                        recursive,
-                        max_keys,
-                        filter,
-                        &mut file_objects,
-                    )
-                    .await
-            }
-        }?;
+        self.as_object_store_trait()
+            .list_files(prefix, recursive, max_keys, filter, &mut file_objects)
+            .await?;
         Ok(file_objects.into_inner())
     }
 
@@ -185,43 +211,182 @@ impl ObjectStore {
         };
 
         let mut file_objects = FileObjectVec::new(callback);
-        match self {
-            ObjectStore::S3Bucket(bucket) => {
-                bucket
-                    .list_files(
-                        prefix,
-                        recursive,
-                        max_files,
-                        filter,
-                        &mut file_objects,
-                    )
-                    .await
+        self.as_object_store_trait()
+            .list_files(prefix, recursive, max_files, filter, &mut file_objects)
+            .await
+    }
+
+    /// Get an object, through the disk cache if one is configured (see
+    /// `LAKESTREAM_CACHE_DIR`) and the object store is not local.
+    pub async fn get_object(
+        &self,
+        key: &str,
+        data: &mut Vec<u8>,
+    ) -> Result<(), LakestreamError> {
+        if self.is_cacheable() {
+            if let Some(cache) = DiskCache::from_config(self.config())? {
+                return cache.get_object(self, key, data).await;
             }
-            ObjectStore::LocalFsBucket(local_fs) => {
-                local_fs
-                    .list_files(
-                        prefix,
-                        recursive,
-                        max_files,
-                        filter,
-                        &mut file_objects,
-                    )
-                    .await
+        }
+        self.as_object_store_trait().get_object(key, data).await
+    }
+
+    pub async fn get_object_to_writer(
+        &self,
+        key: &str,
+        writer: &mut dyn Write,
+    ) -> Result<(), LakestreamError> {
+        // cached objects are stored and read whole
+        if self.is_cacheable() {
+            if let Some(cache) = DiskCache::from_config(self.config())? {
+                let mut data = Vec::new();
+                cache.get_object(self, key, &mut data).await?;
+                writer.write_all(&data)?;
+                return Ok(());
             }
         }
+        self.as_object_store_trait()
+            .get_object_to_writer(key, writer)
+            .await
     }
 
-    pub async fn get_object(
+    pub async fn put_object(
         &self,
         key: &str,
-        data: &mut Vec<u8>,
+        data: &[u8],
     ) -> Result<(), LakestreamError> {
-        match self {
-            ObjectStore::S3Bucket(bucket) => bucket.get_object(key, data).await,
-            ObjectStore::LocalFsBucket(local_fs) => {
-                local_fs.get_object(key, data).await
+        self.as_object_store_trait().put_object(key, data).await
+    }
+
+    pub async fn get_object_if_none_match(
+        &self,
+        key: &str,
+        etag: Option<&str>,
+        data: &mut Vec<u8>,
+    ) -> Result<ConditionalGet, LakestreamError> {
+        self.as_object_store_trait()
+            .get_object_if_none_match(key, etag, data)
+            .await
+    }
+
+    /// Like `get_object`, but also returns the Content-Encoding of the
+    /// object. Objects served from the disk cache have no Content-Encoding.
+    pub async fn get_object_with_content_encoding(
+        &self,
+        key: &str,
+        data: &mut Vec<u8>,
+    ) -> Result<Option<String>, LakestreamError> {
+        if self.is_cacheable() {
+            if let Some(cache) = DiskCache::from_config(self.config())? {
+                cache.get_object(self, key, data).await?;
+                return Ok(None);
             }
         }
+        self.as_object_store_trait()
+            .get_object_with_content_encoding(key, data)
+            .await
+    }
+
+    // custom backends opt in by returning an ETag from
+    // get_object_if_none_match
+    fn is_cacheable(&self) -> bool {
+        matches!(
+            self,
+            ObjectStore::S3Bucket(_)
+                | ObjectStore::AzureBucket(_)
+                | ObjectStore::HttpBucket(_)
+                | ObjectStore::Custom(_)
+        )
+    }
+}
+
+/// Object store of a backend that is registered at runtime, see
+/// `ObjectStoreRegistry`.
+#[derive(Clone)]
+pub struct CustomBucket {
+    scheme: String,
+    bucket: Arc<dyn ObjectStoreTrait + Send + Sync>,
+}
+
+impl CustomBucket {
+    pub fn new<T>(scheme: &str, bucket: T) -> CustomBucket
+    where
+        T: ObjectStoreTrait + Send + Sync + 'static,
+    {
+        CustomBucket {
+            scheme: scheme.to_string(),
+            bucket: Arc::new(bucket),
+        }
+    }
+
+    pub fn scheme(&self) -> &str {
+        &self.scheme
+    }
+}
+
+// lets adapters such as EncryptedStore wrap any of the object stores
+#[async_trait(?Send)]
+impl ObjectStoreTrait for ObjectStore {
+    fn name(&self) -> &str {
+        ObjectStore::name(self)
+    }
+
+    fn config(&self) -> &Config {
+        ObjectStore::config(self)
+    }
+
+    async fn list_files(
+        &self,
+        prefix: Option<&str>,
+        recursive: bool,
+        max_keys: Option<u32>,
+        filter: &Option<FileObjectFilter>,
+        file_objects: &mut FileObjectVec,
+    ) -> Result<(), LakestreamError> {
+        self.as_object_store_trait()
+            .list_files(prefix, recursive, max_keys, filter, file_objects)
+            .await
+    }
+
+    async fn get_object(
+        &self,
+        key: &str,
+        data: &mut Vec<u8>,
+    ) -> Result<(), LakestreamError> {
+        ObjectStore::get_object(self, key, data).await
+    }
+
+    async fn get_object_to_writer(
+        &self,
+        key: &str,
+        writer: &mut dyn Write,
+    ) -> Result<(), LakestreamError> {
+        ObjectStore::get_object_to_writer(self, key, writer).await
+    }
+
+    async fn put_object(
+        &self,
+        key: &str,
+        data: &[u8],
+    ) -> Result<(), LakestreamError> {
+        ObjectStore::put_object(self, key, data).await
+    }
+
+    async fn get_object_if_none_match(
+        &self,
+        key: &str,
+        etag: Option<&str>,
+        data: &mut Vec<u8>,
+    ) -> Result<ConditionalGet, LakestreamError> {
+        ObjectStore::get_object_if_none_match(self, key, etag, data).await
+    }
+
+    async fn get_object_with_content_encoding(
+        &self,
+        key: &str,
+        data: &mut Vec<u8>,
+    ) -> Result<Option<String>, LakestreamError> {
+        ObjectStore::get_object_with_content_encoding(self, key, data).await
     }
 }
 
@@ -248,4 +413,65 @@ pub trait ObjectStoreTrait {
         key: &str,
         data: &mut Vec<u8>,
     ) -> Result<(), LakestreamError>;
+
+    /// GET that writes the object to `writer` in chunks as it arrives, so
+    /// it does not have to fit in memory. Backends that cannot stream keep
+    /// the default, which writes it at once.
+    async fn get_object_to_writer(
+        &self,
+        key: &str,
+        writer: &mut dyn Write,
+    ) -> Result<(), LakestreamError> {
+        let mut data = Vec::new();
+        self.get_object(key, &mut data).await?;
+        writer.write_all(&data)?;
+        Ok(())
+    }
+
+    /// Store an object. Read-only backends keep the default, which fails.
+    async fn put_object(
+        &self,
+        key: &str,
+        data: &[u8],
+    ) -> Result<(), LakestreamError> {
+        let _ = data;
+        Err(LakestreamError::String(format!(
+            "Cannot put {}: object store {} is read-only",
+            key,
+            self.name()
+        )))
+    }
+
+    /// GET that the backend may skip if the object still matches `etag`.
+    /// Backends without support for conditional requests always fetch.
+    async fn get_object_if_none_match(
+        &self,
+        key: &str,
+        etag: Option<&str>,
+        data: &mut Vec<u8>,
+    ) -> Result<ConditionalGet, LakestreamError> {
+        let _ = etag;
+        self.get_object(key, data).await?;
+        Ok(ConditionalGet::Modified(None))
+    }
+
+    /// GET that also returns the Content-Encoding the object is stored
+    /// with. Backends that do not keep this metadata return None.
+    async fn get_object_with_content_encoding(
+        &self,
+        key: &str,
+        data: &mut Vec<u8>,
+    ) -> Result<Option<String>, LakestreamError> {
+        self.get_object(key, data).await?;
+        Ok(None)
+    }
+}
+
+/// Result of `ObjectStoreTrait::get_object_if_none_match`.
+#[derive(Debug, PartialEq)]
+pub enum ConditionalGet {
+    /// object still matches the ETag, data is left untouched
+    NotModified,
+    /// object is fetched into data, with its current ETag if known
+    Modified(Option<String>),
 }
@@ -1,11 +1,8 @@
 use std::pin::Pin;
 
 use futures::Future;
-use log::error;
 
-use crate::api::object_store_handler::ObjectStoreBackend;
-use crate::localfs::backend::LocalFsBackend;
-use crate::s3::backend::S3Backend;
+use super::object_store_registry::{builtin_registry, ObjectStoreRegistry};
 use crate::{
     CallbackWrapper, Config, LakestreamError, ObjectStore, ObjectStoreVec,
 };
@@ -17,9 +14,26 @@ pub type BoxedAsyncCallbackForObjectStore = Box<
         + 'static,
 >;
 
+/// List the object stores for the scheme in the "uri" setting using the
+/// builtin backends.
 pub async fn object_stores_from_config(
     config: Config,
     callback: &Option<CallbackWrapper<ObjectStore>>,
+) -> Result<ObjectStoreVec, LakestreamError> {
+    object_stores_from_config_with_registry(
+        builtin_registry(),
+        config,
+        callback,
+    )
+    .await
+}
+
+/// Like `object_stores_from_config`, but resolves the scheme through the
+/// given registry so that registered backends are included.
+pub async fn object_stores_from_config_with_registry(
+    registry: &ObjectStoreRegistry,
+    config: Config,
+    callback: &Option<CallbackWrapper<ObjectStore>>,
 ) -> Result<ObjectStoreVec, LakestreamError> {
     let uri = config.get("uri").unwrap_or(&"".to_string()).clone();
 
@@ -45,16 +59,10 @@ pub async fn object_stores_from_config(
 
     let mut object_stores = ObjectStoreVec::new(callback);
 
-    if uri.starts_with("s3://") {
-        // Delegate the logic to the S3 backend
-        S3Backend::list_buckets(config.clone(), &mut object_stores).await?;
-    } else if uri.starts_with("localfs://") {
-        // Delegate the logic to the LocalFs backend
-        LocalFsBackend::list_buckets(config.clone(), &mut object_stores)
-            .await?;
-    } else {
-        error!("Unsupported object store type: {}", uri);
-    }
+    // Delegate the logic to the backend registered for the scheme
+    registry
+        .list_buckets(&uri, config, &mut object_stores)
+        .await?;
 
     Ok(object_stores)
 }
@@ -0,0 +1,338 @@
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::sync::{Arc, OnceLock};
+
+use async_trait::async_trait;
+
+use crate::api::object_store_handler::ObjectStoreBackend;
+use crate::archive::backend::{ArchiveBackend, ArchiveBucket};
+use crate::azure::backend::{AzureBackend, AzureBucket};
+use crate::encryption::backend::{EncryptedBackend, EncryptedStore, MasterKey};
+use crate::http::backend::{HttpBackend, HttpBucket};
+use crate::localfs::backend::{LocalFsBackend, LocalFsBucket};
+use crate::memory::backend::{MemoryBackend, MemoryBucket};
+use crate::s3::backend::{S3Backend, S3Bucket};
+use crate::{
+    Config, CustomBucket, LakestreamError, ObjectStore, ObjectStoreVec,
+};
+
+/// Creates object stores for a URI scheme and lists the available ones.
+#[async_trait(?Send)]
+pub trait ObjectStoreFactory: Send + Sync {
+    fn create_object_store(
+        &self,
+        name: &str,
+        config: Config,
+    ) -> Result<ObjectStore, LakestreamError>;
+
+    /// Creates an object store from the registry the factory is registered
+    /// in. Object stores that wrap another one, such as "archive+s3",
+    /// create the inner object store via that registry, so it can be of a
+    /// registered backend as well.
+    fn create_object_store_in(
+        &self,
+        _registry: &ObjectStoreRegistry,
+        name: &str,
+        config: Config,
+    ) -> Result<ObjectStore, LakestreamError> {
+        self.create_object_store(name, config)
+    }
+
+    async fn list_buckets(
+        &self,
+        config: Config,
+        object_stores: &mut ObjectStoreVec,
+    ) -> Result<(), LakestreamError>;
+}
+
+/// Adapts an `ObjectStoreBackend` and a bucket constructor to an
+/// `ObjectStoreFactory`.
+pub struct BackendFactory<B, F> {
+    bucket_factory: F,
+    _backend: PhantomData<fn() -> B>,
+}
+
+impl<B, F> BackendFactory<B, F>
+where
+    B: ObjectStoreBackend,
+    F: Fn(&str, Config) -> Result<ObjectStore, LakestreamError> + Send + Sync,
+{
+    pub fn new(bucket_factory: F) -> Self {
+        BackendFactory {
+            bucket_factory,
+            _backend: PhantomData,
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl<B, F> ObjectStoreFactory for BackendFactory<B, F>
+where
+    B: ObjectStoreBackend,
+    F: Fn(&str, Config) -> Result<ObjectStore, LakestreamError> + Send + Sync,
+{
+    fn create_object_store(
+        &self,
+        name: &str,
+        config: Config,
+    ) -> Result<ObjectStore, LakestreamError> {
+        (self.bucket_factory)(name, config)
+    }
+
+    async fn list_buckets(
+        &self,
+        config: Config,
+        object_stores: &mut ObjectStoreVec,
+    ) -> Result<(), LakestreamError> {
+        B::list_buckets(config, object_stores).await
+    }
+}
+
+/// Like `BackendFactory`, for object stores that wrap another object store.
+/// The bucket constructor gets the registry to create the inner one with.
+pub(crate) struct WrappingBackendFactory<B, F> {
+    bucket_factory: F,
+    _backend: PhantomData<fn() -> B>,
+}
+
+#[async_trait(?Send)]
+impl<B, F> ObjectStoreFactory for WrappingBackendFactory<B, F>
+where
+    B: ObjectStoreBackend,
+    F: Fn(
+            &str,
+            Config,
+            &ObjectStoreRegistry,
+        ) -> Result<ObjectStore, LakestreamError>
+        + Send
+        + Sync,
+{
+    fn create_object_store(
+        &self,
+        name: &str,
+        config: Config,
+    ) -> Result<ObjectStore, LakestreamError> {
+        (self.bucket_factory)(name, config, builtin_registry())
+    }
+
+    fn create_object_store_in(
+        &self,
+        registry: &ObjectStoreRegistry,
+        name: &str,
+        config: Config,
+    ) -> Result<ObjectStore, LakestreamError> {
+        (self.bucket_factory)(name, config, registry)
+    }
+
+    async fn list_buckets(
+        &self,
+        config: Config,
+        object_stores: &mut ObjectStoreVec,
+    ) -> Result<(), LakestreamError> {
+        B::list_buckets(config, object_stores).await
+    }
+}
+
+/// Maps URI schemes (e.g. "s3") to the factory of their backend.
+#[derive(Clone)]
+pub struct ObjectStoreRegistry {
+    factories: HashMap<String, Arc<dyn ObjectStoreFactory>>,
+}
+
+impl ObjectStoreRegistry {
+    /// Registry without any backends.
+    pub fn empty() -> Self {
+        ObjectStoreRegistry {
+            factories: HashMap::new(),
+        }
+    }
+
+    /// Registry with all backends that are built into lakestream.
+    pub fn new() -> Self {
+        builtin_registry().clone()
+    }
+
+    pub fn register<F>(&mut self, scheme: &str, factory: F)
+    where
+        F: ObjectStoreFactory + 'static,
+    {
+        self.factories
+            .insert(scheme.to_lowercase(), Arc::new(factory));
+    }
+
+    pub fn register_backend<B, F>(&mut self, scheme: &str, bucket_factory: F)
+    where
+        B: ObjectStoreBackend + 'static,
+        F: Fn(&str, Config) -> Result<ObjectStore, LakestreamError>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.register(scheme, BackendFactory::<B, F>::new(bucket_factory));
+    }
+
+    fn register_wrapping_backend<B, F>(
+        &mut self,
+        scheme: &str,
+        bucket_factory: F,
+    ) where
+        B: ObjectStoreBackend + 'static,
+        F: Fn(
+                &str,
+                Config,
+                &ObjectStoreRegistry,
+            ) -> Result<ObjectStore, LakestreamError>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.register(
+            scheme,
+            WrappingBackendFactory::<B, F> {
+                bucket_factory,
+                _backend: PhantomData,
+            },
+        );
+    }
+
+    pub fn get(&self, scheme: &str) -> Option<&dyn ObjectStoreFactory> {
+        self.factories
+            .get(&scheme.to_lowercase())
+            .map(|factory| factory.as_ref())
+    }
+
+    pub fn schemes(&self) -> Vec<&str> {
+        let mut schemes: Vec<&str> =
+            self.factories.keys().map(String::as_str).collect();
+        schemes.sort();
+        schemes
+    }
+
+    /// Create an object store from a URI such as "s3://bucket-name".
+    ///
+    /// Composite schemes such as "archive+s3" that are not registered
+    /// themselves go to the factory of the outer scheme ("archive"), with
+    /// the inner URI (e.g. "s3://bucket-name/data.zip") as name.
+    pub fn create_object_store(
+        &self,
+        uri: &str,
+        config: Config,
+    ) -> Result<ObjectStore, LakestreamError> {
+        let (scheme, name) = split_scheme(uri)?;
+        if self.get(scheme).is_none() {
+            if let Some((outer, inner)) = scheme.split_once('+') {
+                return self.factory(outer)?.create_object_store_in(
+                    self,
+                    &format!("{}://{}", inner, name),
+                    config,
+                );
+            }
+        }
+        self.factory(scheme)?
+            .create_object_store_in(self, name, config)
+    }
+
+    /// List buckets of the backend that matches the scheme of a URI.
+    pub async fn list_buckets(
+        &self,
+        uri: &str,
+        config: Config,
+        object_stores: &mut ObjectStoreVec,
+    ) -> Result<(), LakestreamError> {
+        let (scheme, _) = split_scheme(uri)?;
+        self.factory(scheme)?
+            .list_buckets(config, object_stores)
+            .await
+    }
+
+    fn factory(
+        &self,
+        scheme: &str,
+    ) -> Result<&dyn ObjectStoreFactory, LakestreamError> {
+        self.get(scheme).ok_or_else(|| {
+            LakestreamError::ConfigError(format!(
+                "Unsupported object store type: {}://",
+                scheme
+            ))
+        })
+    }
+}
+
+impl Default for ObjectStoreRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn split_scheme(uri: &str) -> Result<(&str, &str), LakestreamError> {
+    uri.split_once("://").ok_or_else(|| {
+        LakestreamError::ConfigError(format!("No scheme in URI: {}", uri))
+    })
+}
+
+pub(crate) fn builtin_registry() -> &'static ObjectStoreRegistry {
+    static BUILTIN_REGISTRY: OnceLock<ObjectStoreRegistry> = OnceLock::new();
+
+    BUILTIN_REGISTRY.get_or_init(|| {
+        let mut registry = ObjectStoreRegistry::empty();
+        registry.register_backend::<S3Backend, _>("s3", |name, config| {
+            Ok(ObjectStore::S3Bucket(S3Bucket::new(name, config)?))
+        });
+        registry.register_wrapping_backend::<ArchiveBackend, _>(
+            "archive",
+            |name, config, registry| {
+                Ok(ObjectStore::ArchiveBucket(ArchiveBucket::new(
+                    name, config, registry,
+                )?))
+            },
+        );
+        for scheme in ["az", "abfs", "abfss"] {
+            registry.register_backend::<AzureBackend, _>(
+                scheme,
+                |name, config| {
+                    Ok(ObjectStore::AzureBucket(AzureBucket::new(
+                        name, config,
+                    )?))
+                },
+            );
+        }
+        // wraps another object store, e.g. "encrypted+s3://bucket-name"
+        registry.register_wrapping_backend::<EncryptedBackend, _>(
+            "encrypted",
+            |name, config, registry| {
+                let (scheme, _) = split_scheme(name)?;
+                let master_key = MasterKey::from_config(&config)?;
+                let inner = registry.create_object_store(name, config)?;
+                Ok(ObjectStore::Custom(CustomBucket::new(
+                    &format!("encrypted+{}", scheme),
+                    EncryptedStore::new(inner, master_key),
+                )))
+            },
+        );
+        for scheme in ["http", "https"] {
+            registry.register_backend::<HttpBackend, _>(
+                scheme,
+                move |name, config| {
+                    Ok(ObjectStore::HttpBucket(HttpBucket::new(
+                        name, scheme, config,
+                    )?))
+                },
+            );
+        }
+        registry.register_backend::<LocalFsBackend, _>(
+            "localfs",
+            |name, config| {
+                Ok(ObjectStore::LocalFsBucket(LocalFsBucket::new(
+                    name, config,
+                )?))
+            },
+        );
+        registry.register_backend::<MemoryBackend, _>(
+            "memory",
+            |name, config| {
+                Ok(ObjectStore::MemoryBucket(MemoryBucket::new(name, config)?))
+            },
+        );
+        registry
+    })
+}
@@ -0,0 +1,120 @@
+use std::collections::BTreeMap;
+
+use crate::FileObject;
+
+/// Size and number of the objects in and below a prefix.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Totals {
+    pub size: u64,
+    pub objects: u64,
+}
+
+/// Totals per prefix (virtual directory) of a listing, down to a depth
+/// below the listed prefix. Objects deeper down count towards the prefix
+/// at that depth. Memory grows with the number of prefixes, not with the
+/// number of objects.
+pub struct PrefixTotals {
+    // the listed prefix, prefixes are relative to it
+    base: String,
+    depth: Option<usize>,
+    totals: BTreeMap<String, Totals>,
+}
+
+impl PrefixTotals {
+    /// `depth` None counts every prefix.
+    pub fn new(base: &str, depth: Option<usize>) -> PrefixTotals {
+        PrefixTotals {
+            base: base.to_string(),
+            depth,
+            totals: BTreeMap::from([(String::new(), Totals::default())]),
+        }
+    }
+
+    /// Adds an object to the totals of its prefixes. Directory entries
+    /// (keys that end with '/') are not objects and are skipped.
+    pub fn add(&mut self, file_object: &FileObject) {
+        let key = file_object.name();
+        if key.ends_with('/') {
+            return;
+        }
+        let relative_key = key.strip_prefix(&self.base).unwrap_or(key);
+        let depth = self.depth.unwrap_or(usize::MAX);
+        let prefixes = std::iter::once("").chain(
+            relative_key
+                .match_indices('/')
+                .take(depth)
+                .map(|(index, _)| &relative_key[..=index]),
+        );
+        for prefix in prefixes {
+            // most objects are in a prefix that is already known
+            let totals = match self.totals.get_mut(prefix) {
+                Some(totals) => totals,
+                None => self.totals.entry(prefix.to_string()).or_default(),
+            };
+            totals.size += file_object.size();
+            totals.objects += 1;
+        }
+    }
+
+    /// Prefixes with their totals in lexicographic order, so a prefix comes
+    /// before the prefixes below it. The first is the listed prefix itself,
+    /// as empty string. Prefixes are relative to the listed prefix.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Totals)> {
+        self.totals
+            .iter()
+            .map(|(prefix, totals)| (prefix.as_str(), totals))
+    }
+}
+
+impl<'a> Extend<&'a FileObject> for PrefixTotals {
+    fn extend<T: IntoIterator<Item = &'a FileObject>>(&mut self, iter: T) {
+        for file_object in iter {
+            self.add(file_object);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prefix_totals() {
+        let file_objects = [
+            ("data/a.csv", 10),
+            ("data/year=2023/b.csv", 20),
+            ("data/year=2023/m=1/c.csv", 30),
+            ("data/year=2024/", 0),
+            ("data/year=2024/d.csv", 40),
+        ]
+        .map(|(name, size)| {
+            FileObject::new(name.to_string(), size, None, None)
+        });
+
+        let mut totals = PrefixTotals::new("data/", Some(1));
+        totals.extend(&file_objects);
+        let entries: Vec<(&str, u64, u64)> = totals
+            .iter()
+            .map(|(prefix, totals)| (prefix, totals.size, totals.objects))
+            .collect();
+        assert_eq!(
+            entries,
+            [("", 100, 4), ("year=2023/", 50, 2), ("year=2024/", 40, 1)]
+        );
+
+        let mut totals = PrefixTotals::new("", None);
+        totals.extend(&file_objects);
+        let prefixes: Vec<&str> =
+            totals.iter().map(|(prefix, _)| prefix).collect();
+        assert_eq!(
+            prefixes,
+            [
+                "",
+                "data/",
+                "data/year=2023/",
+                "data/year=2023/m=1/",
+                "data/year=2024/"
+            ]
+        );
+    }
+}
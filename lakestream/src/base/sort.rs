@@ -0,0 +1,174 @@
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+
+use crate::FileObject;
+
+/// What to sort file objects on. Ties are ordered by name.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SortKey {
+    Name,
+    Size,
+    /// modification time; objects without one (directories) come first
+    Mtime,
+}
+
+impl SortKey {
+    pub fn from_name(name: &str) -> Result<SortKey, String> {
+        match name {
+            "name" => Ok(SortKey::Name),
+            "size" => Ok(SortKey::Size),
+            "mtime" => Ok(SortKey::Mtime),
+            _ => Err(format!(
+                "Invalid sort key: '{}' (expected name, size or mtime)",
+                name
+            )),
+        }
+    }
+
+    pub fn compare(&self, a: &FileObject, b: &FileObject) -> Ordering {
+        let ordering = match self {
+            SortKey::Name => Ordering::Equal,
+            SortKey::Size => a.size().cmp(&b.size()),
+            SortKey::Mtime => a.modified().cmp(&b.modified()),
+        };
+        ordering.then_with(|| a.name().cmp(b.name()))
+    }
+
+    /// Sorts ascending, or descending with `reverse`.
+    pub fn sort(&self, file_objects: &mut [FileObject], reverse: bool) {
+        file_objects.sort_by(|a, b| {
+            let ordering = self.compare(a, b);
+            if reverse {
+                ordering.reverse()
+            } else {
+                ordering
+            }
+        });
+    }
+}
+
+/// The `limit` greatest file objects by a sort key, e.g. the 20 largest or
+/// newest. Only that many are kept (in a heap), however long the listing.
+/// Directories are left out.
+pub struct TopFileObjects {
+    key: SortKey,
+    limit: usize,
+    // min-heap, so the smallest of the kept objects is replaced first
+    heap: BinaryHeap<Reverse<Ranked>>,
+}
+
+// a file object ordered by a sort key
+struct Ranked {
+    key: SortKey,
+    file_object: FileObject,
+}
+
+impl Ord for Ranked {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key.compare(&self.file_object, &other.file_object)
+    }
+}
+
+impl PartialOrd for Ranked {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Ranked {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Ranked {}
+
+impl TopFileObjects {
+    pub fn new(key: SortKey, limit: usize) -> TopFileObjects {
+        TopFileObjects {
+            key,
+            limit,
+            // the limit comes from the user, so only what is pushed
+            heap: BinaryHeap::new(),
+        }
+    }
+
+    pub fn push(&mut self, file_object: FileObject) {
+        if self.limit == 0 || file_object.name().ends_with('/') {
+            return;
+        }
+        let ranked = Ranked {
+            key: self.key,
+            file_object,
+        };
+        if self.heap.len() < self.limit {
+            self.heap.push(Reverse(ranked));
+        } else if let Some(mut smallest) = self.heap.peek_mut() {
+            if ranked > smallest.0 {
+                *smallest = Reverse(ranked);
+            }
+        }
+    }
+
+    /// The kept file objects, greatest first.
+    pub fn into_sorted_vec(self) -> Vec<FileObject> {
+        // ascending order of Reverse is descending order of the objects
+        self.heap
+            .into_sorted_vec()
+            .into_iter()
+            .map(|Reverse(ranked)| ranked.file_object)
+            .collect()
+    }
+}
+
+impl Extend<FileObject> for TopFileObjects {
+    fn extend<T: IntoIterator<Item = FileObject>>(&mut self, iter: T) {
+        for file_object in iter {
+            self.push(file_object);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(file_objects: &[FileObject]) -> Vec<&str> {
+        file_objects
+            .iter()
+            .map(|file_object| file_object.name())
+            .collect()
+    }
+
+    #[test]
+    fn test_sort_and_top_file_objects() {
+        let file_objects = vec![
+            FileObject::new("b".to_string(), 30, Some(100), None),
+            FileObject::new("a".to_string(), 10, Some(300), None),
+            FileObject::new("dir/".to_string(), 0, None, None),
+            FileObject::new("d".to_string(), 30, Some(200), None),
+            FileObject::new("c".to_string(), 20, Some(400), None),
+        ];
+
+        let mut sorted = file_objects.clone();
+        SortKey::Size.sort(&mut sorted, false);
+        assert_eq!(names(&sorted), ["dir/", "a", "c", "b", "d"]);
+        SortKey::Mtime.sort(&mut sorted, true);
+        assert_eq!(names(&sorted), ["c", "a", "d", "b", "dir/"]);
+        SortKey::Name.sort(&mut sorted, false);
+        assert_eq!(names(&sorted), ["a", "b", "c", "d", "dir/"]);
+
+        let mut largest = TopFileObjects::new(SortKey::Size, 3);
+        largest.extend(file_objects.clone());
+        assert_eq!(names(&largest.into_sorted_vec()), ["d", "b", "c"]);
+
+        let mut newest = TopFileObjects::new(SortKey::Mtime, 10);
+        newest.extend(file_objects.clone());
+        assert_eq!(names(&newest.into_sorted_vec()), ["c", "a", "d", "b"]);
+
+        // nothing is allocated up front for the limit
+        let mut all = TopFileObjects::new(SortKey::Name, usize::MAX);
+        all.extend(file_objects);
+        assert_eq!(names(&all.into_sorted_vec()), ["d", "c", "b", "a"]);
+    }
+}
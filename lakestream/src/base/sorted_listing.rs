@@ -0,0 +1,97 @@
+use std::collections::{BTreeMap, VecDeque};
+
+use crate::{FileObject, FileObjectFilter, FileObjectVec, LakestreamError};
+
+/// Lists the objects of a backend that keeps its keys in a sorted map, one
+/// directory level at a time, with `list_prefix` for a single level. Like
+/// S3, directories (prefixes) are listed after the objects of a level, and
+/// excluded directories are not descended into.
+pub async fn list_files<F>(
+    prefix: Option<&str>,
+    recursive: bool,
+    max_keys: Option<u32>,
+    filter: &Option<FileObjectFilter>,
+    file_objects: &mut FileObjectVec,
+    mut list_prefix: F,
+) -> Result<(), LakestreamError>
+where
+    F: FnMut(&str) -> Result<Vec<FileObject>, LakestreamError>,
+{
+    let max_keys = max_keys.map(|keys| keys as usize);
+    let mut directory_stack = VecDeque::new();
+    directory_stack.push_back(prefix.unwrap_or_default().to_owned());
+
+    while let Some(prefix) = directory_stack.pop_front() {
+        let mut temp_file_objects = Vec::new();
+
+        for file_object in list_prefix(&prefix)? {
+            if file_object.name().ends_with('/') {
+                // excluded prefixes are not descended into
+                if filter.as_ref().is_some_and(|filter| {
+                    filter.excludes_directory(file_object.name())
+                }) {
+                    continue;
+                }
+                if recursive {
+                    directory_stack.push_back(file_object.name().to_owned());
+                }
+                if filter.is_none() {
+                    temp_file_objects.push(file_object);
+                }
+            } else if filter
+                .as_ref()
+                .is_none_or(|filter| filter.matches(&file_object))
+            {
+                temp_file_objects.push(file_object);
+            }
+        }
+
+        if let Some(max_keys) = max_keys {
+            temp_file_objects
+                .truncate(max_keys.saturating_sub(file_objects.len()));
+        }
+        file_objects.extend_async(temp_file_objects).await;
+
+        if max_keys.is_some_and(|max_keys| file_objects.len() >= max_keys) {
+            break;
+        }
+    }
+    Ok(())
+}
+
+/// Single level listing of a sorted map with "/" as delimiter. Keys below
+/// the next delimiter are rolled up into a directory (common prefix), the
+/// other entries are made into file objects by `file_object`.
+pub fn list_prefix<V>(
+    entries: &BTreeMap<String, V>,
+    prefix: &str,
+    file_object: impl Fn(&String, &V) -> FileObject,
+) -> Vec<FileObject> {
+    let mut file_objects: Vec<FileObject> = Vec::new();
+    let mut directories: Vec<FileObject> = Vec::new();
+
+    for (key, value) in entries.range(prefix.to_owned()..) {
+        let Some(remainder) = key.strip_prefix(prefix) else {
+            break;
+        };
+        match remainder.find('/') {
+            Some(index) => {
+                let directory = &key[..prefix.len() + index + 1];
+                if directories
+                    .last()
+                    .is_none_or(|last| last.name() != directory)
+                {
+                    directories.push(FileObject::new(
+                        directory.to_owned(),
+                        0,
+                        None,
+                        None,
+                    ));
+                }
+            }
+            None => file_objects.push(file_object(key, value)),
+        }
+    }
+    file_objects.extend(directories);
+    file_objects
+}
@@ -0,0 +1,238 @@
+use std::fs::{self, File};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::SystemTime;
+use std::{env, mem, process};
+
+use log::{info, warn};
+use sha2::{Digest, Sha256};
+
+use crate::base::filters::parse_bytes;
+use crate::default::DEFAULT_CACHE_MAX_SIZE;
+use crate::utils::blocking::spawn_blocking;
+use crate::{ConditionalGet, Config, LakestreamError, ObjectStore};
+
+const CACHE_DIR_KEY: &str = "LAKESTREAM_CACHE_DIR";
+const CACHE_MAX_SIZE_KEY: &str = "LAKESTREAM_CACHE_MAX_SIZE";
+
+// Read-through cache of objects on disk. Each entry is a single file, named
+// after a hash of the bucket URI and the key, that holds the ETag of the
+// object (prefixed with its length as 4 bytes big endian) followed by the
+// data, so the two are always replaced together. The ETag is used to
+// revalidate the entry via a conditional GET. Entries are evicted least
+// recently used first, based on the mtime of the file which is updated on
+// every hit. File I/O runs on the blocking thread pool.
+#[derive(Clone)]
+pub struct DiskCache {
+    dir: PathBuf,
+    max_size: u64,
+}
+
+impl DiskCache {
+    // cache as configured via LAKESTREAM_CACHE_DIR and
+    // LAKESTREAM_CACHE_MAX_SIZE, in the config or environment
+    pub fn from_config(
+        config: &Config,
+    ) -> Result<Option<DiskCache>, LakestreamError> {
+        let setting =
+            |key: &str| config.get(key).cloned().or_else(|| env::var(key).ok());
+
+        let Some(dir) = setting(CACHE_DIR_KEY).filter(|dir| !dir.is_empty())
+        else {
+            return Ok(None);
+        };
+        let max_size = match setting(CACHE_MAX_SIZE_KEY) {
+            Some(max_size) => parse_bytes(max_size.trim()).map_err(|_| {
+                LakestreamError::ConfigError(format!(
+                    "Invalid {}: '{}'",
+                    CACHE_MAX_SIZE_KEY, max_size
+                ))
+            })?,
+            None => DEFAULT_CACHE_MAX_SIZE,
+        };
+        Ok(Some(DiskCache::new(Path::new(&dir), max_size)))
+    }
+
+    pub fn new(dir: &Path, max_size: u64) -> DiskCache {
+        DiskCache {
+            dir: dir.to_path_buf(),
+            max_size,
+        }
+    }
+
+    pub async fn get_object(
+        &self,
+        object_store: &ObjectStore,
+        key: &str,
+        data: &mut Vec<u8>,
+    ) -> Result<(), LakestreamError> {
+        let entry_path = self.entry_path(&object_store.println_path(), key);
+
+        let cached_etag = {
+            let path = entry_path.clone();
+            spawn_blocking(move || read_etag(&path)).await.ok()
+        };
+
+        let mut result = object_store
+            .get_object_if_none_match(key, cached_etag.as_deref(), data)
+            .await?;
+
+        if result == ConditionalGet::NotModified {
+            info!("Cache hit for: {}", key);
+            let path = entry_path.clone();
+            match spawn_blocking(move || read_entry(&path)).await {
+                Ok((etag, cached)) if Some(&etag) == cached_etag.as_ref() => {
+                    *data = cached;
+                    return Ok(());
+                }
+                Ok(_) => info!("Cache entry was replaced for: {}", key),
+                // entry was removed in between
+                Err(err) => warn!("Failed to read cache entry: {}", err),
+            }
+            // fetch it again
+            result = object_store
+                .get_object_if_none_match(key, None, data)
+                .await?;
+        }
+
+        let cache = self.clone();
+        // the data is moved to the blocking thread and back
+        let entry_data = mem::take(data);
+        *data = spawn_blocking(move || {
+            match result {
+                ConditionalGet::Modified(Some(etag)) => {
+                    if let Err(err) =
+                        cache.store_entry(&entry_path, &etag, &entry_data)
+                    {
+                        warn!("Failed to write cache entry: {}", err);
+                        let _ = fs::remove_file(&entry_path);
+                    }
+                }
+                // nothing to revalidate with, so not cacheable
+                _ => {
+                    let _ = fs::remove_file(&entry_path);
+                }
+            }
+            Ok(entry_data)
+        })
+        .await?;
+        Ok(())
+    }
+
+    fn entry_path(&self, bucket_uri: &str, key: &str) -> PathBuf {
+        let mut hasher = Sha256::new();
+        hasher.update(bucket_uri.as_bytes());
+        hasher.update(b"\n");
+        hasher.update(key.as_bytes());
+        self.dir
+            .join(hex::encode(hasher.finalize()))
+            .with_extension("entry")
+    }
+
+    fn store_entry(
+        &self,
+        entry_path: &Path,
+        etag: &str,
+        data: &[u8],
+    ) -> io::Result<()> {
+        let etag_length = u32::try_from(etag.len()).map_err(|_| {
+            io::Error::new(io::ErrorKind::InvalidInput, "ETag is too long")
+        })?;
+        let header = [&etag_length.to_be_bytes(), etag.as_bytes()].concat();
+        if (header.len() + data.len()) as u64 > self.max_size {
+            let _ = fs::remove_file(entry_path);
+            return Ok(());
+        }
+        fs::create_dir_all(&self.dir)?;
+        write_file(entry_path, &[&header, data])?;
+        self.evict(entry_path)
+    }
+
+    // remove the least recently used entries until the cache fits in
+    // max_size, except for the entry that was just written
+    fn evict(&self, keep: &Path) -> io::Result<()> {
+        let mut entries: Vec<(SystemTime, u64, PathBuf)> = Vec::new();
+        for dir_entry in fs::read_dir(&self.dir)? {
+            let path = dir_entry?.path();
+            if path
+                .extension()
+                .is_none_or(|extension| extension != "entry")
+            {
+                continue;
+            }
+            let metadata = fs::metadata(&path)?;
+            entries.push((metadata.modified()?, metadata.len(), path));
+        }
+
+        let mut total_size: u64 = entries.iter().map(|entry| entry.1).sum();
+        if total_size <= self.max_size {
+            return Ok(());
+        }
+
+        entries.sort();
+        for (_, size, path) in entries {
+            if total_size <= self.max_size {
+                break;
+            }
+            if path == keep {
+                continue;
+            }
+            info!("Evicting cache entry: {}", path.display());
+            let _ = fs::remove_file(&path);
+            total_size -= size;
+        }
+        Ok(())
+    }
+}
+
+// the ETag of an entry, without reading its data
+fn read_etag(entry_path: &Path) -> io::Result<String> {
+    read_header(&mut File::open(entry_path)?)
+}
+
+// the ETag and data of an entry
+fn read_entry(entry_path: &Path) -> io::Result<(String, Vec<u8>)> {
+    let mut file = File::open(entry_path)?;
+    let etag = read_header(&mut file)?;
+    let mut data = Vec::new();
+    file.read_to_end(&mut data)?;
+    // mark as recently used for the LRU eviction
+    File::options()
+        .write(true)
+        .open(entry_path)?
+        .set_modified(SystemTime::now())?;
+    Ok((etag, data))
+}
+
+fn read_header(file: &mut File) -> io::Result<String> {
+    let mut etag_length = [0; 4];
+    file.read_exact(&mut etag_length)?;
+    let mut etag = vec![0; u32::from_be_bytes(etag_length) as usize];
+    file.read_exact(&mut etag)?;
+    String::from_utf8(etag)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
+
+// writes to a temporary file first so readers never see partial data. The
+// name is unique, so concurrent writers of an entry do not mix their data.
+fn write_file(path: &Path, parts: &[&[u8]]) -> io::Result<()> {
+    static TEMP_FILES: AtomicU64 = AtomicU64::new(0);
+    let temp_path = path.with_extension(format!(
+        "{}-{}.tmp",
+        process::id(),
+        TEMP_FILES.fetch_add(1, Ordering::Relaxed)
+    ));
+    let result = File::create(&temp_path)
+        .and_then(|mut file| {
+            for part in parts {
+                file.write_all(part)?;
+            }
+            file.sync_all()
+        })
+        .and_then(|()| fs::rename(&temp_path, path));
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+    result
+}
@@ -0,0 +1 @@
+pub mod disk_cache;
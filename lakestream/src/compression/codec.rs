@@ -0,0 +1,111 @@
+use std::io::Write;
+
+use bzip2::write::BzEncoder;
+use flate2::write::GzEncoder;
+use ruzstd::encoding::{compress_to_vec, CompressionLevel};
+
+use super::decoder::Decoder;
+use crate::LakestreamError;
+
+/// Compression format of an object.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Codec {
+    Gzip,
+    Zstd,
+    Bzip2,
+}
+
+const CODEC_EXTENSIONS: [(&str, Codec); 3] = [
+    (".gz", Codec::Gzip),
+    (".zst", Codec::Zstd),
+    (".bz2", Codec::Bzip2),
+];
+
+impl Codec {
+    pub fn from_name(name: &str) -> Result<Codec, LakestreamError> {
+        match name.to_lowercase().as_str() {
+            "gzip" | "gz" => Ok(Codec::Gzip),
+            "zstd" | "zst" => Ok(Codec::Zstd),
+            "bzip2" | "bz2" => Ok(Codec::Bzip2),
+            _ => Err(LakestreamError::ConfigError(format!(
+                "Unsupported compression: {} (expected gzip, zstd or bzip2)",
+                name
+            ))),
+        }
+    }
+
+    pub fn from_extension(key: &str) -> Option<Codec> {
+        let key = key.to_lowercase();
+        CODEC_EXTENSIONS
+            .iter()
+            .find(|(extension, _)| key.ends_with(extension))
+            .map(|(_, codec)| *codec)
+    }
+
+    pub fn from_content_encoding(content_encoding: &str) -> Option<Codec> {
+        match content_encoding.trim().to_lowercase().as_str() {
+            "gzip" | "x-gzip" => Some(Codec::Gzip),
+            "zstd" => Some(Codec::Zstd),
+            "bzip2" | "x-bzip2" => Some(Codec::Bzip2),
+            _ => None,
+        }
+    }
+
+    pub fn from_magic(data: &[u8]) -> Option<Codec> {
+        if data.starts_with(&[0x1f, 0x8b]) {
+            Some(Codec::Gzip)
+        } else if data.starts_with(&[0x28, 0xb5, 0x2f, 0xfd]) {
+            Some(Codec::Zstd)
+        } else if data.starts_with(b"BZh") {
+            Some(Codec::Bzip2)
+        } else {
+            None
+        }
+    }
+
+    /// Codec of an object, from its Content-Encoding or else its key
+    /// extension. The magic bytes of the data are checked last, so objects
+    /// that were cached or stored without either are still recognized.
+    pub fn detect(
+        key: &str,
+        content_encoding: Option<&str>,
+        data: &[u8],
+    ) -> Option<Codec> {
+        content_encoding
+            .and_then(Codec::from_content_encoding)
+            .or_else(|| Codec::from_extension(key))
+            .or_else(|| Codec::from_magic(data))
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Codec::Gzip => "gzip",
+            Codec::Zstd => "zstd",
+            Codec::Bzip2 => "bzip2",
+        }
+    }
+
+    pub fn compress(&self, data: &[u8]) -> Result<Vec<u8>, LakestreamError> {
+        match self {
+            Codec::Gzip => {
+                let mut encoder =
+                    GzEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder.write_all(data)?;
+                Ok(encoder.finish()?)
+            }
+            Codec::Zstd => Ok(compress_to_vec(data, CompressionLevel::Fastest)),
+            Codec::Bzip2 => {
+                let mut encoder =
+                    BzEncoder::new(Vec::new(), bzip2::Compression::default());
+                encoder.write_all(data)?;
+                Ok(encoder.finish()?)
+            }
+        }
+    }
+
+    pub fn decompress(&self, data: &[u8]) -> Result<Vec<u8>, LakestreamError> {
+        let mut decoder = Decoder::new(*self, Vec::new());
+        decoder.write_all(data)?;
+        Ok(decoder.finish()?)
+    }
+}
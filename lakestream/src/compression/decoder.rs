@@ -0,0 +1,325 @@
+use std::io::{self, Write};
+
+use bzip2::{Decompress, Status};
+use flate2::write::MultiGzDecoder;
+use ruzstd::decoding::FrameDecoder;
+
+use super::codec::Codec;
+
+// output of a single decode step
+const OUTPUT_CHUNK_SIZE: usize = 64 * 1024;
+// the longest magic bytes of a codec
+const MAGIC_SIZE: usize = 4;
+// a zstd frame header is at most 18 bytes
+const MAX_ZSTD_FRAME_HEADER_SIZE: usize = 18;
+
+/// Decompresses data that is written to it, chunk by chunk, into a writer.
+/// Only the compressed data that does not make a complete block yet is
+/// held, so objects are decoded while they arrive. Concatenated streams
+/// (frames for zstd) are decoded one after the other.
+pub struct Decoder<W: Write> {
+    codec: Codec,
+    inner: Inner<W>,
+}
+
+enum Inner<W: Write> {
+    Gzip(MultiGzDecoder<W>),
+    Zstd(ZstdDecoder<W>),
+    Bzip2(Bzip2Decoder<W>),
+}
+
+impl<W: Write> Decoder<W> {
+    pub fn new(codec: Codec, writer: W) -> Decoder<W> {
+        let inner = match codec {
+            Codec::Gzip => Inner::Gzip(MultiGzDecoder::new(writer)),
+            Codec::Zstd => Inner::Zstd(ZstdDecoder {
+                writer,
+                frame: None,
+                input: Vec::new(),
+                output: vec![0; OUTPUT_CHUNK_SIZE],
+            }),
+            Codec::Bzip2 => Inner::Bzip2(Bzip2Decoder {
+                writer,
+                stream: None,
+                output: vec![0; OUTPUT_CHUNK_SIZE],
+            }),
+        };
+        Decoder { codec, inner }
+    }
+
+    /// Decodes what is left, and fails if the data ends within a stream.
+    pub fn finish(self) -> io::Result<W> {
+        let result = match self.inner {
+            Inner::Gzip(decoder) => decoder.finish(),
+            Inner::Zstd(decoder) => decoder.finish(),
+            Inner::Bzip2(decoder) => decoder.finish(),
+        };
+        result.map_err(|err| decode_error(self.codec, err))
+    }
+}
+
+impl<W: Write> Write for Decoder<W> {
+    fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+        let result = match &mut self.inner {
+            Inner::Gzip(decoder) => decoder.write(data),
+            Inner::Zstd(decoder) => {
+                decoder.decode(data, false).map(|_| data.len())
+            }
+            Inner::Bzip2(decoder) => decoder.decode(data).map(|_| data.len()),
+        };
+        result.map_err(|err| decode_error(self.codec, err))
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match &mut self.inner {
+            Inner::Gzip(decoder) => decoder.flush(),
+            Inner::Zstd(decoder) => decoder.writer.flush(),
+            Inner::Bzip2(decoder) => decoder.writer.flush(),
+        }
+    }
+}
+
+/// Decompresses with a codec that is known up front (e.g. from the key
+/// extension), or else with the codec recognized from the first bytes that
+/// are written to it. Data that is not compressed is passed on as is.
+pub struct DetectingDecoder<W: Write> {
+    state: State<W>,
+}
+
+enum State<W: Write> {
+    // the first bytes, until there are enough to recognize a codec
+    Start(Vec<u8>, W),
+    Plain(W),
+    Compressed(Decoder<W>),
+    // only while switching between states
+    Switching,
+}
+
+impl<W: Write> DetectingDecoder<W> {
+    pub fn new(codec: Option<Codec>, writer: W) -> DetectingDecoder<W> {
+        let state = match codec {
+            Some(codec) => State::Compressed(Decoder::new(codec, writer)),
+            None => State::Start(Vec::new(), writer),
+        };
+        DetectingDecoder { state }
+    }
+
+    /// Decodes what is left, and fails if the data ends within a stream.
+    pub fn finish(mut self) -> io::Result<W> {
+        self.detect_codec()?;
+        match self.state {
+            State::Plain(writer) => Ok(writer),
+            State::Compressed(decoder) => decoder.finish(),
+            State::Start(..) | State::Switching => unreachable!(),
+        }
+    }
+
+    // moves on from the start, with the bytes that are held so far
+    fn detect_codec(&mut self) -> io::Result<()> {
+        if !matches!(self.state, State::Start(..)) {
+            return Ok(());
+        }
+        let State::Start(head, writer) =
+            std::mem::replace(&mut self.state, State::Switching)
+        else {
+            unreachable!()
+        };
+        self.state = match Codec::from_magic(&head) {
+            Some(codec) => State::Compressed(Decoder::new(codec, writer)),
+            None => State::Plain(writer),
+        };
+        self.write_all(&head)
+    }
+}
+
+impl<W: Write> Write for DetectingDecoder<W> {
+    fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+        match &mut self.state {
+            State::Start(head, _) => {
+                head.extend_from_slice(data);
+                if head.len() >= MAGIC_SIZE {
+                    self.detect_codec()?;
+                }
+                Ok(data.len())
+            }
+            State::Plain(writer) => writer.write(data),
+            State::Compressed(decoder) => decoder.write(data),
+            State::Switching => unreachable!(),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match &mut self.state {
+            State::Start(_, writer) | State::Plain(writer) => writer.flush(),
+            State::Compressed(decoder) => decoder.flush(),
+            State::Switching => unreachable!(),
+        }
+    }
+}
+
+// errors of the underlying writer are passed on as well
+fn decode_error(codec: Codec, err: io::Error) -> io::Error {
+    io::Error::new(
+        err.kind(),
+        format!("Failed to decompress {} data: {}", codec.name(), err),
+    )
+}
+
+fn invalid_data(err: impl ToString) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, err.to_string())
+}
+
+fn unexpected_end() -> io::Error {
+    io::Error::new(io::ErrorKind::UnexpectedEof, "data ends within a stream")
+}
+
+struct ZstdDecoder<W: Write> {
+    writer: W,
+    // None between frames
+    frame: Option<Box<FrameDecoder>>,
+    // start of a block that is not complete yet
+    input: Vec<u8>,
+    output: Vec<u8>,
+}
+
+impl<W: Write> ZstdDecoder<W> {
+    // at the end of the data, incomplete headers and blocks are errors
+    fn decode(&mut self, data: &[u8], at_end: bool) -> io::Result<()> {
+        self.input.extend_from_slice(data);
+        loop {
+            let frame = match &mut self.frame {
+                Some(frame) => frame,
+                None if self.input.is_empty() => return Ok(()),
+                None => {
+                    let mut source = self.input.as_slice();
+                    let mut frame = Box::new(FrameDecoder::new());
+                    match frame.init(&mut source) {
+                        Ok(()) => {
+                            let header_size = self.input.len() - source.len();
+                            self.input.drain(..header_size);
+                            self.frame.insert(frame)
+                        }
+                        // the rest of the header is still to come
+                        Err(_)
+                            if !at_end
+                                && self.input.len()
+                                    < MAX_ZSTD_FRAME_HEADER_SIZE =>
+                        {
+                            return Ok(())
+                        }
+                        Err(err) => return Err(invalid_data(err)),
+                    }
+                }
+            };
+            // a frame that is decoded up to its checksum reports 4 bytes
+            // read, even if there are fewer
+            if self.input.len() < 4 && !at_end && !frame.is_finished() {
+                return Ok(());
+            }
+            let (read, written) = frame
+                .decode_from_to(&self.input, &mut self.output)
+                .map_err(invalid_data)?;
+            if read > self.input.len() {
+                return Err(unexpected_end());
+            }
+            self.input.drain(..read);
+            self.writer.write_all(&self.output[..written])?;
+            if frame.is_finished() && frame.can_collect() == 0 {
+                self.frame = None;
+            } else if read == 0 && written == 0 {
+                return Ok(());
+            }
+        }
+    }
+
+    fn finish(mut self) -> io::Result<W> {
+        self.decode(&[], true)?;
+        if self.frame.is_some() || !self.input.is_empty() {
+            return Err(unexpected_end());
+        }
+        Ok(self.writer)
+    }
+}
+
+struct Bzip2Decoder<W: Write> {
+    writer: W,
+    // None between streams
+    stream: Option<Decompress>,
+    output: Vec<u8>,
+}
+
+impl<W: Write> Bzip2Decoder<W> {
+    fn decode(&mut self, mut data: &[u8]) -> io::Result<()> {
+        loop {
+            let stream = match &mut self.stream {
+                Some(stream) => stream,
+                None if data.is_empty() => return Ok(()),
+                None => self.stream.insert(Decompress::new(false)),
+            };
+            let (total_in, total_out) = (stream.total_in(), stream.total_out());
+            let status = stream
+                .decompress(data, &mut self.output)
+                .map_err(invalid_data)?;
+            let read = (stream.total_in() - total_in) as usize;
+            let written = (stream.total_out() - total_out) as usize;
+            data = &data[read..];
+            self.writer.write_all(&self.output[..written])?;
+            if status == Status::StreamEnd {
+                self.stream = None;
+            } else if read == 0 && written == 0 {
+                return Ok(());
+            }
+        }
+    }
+
+    fn finish(self) -> io::Result<W> {
+        if self.stream.is_some() {
+            return Err(unexpected_end());
+        }
+        Ok(self.writer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_in_chunks() {
+        let data: Vec<u8> = (0..20_000)
+            .flat_map(|line| format!("line {}\n", line).into_bytes())
+            .collect();
+        for codec in [Codec::Gzip, Codec::Zstd, Codec::Bzip2] {
+            // two concatenated streams
+            let mut compressed = codec.compress(&data).unwrap();
+            compressed.extend(codec.compress(b"last line\n").unwrap());
+
+            let mut decoder = Decoder::new(codec, Vec::new());
+            for chunk in compressed.chunks(7) {
+                decoder.write_all(chunk).unwrap();
+            }
+            let decoded = decoder.finish().unwrap();
+            assert_eq!(decoded.len(), data.len() + 10, "{}", codec.name());
+            assert!(decoded.starts_with(&data));
+
+            let mut decoder = Decoder::new(codec, Vec::new());
+            let _ = decoder.write_all(&compressed[..compressed.len() / 2]);
+            assert!(decoder.finish().is_err(), "{}", codec.name());
+
+            // recognized from the first bytes
+            let mut decoder = DetectingDecoder::new(None, Vec::new());
+            for chunk in compressed.chunks(1) {
+                decoder.write_all(chunk).unwrap();
+            }
+            let decoded = decoder.finish().unwrap();
+            assert_eq!(decoded.len(), data.len() + 10, "{}", codec.name());
+        }
+
+        // data that is not compressed, or too short to tell
+        for data in [&b"plain text"[..], b"ab", b""] {
+            let mut decoder = DetectingDecoder::new(None, Vec::new());
+            decoder.write_all(data).unwrap();
+            assert_eq!(decoder.finish().unwrap(), data);
+        }
+    }
+}
@@ -1,2 +1,4 @@
 pub const AWS_DEFAULT_REGION: &str = "us-east-1";
 pub const AWS_MAX_LIST_OBJECTS: u32 = 1000;
+pub const AZURE_MAX_LIST_OBJECTS: u32 = 5000;
+pub const DEFAULT_CACHE_MAX_SIZE: u64 = 1024 * 1024 * 1024;
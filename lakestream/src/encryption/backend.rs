@@ -0,0 +1,25 @@
+use async_trait::async_trait;
+
+pub use super::key::MasterKey;
+pub use super::store::EncryptedStore;
+use crate::{Config, LakestreamError, ObjectStoreBackend, ObjectStoreVec};
+
+pub struct EncryptedBackend;
+
+#[async_trait(?Send)]
+impl ObjectStoreBackend for EncryptedBackend {
+    fn new(_config: Config) -> Result<Self, LakestreamError> {
+        Ok(Self)
+    }
+
+    async fn list_buckets(
+        _config: Config,
+        _object_stores: &mut ObjectStoreVec,
+    ) -> Result<(), LakestreamError> {
+        Err(LakestreamError::ConfigError(
+            "Listing buckets is not supported for encrypted stores, list \
+             the buckets of the underlying object store instead"
+                .to_string(),
+        ))
+    }
+}
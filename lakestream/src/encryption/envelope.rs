@@ -0,0 +1,175 @@
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+
+use super::key::MasterKey;
+use crate::LakestreamError;
+
+const OBJECT_MAGIC: &[u8; 4] = b"LSE1";
+const SIDECAR_MAGIC: &[u8; 4] = b"LSK1";
+const NONCE_LENGTH: usize = 12;
+const TAG_LENGTH: usize = 16;
+const DATA_KEY_LENGTH: usize = 32;
+// version (hex nonce of the object) || wrapped data key
+const SIDECAR_ENTRY_LENGTH: usize = 2 * NONCE_LENGTH
+    + SIDECAR_MAGIC.len()
+    + NONCE_LENGTH
+    + DATA_KEY_LENGTH
+    + TAG_LENGTH;
+
+// bytes that an encrypted object is larger than its plaintext
+pub const ENVELOPE_OVERHEAD: u64 =
+    (OBJECT_MAGIC.len() + NONCE_LENGTH + TAG_LENGTH) as u64;
+
+// An object is encrypted with a fresh data key. The data key is wrapped
+// (encrypted) with the master key and stored in the sidecar. Both use the
+// object key as associated data, so objects and sidecars can not be swapped.
+pub struct Envelope {
+    pub object: Vec<u8>,
+    pub wrapped_key: Vec<u8>,
+}
+
+pub fn seal(
+    master_key: &MasterKey,
+    key: &str,
+    plaintext: &[u8],
+) -> Result<Envelope, LakestreamError> {
+    let data_key = Aes256Gcm::generate_key(&mut OsRng);
+    let object = encrypt(&data_key, OBJECT_MAGIC, key, plaintext)?;
+
+    let master_key = Key::<Aes256Gcm>::from_slice(master_key.as_bytes());
+    let wrapped_key = encrypt(master_key, SIDECAR_MAGIC, key, &data_key)?;
+
+    Ok(Envelope {
+        object,
+        wrapped_key,
+    })
+}
+
+pub fn open(
+    master_key: &MasterKey,
+    key: &str,
+    object: &[u8],
+    sidecar: &[u8],
+) -> Result<Vec<u8>, LakestreamError> {
+    let version = object_version(key, object)?;
+    let wrapped_key = sidecar
+        .chunks_exact(SIDECAR_ENTRY_LENGTH)
+        .find(|entry| entry.starts_with(version.as_bytes()))
+        .map(|entry| &entry[version.len()..])
+        .ok_or_else(|| {
+            LakestreamError::InternalError(format!(
+                "No data key for the stored version of {}",
+                key
+            ))
+        })?;
+
+    let master_key = Key::<Aes256Gcm>::from_slice(master_key.as_bytes());
+    let data_key = decrypt(master_key, SIDECAR_MAGIC, key, wrapped_key)?;
+    if data_key.len() != DATA_KEY_LENGTH {
+        return Err(decrypt_error(key));
+    }
+    decrypt(
+        Key::<Aes256Gcm>::from_slice(&data_key),
+        OBJECT_MAGIC,
+        key,
+        object,
+    )
+}
+
+/// Sidecar with the wrapped data key of a new version of an object, in
+/// front of the keys of the `kept - 1` most recent versions in `sidecar`.
+pub fn add_to_sidecar(
+    sidecar: &[u8],
+    envelope: &Envelope,
+    key: &str,
+    kept: usize,
+) -> Result<Vec<u8>, LakestreamError> {
+    let version = object_version(key, &envelope.object)?;
+    let mut entries =
+        vec![[version.as_bytes(), &envelope.wrapped_key].concat()];
+    entries.extend(
+        sidecar
+            .chunks_exact(SIDECAR_ENTRY_LENGTH)
+            .take(kept.saturating_sub(1))
+            .map(<[u8]>::to_vec),
+    );
+    Ok(entries.concat())
+}
+
+/// Version of an encrypted object: its (random) nonce, as hex. Each put
+/// makes a new version.
+pub fn object_version(
+    key: &str,
+    object: &[u8],
+) -> Result<String, LakestreamError> {
+    let header_length = OBJECT_MAGIC.len() + NONCE_LENGTH;
+    if object.len() < header_length + TAG_LENGTH
+        || !object.starts_with(OBJECT_MAGIC)
+    {
+        return Err(not_encrypted_error(key));
+    }
+    Ok(hex::encode(&object[OBJECT_MAGIC.len()..header_length]))
+}
+
+// magic || nonce || ciphertext (including the tag)
+fn encrypt(
+    cipher_key: &Key<Aes256Gcm>,
+    magic: &[u8; 4],
+    key: &str,
+    plaintext: &[u8],
+) -> Result<Vec<u8>, LakestreamError> {
+    let cipher = Aes256Gcm::new(cipher_key);
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(
+            &nonce,
+            Payload {
+                msg: plaintext,
+                aad: key.as_bytes(),
+            },
+        )
+        .map_err(|_| {
+            LakestreamError::InternalError(format!("Failed to encrypt {}", key))
+        })?;
+
+    let mut sealed =
+        Vec::with_capacity(magic.len() + NONCE_LENGTH + ciphertext.len());
+    sealed.extend_from_slice(magic);
+    sealed.extend_from_slice(&nonce);
+    sealed.extend_from_slice(&ciphertext);
+    Ok(sealed)
+}
+
+fn decrypt(
+    cipher_key: &Key<Aes256Gcm>,
+    magic: &[u8; 4],
+    key: &str,
+    sealed: &[u8],
+) -> Result<Vec<u8>, LakestreamError> {
+    let header_length = magic.len() + NONCE_LENGTH;
+    if sealed.len() < header_length + TAG_LENGTH || !sealed.starts_with(magic) {
+        return Err(not_encrypted_error(key));
+    }
+    let nonce = Nonce::from_slice(&sealed[magic.len()..header_length]);
+
+    Aes256Gcm::new(cipher_key)
+        .decrypt(
+            nonce,
+            Payload {
+                msg: &sealed[header_length..],
+                aad: key.as_bytes(),
+            },
+        )
+        .map_err(|_| decrypt_error(key))
+}
+
+fn not_encrypted_error(key: &str) -> LakestreamError {
+    LakestreamError::InternalError(format!("Not an encrypted object: {}", key))
+}
+
+fn decrypt_error(key: &str) -> LakestreamError {
+    LakestreamError::InternalError(format!(
+        "Failed to decrypt {}: wrong key or corrupted object",
+        key
+    ))
+}
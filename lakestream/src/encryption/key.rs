@@ -0,0 +1,78 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+
+use crate::{Config, LakestreamError};
+
+const KEYFILE_KEY: &str = "LAKESTREAM_ENCRYPTION_KEYFILE";
+const KEY_LENGTH: usize = 32;
+
+/// AES-256 key that wraps the data keys of encrypted objects.
+#[derive(Clone)]
+pub struct MasterKey([u8; KEY_LENGTH]);
+
+impl MasterKey {
+    pub fn from_bytes(bytes: &[u8]) -> Result<MasterKey, LakestreamError> {
+        let key: [u8; KEY_LENGTH] = bytes.try_into().map_err(|_| {
+            LakestreamError::ConfigError(format!(
+                "Encryption key must be {} bytes, got {}",
+                KEY_LENGTH,
+                bytes.len()
+            ))
+        })?;
+        Ok(MasterKey(key))
+    }
+
+    /// Load a key from a file with either 32 raw bytes, or the key encoded
+    /// as hex or base64 text.
+    pub fn from_keyfile(path: &Path) -> Result<MasterKey, LakestreamError> {
+        let contents = fs::read(path).map_err(|err| {
+            LakestreamError::ConfigError(format!(
+                "Failed to read keyfile {}: {}",
+                path.display(),
+                err
+            ))
+        })?;
+        if contents.len() == KEY_LENGTH {
+            return MasterKey::from_bytes(&contents);
+        }
+
+        let text = String::from_utf8_lossy(&contents);
+        let text = text.trim();
+        let decoded = hex::decode(text)
+            .ok()
+            .filter(|key| key.len() == KEY_LENGTH)
+            .or_else(|| STANDARD.decode(text).ok());
+        match decoded {
+            Some(key) => MasterKey::from_bytes(&key),
+            None => Err(LakestreamError::ConfigError(format!(
+                "Invalid keyfile {}: expected {} raw bytes, hex or base64",
+                path.display(),
+                KEY_LENGTH
+            ))),
+        }
+    }
+
+    /// Load the key from the keyfile set in LAKESTREAM_ENCRYPTION_KEYFILE,
+    /// in the config or environment.
+    pub fn from_config(config: &Config) -> Result<MasterKey, LakestreamError> {
+        let path = config
+            .get(KEYFILE_KEY)
+            .cloned()
+            .or_else(|| env::var(KEYFILE_KEY).ok())
+            .ok_or_else(|| {
+                LakestreamError::ConfigError(format!(
+                    "{} not found in the config and environment",
+                    KEYFILE_KEY
+                ))
+            })?;
+        MasterKey::from_keyfile(Path::new(&path))
+    }
+
+    pub(super) fn as_bytes(&self) -> &[u8; KEY_LENGTH] {
+        &self.0
+    }
+}
@@ -0,0 +1,4 @@
+pub mod backend;
+mod envelope;
+mod key;
+mod store;
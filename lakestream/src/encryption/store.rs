@@ -0,0 +1,203 @@
+use std::future::Future;
+use std::pin::{pin, Pin};
+
+use async_trait::async_trait;
+use futures::channel::mpsc;
+use futures::future::{self, Either};
+use futures::{SinkExt, StreamExt};
+use log::warn;
+
+use super::envelope::{add_to_sidecar, open, seal, ENVELOPE_OVERHEAD};
+use super::key::MasterKey;
+use crate::{
+    Config, FileObject, FileObjectFilter, FileObjectVec, LakestreamError,
+    ObjectStoreTrait,
+};
+
+// wrapped data keys of the most recent versions of an object are stored
+// next to it, as "{key}{suffix}"
+const SIDECAR_SUFFIX: &str = ".lakestream-key";
+// the previous version is kept, so it stays readable while a put of the
+// next one is on its way
+const KEPT_VERSIONS: usize = 2;
+
+/// Wraps an object store to encrypt objects on put and decrypt them on get,
+/// using AES-GCM envelope encryption. Only ciphertext and wrapped keys are
+/// sent to the underlying object store.
+pub struct EncryptedStore<T> {
+    inner: T,
+    master_key: MasterKey,
+}
+
+impl<T: ObjectStoreTrait> EncryptedStore<T> {
+    pub fn new(inner: T, master_key: MasterKey) -> EncryptedStore<T> {
+        EncryptedStore { inner, master_key }
+    }
+
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+}
+
+#[async_trait(?Send)]
+impl<T: ObjectStoreTrait> ObjectStoreTrait for EncryptedStore<T> {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn config(&self) -> &Config {
+        self.inner.config()
+    }
+
+    // Sidecars are hidden and sizes are those of the plaintext. Because of
+    // that, filters and max_keys are applied here, to each batch of the
+    // underlying listing as it comes in. Only the exclude patterns are
+    // passed on, so excluded directories are not listed at all.
+    async fn list_files(
+        &self,
+        prefix: Option<&str>,
+        recursive: bool,
+        max_keys: Option<u32>,
+        filter: &Option<FileObjectFilter>,
+        file_objects: &mut FileObjectVec,
+    ) -> Result<(), LakestreamError> {
+        // without a buffer, the underlying listing waits for each batch to
+        // be passed on
+        let (sender, mut receiver) = mpsc::channel::<Vec<FileObject>>(0);
+        let callback = Box::new(move |batch: &[FileObject]| {
+            let mut sender = sender.clone();
+            let batch = batch.to_vec();
+            Box::pin(async move {
+                // the batches are no longer taken once max_keys is reached
+                let _ = sender.send(batch).await;
+            }) as Pin<Box<dyn Future<Output = ()> + Send + 'static>>
+        });
+        let mut inner_file_objects = FileObjectVec::new(Some(callback));
+        let inner_filter = filter.as_ref().map(FileObjectFilter::excludes_only);
+        let listing = async {
+            let result = self
+                .inner
+                .list_files(
+                    prefix,
+                    recursive,
+                    None,
+                    &inner_filter,
+                    &mut inner_file_objects,
+                )
+                .await;
+            // drops the callback, which ends the batches
+            drop(inner_file_objects);
+            result
+        };
+
+        let max_keys = max_keys.map(|max_keys| max_keys as usize);
+        let forward = async {
+            while let Some(batch) = receiver.next().await {
+                let mut batch = plaintext_file_objects(batch, filter);
+                if let Some(max_keys) = max_keys {
+                    batch.truncate(max_keys.saturating_sub(file_objects.len()));
+                }
+                file_objects.extend_async(batch).await;
+                if max_keys
+                    .is_some_and(|max_keys| file_objects.len() >= max_keys)
+                {
+                    break;
+                }
+            }
+        };
+
+        match future::select(pin!(listing), pin!(forward)).await {
+            // the batches that are still to be passed on
+            Either::Left((result, forward)) => {
+                result?;
+                forward.await;
+            }
+            // max_keys is reached, the rest of the listing is not needed
+            Either::Right(((), _)) => {}
+        }
+        Ok(())
+    }
+
+    async fn get_object(
+        &self,
+        key: &str,
+        data: &mut Vec<u8>,
+    ) -> Result<(), LakestreamError> {
+        let mut object = Vec::new();
+        self.inner.get_object(key, &mut object).await?;
+        let mut sidecar = Vec::new();
+        self.inner
+            .get_object(&sidecar_key(key), &mut sidecar)
+            .await?;
+
+        *data = open(&self.master_key, key, &object, &sidecar)?;
+        Ok(())
+    }
+
+    // The sidecar is stored first, with the data key of the new version
+    // added. So an object is never stored without its data key, and if
+    // storing the object fails, the previous version is left intact. Only
+    // the keys of the most recent versions are kept, so a single sidecar
+    // per object is enough. Concurrent puts of the same object can leave
+    // the object without its key in the sidecar.
+    async fn put_object(
+        &self,
+        key: &str,
+        data: &[u8],
+    ) -> Result<(), LakestreamError> {
+        let envelope = seal(&self.master_key, key, data)?;
+        let sidecar_key = sidecar_key(key);
+
+        // a missing or unreadable sidecar has no keys worth keeping
+        let mut sidecar = Vec::new();
+        if let Err(err) =
+            self.inner.get_object(&sidecar_key, &mut sidecar).await
+        {
+            if !matches!(err, LakestreamError::NotFound(_)) {
+                warn!("Failed to read {}: {}", sidecar_key, err);
+            }
+            sidecar.clear();
+        }
+        let sidecar = add_to_sidecar(&sidecar, &envelope, key, KEPT_VERSIONS)?;
+
+        self.inner.put_object(&sidecar_key, &sidecar).await?;
+        self.inner.put_object(key, &envelope.object).await
+    }
+}
+
+fn sidecar_key(key: &str) -> String {
+    format!("{}{}", key, SIDECAR_SUFFIX)
+}
+
+fn is_sidecar(key: &str) -> bool {
+    key.ends_with(SIDECAR_SUFFIX)
+}
+
+// objects of the underlying listing as listed by the encrypted store
+fn plaintext_file_objects(
+    file_objects: Vec<FileObject>,
+    filter: &Option<FileObjectFilter>,
+) -> Vec<FileObject> {
+    file_objects
+        .into_iter()
+        .filter(|file_object| !is_sidecar(file_object.name()))
+        .map(|file_object| {
+            if file_object.name().ends_with('/') {
+                return file_object;
+            }
+            FileObject::new(
+                file_object.name().to_string(),
+                file_object.size().saturating_sub(ENVELOPE_OVERHEAD),
+                file_object.modified(),
+                file_object.tags().clone(),
+            )
+        })
+        .filter(|file_object| match filter {
+            Some(filter) => {
+                !file_object.name().ends_with('/')
+                    && filter.matches(file_object)
+            }
+            None => true,
+        })
+        .collect()
+}
@@ -0,0 +1,25 @@
+use async_trait::async_trait;
+
+pub use super::bucket::HttpBucket;
+use crate::{Config, LakestreamError, ObjectStoreBackend, ObjectStoreVec};
+
+pub struct HttpBackend;
+
+#[async_trait(?Send)]
+impl ObjectStoreBackend for HttpBackend {
+    fn new(_config: Config) -> Result<Self, LakestreamError> {
+        Ok(Self)
+    }
+
+    async fn list_buckets(
+        _config: Config,
+        _object_stores: &mut ObjectStoreVec,
+    ) -> Result<(), LakestreamError> {
+        // a web server is addressed by host, there is nothing to enumerate
+        Err(LakestreamError::ConfigError(
+            "Listing buckets is not supported for http(s), use a URL \
+             such as https://example.com/data/"
+                .to_string(),
+        ))
+    }
+}
@@ -0,0 +1,126 @@
+use std::io::Write;
+
+use async_trait::async_trait;
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+
+use super::get::{
+    get_object, get_object_if_none_match, get_object_to_writer,
+    get_object_with_content_encoding,
+};
+use super::list::list_files;
+use crate::base::config::Config;
+use crate::{
+    ConditionalGet, FileObjectFilter, FileObjectVec, LakestreamError,
+    ObjectStoreTrait,
+};
+
+// encode everything except unreserved characters, keep '/' as separator
+const PATH_ENCODE_SET: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~')
+    .remove(b'/');
+
+#[derive(Clone)]
+pub struct HttpBucket {
+    // host, optionally with port, e.g. "example.com:8080"
+    name: String,
+    scheme: String,
+    config: Config,
+}
+
+impl HttpBucket {
+    pub fn new(
+        name: &str,
+        scheme: &str,
+        config: Config,
+    ) -> Result<HttpBucket, LakestreamError> {
+        if name.is_empty() || name.contains('/') {
+            return Err(LakestreamError::ConfigError(format!(
+                "Invalid host: '{}'",
+                name
+            )));
+        }
+        if scheme != "http" && scheme != "https" {
+            return Err(LakestreamError::ConfigError(format!(
+                "Unsupported scheme for http backend: '{}'",
+                scheme
+            )));
+        }
+        Ok(HttpBucket {
+            name: name.to_string(),
+            scheme: scheme.to_string(),
+            config,
+        })
+    }
+
+    pub fn scheme(&self) -> &str {
+        &self.scheme
+    }
+
+    pub fn url(&self, key: &str) -> String {
+        format!(
+            "{}://{}/{}",
+            self.scheme,
+            self.name,
+            utf8_percent_encode(key.trim_start_matches('/'), PATH_ENCODE_SET)
+        )
+    }
+}
+
+#[async_trait(?Send)]
+impl ObjectStoreTrait for HttpBucket {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn config(&self) -> &Config {
+        &self.config
+    }
+
+    async fn list_files(
+        &self,
+        prefix: Option<&str>,
+        recursive: bool,
+        max_keys: Option<u32>,
+        filter: &Option<FileObjectFilter>,
+        file_objects: &mut FileObjectVec,
+    ) -> Result<(), LakestreamError> {
+        list_files(self, prefix, recursive, max_keys, filter, file_objects)
+            .await
+    }
+
+    async fn get_object(
+        &self,
+        key: &str,
+        data: &mut Vec<u8>,
+    ) -> Result<(), LakestreamError> {
+        get_object(self, key, data).await
+    }
+
+    async fn get_object_to_writer(
+        &self,
+        key: &str,
+        writer: &mut dyn Write,
+    ) -> Result<(), LakestreamError> {
+        get_object_to_writer(self, key, writer).await
+    }
+
+    async fn get_object_if_none_match(
+        &self,
+        key: &str,
+        etag: Option<&str>,
+        data: &mut Vec<u8>,
+    ) -> Result<ConditionalGet, LakestreamError> {
+        get_object_if_none_match(self, key, etag, data).await
+    }
+
+    async fn get_object_with_content_encoding(
+        &self,
+        key: &str,
+        data: &mut Vec<u8>,
+    ) -> Result<Option<String>, LakestreamError> {
+        get_object_with_content_encoding(self, key, data).await
+    }
+}
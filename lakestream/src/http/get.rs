@@ -0,0 +1,131 @@
+use std::collections::HashMap;
+use std::io::Write;
+
+use log::info;
+
+use super::bucket::HttpBucket;
+use super::requests::{http_get_request_to_writer, http_head_request};
+use crate::utils::time::http_date_to_epoch;
+use crate::{ConditionalGet, FileObject, LakestreamError};
+
+pub async fn get_object(
+    http_bucket: &HttpBucket,
+    object_key: &str,
+    data: &mut Vec<u8>,
+) -> Result<(), LakestreamError> {
+    get_object_if_none_match(http_bucket, object_key, None, data).await?;
+    Ok(())
+}
+
+pub async fn get_object_to_writer(
+    http_bucket: &HttpBucket,
+    object_key: &str,
+    writer: &mut dyn Write,
+) -> Result<(), LakestreamError> {
+    get_object_with_headers(http_bucket, object_key, None, writer).await?;
+    Ok(())
+}
+
+pub async fn get_object_if_none_match(
+    http_bucket: &HttpBucket,
+    object_key: &str,
+    etag: Option<&str>,
+    data: &mut Vec<u8>,
+) -> Result<ConditionalGet, LakestreamError> {
+    // data is left untouched if the object is not modified
+    let mut body = Vec::new();
+    let headers =
+        get_object_with_headers(http_bucket, object_key, etag, &mut body)
+            .await?;
+    Ok(match headers {
+        Some(headers) => {
+            *data = body;
+            ConditionalGet::Modified(headers.get("etag").cloned())
+        }
+        None => ConditionalGet::NotModified,
+    })
+}
+
+pub async fn get_object_with_content_encoding(
+    http_bucket: &HttpBucket,
+    object_key: &str,
+    data: &mut Vec<u8>,
+) -> Result<Option<String>, LakestreamError> {
+    data.clear();
+    let headers =
+        get_object_with_headers(http_bucket, object_key, None, data).await?;
+    Ok(headers.and_then(|headers| headers.get("content-encoding").cloned()))
+}
+
+// writes the object to writer as it arrives, and returns the response
+// headers, or None if the object still matches etag
+async fn get_object_with_headers(
+    http_bucket: &HttpBucket,
+    object_key: &str,
+    etag: Option<&str>,
+    writer: &mut dyn Write,
+) -> Result<Option<HashMap<String, String>>, LakestreamError> {
+    let url = http_bucket.url(object_key);
+    let mut headers = HashMap::new();
+    if let Some(etag) = etag {
+        headers.insert("If-None-Match".to_string(), etag.to_string());
+    }
+
+    info!("Getting object: {}", url);
+    let (status, response_headers) =
+        http_get_request_to_writer(&url, &headers, writer)
+            .await
+            .map_err(LakestreamError::from)?;
+    if status == 304 {
+        info!("Object not modified: {}", object_key);
+        return Ok(None);
+    }
+    check_status(&url, status)?;
+    info!("Got object: {}", object_key);
+    Ok(Some(response_headers))
+}
+
+// size and mtime of a single object, taken from the response headers of
+// a HEAD request
+pub async fn head_object(
+    http_bucket: &HttpBucket,
+    object_key: &str,
+) -> Result<FileObject, LakestreamError> {
+    let url = http_bucket.url(object_key);
+
+    let (status, headers) = http_head_request(&url, &HashMap::new())
+        .await
+        .map_err(LakestreamError::from)?;
+    check_status(&url, status)?;
+
+    let size = headers
+        .get("content-length")
+        .and_then(|length| length.parse::<u64>().ok())
+        .unwrap_or(0);
+    let modified = headers
+        .get("last-modified")
+        .and_then(|date| http_date_to_epoch(date).ok());
+    let tags = headers.get("etag").map(|etag| {
+        [("ETag".to_string(), etag.trim_matches('"').to_string())]
+            .into_iter()
+            .collect()
+    });
+
+    Ok(FileObject::new(
+        object_key.to_string(),
+        size,
+        modified,
+        tags,
+    ))
+}
+
+pub fn check_status(url: &str, status: u16) -> Result<(), LakestreamError> {
+    match status {
+        200..=299 => Ok(()),
+        404 => Err(LakestreamError::NotFound(url.to_string())),
+        _ => Err(LakestreamError::InternalError(format!(
+            "Request to {} failed with status {}",
+            url, status
+        ))),
+    }
+}
@@ -0,0 +1,226 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use log::{info, warn};
+use percent_encoding::percent_decode_str;
+use url::Url;
+
+use super::bucket::HttpBucket;
+use super::get::{check_status, head_object};
+use super::parse_index::{parse_index, IndexEntry};
+use super::requests::http_get_request_if;
+use crate::{FileObject, FileObjectFilter, FileObjectVec, LakestreamError};
+
+const MAX_REDIRECTS: usize = 5;
+
+pub async fn list_files(
+    http_bucket: &HttpBucket,
+    prefix: Option<&str>,
+    recursive: bool,
+    max_keys: Option<u32>,
+    filter: &Option<FileObjectFilter>,
+    file_objects: &mut FileObjectVec,
+) -> Result<(), LakestreamError> {
+    let prefix = prefix.unwrap_or_default();
+    let max_keys = max_keys.map(|keys| keys as usize);
+
+    let Some((directory, entries)) =
+        list_directory(http_bucket, prefix).await?
+    else {
+        // not a directory index, the URI may point to a single file
+        let file_object =
+            head_object(http_bucket, prefix.trim_end_matches('/')).await?;
+        if filter
+            .as_ref()
+            .is_none_or(|filter| filter.matches(&file_object))
+        {
+            file_objects.extend_async(vec![file_object]).await;
+        }
+        return Ok(());
+    };
+
+    // index pages that are listed, by normalised URL, so redirects back to
+    // a directory that is listed already do not loop
+    let mut visited = HashSet::from([directory_url(http_bucket, &directory)]);
+    // directories whose index page is still to be fetched
+    let mut directory_queue: VecDeque<String> = VecDeque::new();
+    let mut listing = Some((directory, entries));
+
+    loop {
+        let (prefix, entries) = match listing.take() {
+            Some(listing) => listing,
+            None => {
+                let Some(key) = directory_queue.pop_front() else {
+                    break;
+                };
+                match list_directory(http_bucket, &key).await? {
+                    Some((directory, entries))
+                        if visited
+                            .insert(directory_url(http_bucket, &directory)) =>
+                    {
+                        (directory, entries)
+                    }
+                    Some((directory, _)) => {
+                        warn!(
+                            "Skipping directory listed before: {}",
+                            directory
+                        );
+                        continue;
+                    }
+                    None => {
+                        warn!("No directory index for: {}", key);
+                        continue;
+                    }
+                }
+            }
+        };
+        let mut temp_file_objects = Vec::new();
+
+        for entry in entries {
+            if max_keys.is_some_and(|max_keys| {
+                file_objects.len() + temp_file_objects.len() >= max_keys
+            }) {
+                break;
+            }
+            let key = format!("{}{}", prefix, entry.name);
+            if entry.is_dir() {
+                if recursive
+                    && !visited.contains(&directory_url(http_bucket, &key))
+                {
+                    directory_queue.push_back(key.clone());
+                }
+                if filter.is_none() {
+                    temp_file_objects.push(FileObject::new(
+                        key,
+                        0,
+                        entry.modified,
+                        None,
+                    ));
+                }
+                continue;
+            }
+
+            let file_object = file_object(http_bucket, key, &entry).await;
+            if filter
+                .as_ref()
+                .is_none_or(|filter| filter.matches(&file_object))
+            {
+                temp_file_objects.push(file_object);
+            }
+        }
+
+        file_objects.extend_async(temp_file_objects).await;
+
+        if max_keys.is_some_and(|max_keys| file_objects.len() >= max_keys) {
+            break;
+        }
+    }
+    Ok(())
+}
+
+// the directory (as key that ends with '/') and entries of its index page,
+// or None if the server has no index page for it. Redirects within the
+// host are followed, e.g. to a directory that is renamed.
+async fn list_directory(
+    http_bucket: &HttpBucket,
+    directory: &str,
+) -> Result<Option<(String, Vec<IndexEntry>)>, LakestreamError> {
+    let mut directory = directory_key(directory);
+
+    for _ in 0..=MAX_REDIRECTS {
+        let url = http_bucket.url(&directory);
+        info!("Listing directory: {}", url);
+
+        // the body is only read for an index page, a (large) file at the
+        // URL is not downloaded
+        let (body, status, headers) =
+            http_get_request_if(&url, &HashMap::new(), |status, headers| {
+                (200..300).contains(&status) && is_html(headers)
+            })
+            .await
+            .map_err(LakestreamError::from)?;
+        match status {
+            404 => return Ok(None),
+            301 | 302 | 303 | 307 | 308 => {
+                directory = redirect_directory(&url, &headers)?;
+                continue;
+            }
+            _ => check_status(&url, status)?,
+        }
+
+        if !is_html(&headers) {
+            return Ok(None);
+        }
+        let html = String::from_utf8_lossy(&body);
+        let entries = parse_index(&html, &directory);
+        return Ok(Some((directory, entries)));
+    }
+    Err(LakestreamError::InternalError(format!(
+        "Too many redirects for directory: {}",
+        http_bucket.url(&directory)
+    )))
+}
+
+// URL of a directory with "." and ".." segments resolved
+fn directory_url(http_bucket: &HttpBucket, directory: &str) -> String {
+    let url = http_bucket.url(directory);
+    Url::parse(&url).map_or(url, String::from)
+}
+
+// pages without a content type are assumed to be HTML
+fn is_html(headers: &HashMap<String, String>) -> bool {
+    headers
+        .get("content-type")
+        .is_none_or(|content_type| content_type.contains("html"))
+}
+
+// index pages are at the URL of the directory with a trailing slash, which
+// also makes the keys of its entries
+fn directory_key(directory: &str) -> String {
+    if directory.is_empty() || directory.ends_with('/') {
+        directory.to_string()
+    } else {
+        format!("{}/", directory)
+    }
+}
+
+// directory that a redirect response points to, on the same host
+fn redirect_directory(
+    url: &str,
+    headers: &HashMap<String, String>,
+) -> Result<String, LakestreamError> {
+    let url = Url::parse(url)?;
+    let location = headers.get("location").ok_or_else(|| {
+        LakestreamError::InternalError(format!(
+            "Redirect without location for: {}",
+            url
+        ))
+    })?;
+    let target = url.join(location)?;
+    if target.origin() != url.origin() {
+        return Err(LakestreamError::InternalError(format!(
+            "Redirect to another host is not followed: {} -> {}",
+            url, target
+        )));
+    }
+    let path = percent_decode_str(target.path()).decode_utf8_lossy();
+    Ok(directory_key(path.trim_start_matches('/')))
+}
+
+// complete size and mtime from a HEAD request when the index page does not
+// provide them exactly
+async fn file_object(
+    http_bucket: &HttpBucket,
+    key: String,
+    entry: &IndexEntry,
+) -> FileObject {
+    if let (Some(size), Some(modified)) = (entry.size, entry.modified) {
+        return FileObject::new(key, size, Some(modified), None);
+    }
+    match head_object(http_bucket, &key).await {
+        Ok(file_object) => file_object,
+        Err(err) => {
+            warn!("HEAD request failed for {}: {}", key, err);
+            FileObject::new(key, entry.size.unwrap_or(0), entry.modified, None)
+        }
+    }
+}
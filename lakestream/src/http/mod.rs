@@ -1,3 +1,9 @@
+pub mod backend;
+mod bucket;
+mod get;
+mod list;
+mod parse_index;
+
 #[cfg(not(target_arch = "wasm32"))]
 pub mod requests;
 
@@ -0,0 +1,192 @@
+use percent_encoding::percent_decode_str;
+use regex::Regex;
+
+use crate::utils::time::days_from_civil;
+
+// entry of a directory index page, name is relative to the listed
+// directory and ends with '/' for sub-directories
+#[derive(Debug, PartialEq)]
+pub struct IndexEntry {
+    pub name: String,
+    pub size: Option<u64>,
+    pub modified: Option<u64>,
+}
+
+impl IndexEntry {
+    pub fn is_dir(&self) -> bool {
+        self.name.ends_with('/')
+    }
+}
+
+const MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct",
+    "Nov", "Dec",
+];
+
+// Parse an autoindex page as generated by nginx and Apache. Each link to a
+// child of the directory is an entry, the text that follows the link up
+// to the next link holds the mtime and size columns (if any).
+pub fn parse_index(html: &str, directory: &str) -> Vec<IndexEntry> {
+    let link_re =
+        Regex::new(r#"(?is)<a\s[^>]*?href\s*=\s*["']([^"']*)["'][^>]*>"#)
+            .unwrap();
+    let tag_re = Regex::new(r"(?s)<[^>]*>").unwrap();
+
+    let links: Vec<_> = link_re.captures_iter(html).collect();
+    let mut entries: Vec<IndexEntry> = Vec::new();
+
+    for (index, captures) in links.iter().enumerate() {
+        let Some(name) = child_name(&captures[1], directory) else {
+            continue;
+        };
+        if entries.iter().any(|entry| entry.name == name) {
+            continue;
+        }
+
+        let text_start = captures.get(0).unwrap().end();
+        let text_end = links
+            .get(index + 1)
+            .map_or(html.len(), |next| next.get(0).unwrap().start());
+        let segment = &html[text_start..text_end];
+        // skip the link text, the columns follow the closing tag
+        let after_link =
+            segment.split_once("</a>").map_or(segment, |(_, rest)| rest);
+        let text = tag_re.replace_all(after_link, " ");
+        let columns: Vec<&str> = text.split_whitespace().collect();
+
+        let is_dir = name.ends_with('/');
+        entries.push(IndexEntry {
+            size: if is_dir { None } else { parse_size(&columns) },
+            modified: parse_modified(&columns),
+            name,
+        });
+    }
+    entries
+}
+
+// name of the entry relative to the directory, or None if the link does not
+// point to a direct child (parent directory, sort links, other hosts)
+fn child_name(href: &str, directory: &str) -> Option<String> {
+    let href = href.replace("&amp;", "&");
+    if href.is_empty()
+        || href.starts_with('?')
+        || href.starts_with('#')
+        || href.contains("://")
+        || href
+            .split('/')
+            .next()
+            .is_some_and(|part| part.contains(':'))
+    {
+        return None;
+    }
+    let href = href.split(['?', '#']).next().unwrap_or_default();
+    let href = percent_decode_str(href).decode_utf8().ok()?;
+
+    let relative = match href.strip_prefix('/') {
+        // absolute links are only children if they are below the directory
+        Some(absolute) => absolute.strip_prefix(directory)?,
+        None => href.strip_prefix("./").unwrap_or(&href),
+    };
+
+    let name = relative.trim_end_matches('/');
+    if name.is_empty() || name == "." || name == ".." || name.contains('/') {
+        return None;
+    }
+    if relative.ends_with('/') {
+        Some(format!("{}/", name))
+    } else {
+        Some(name.to_string())
+    }
+}
+
+// exact sizes only, human readable sizes (e.g. Apache "1.2K") are left to
+// a HEAD request
+fn parse_size(columns: &[&str]) -> Option<u64> {
+    columns
+        .iter()
+        .rev()
+        .find(|column| !column.contains(['-', ':']))
+        .and_then(|column| column.parse::<u64>().ok())
+}
+
+// nginx: "18-Oct-2023 10:00", Apache: "2023-10-18 10:00", both in UTC
+fn parse_modified(columns: &[&str]) -> Option<u64> {
+    columns.windows(2).find_map(|pair| {
+        let (year, month, day) = parse_date(pair[0])?;
+        let mut time = pair[1].split(':').map(|part| part.parse::<u64>());
+        let hour = time.next()?.ok()?;
+        let minute = time.next()?.ok()?;
+        let second = time.next().and_then(Result::ok).unwrap_or(0);
+        if hour > 23 || minute > 59 || second > 60 {
+            return None;
+        }
+        let days = days_from_civil(year, month, day)?;
+        Some(days * 86400 + hour * 3600 + minute * 60 + second)
+    })
+}
+
+fn parse_date(date: &str) -> Option<(i64, u32, u32)> {
+    let parts: Vec<&str> = date.split('-').collect();
+    if parts.len() != 3 {
+        return None;
+    }
+    let (year, month, day) = match MONTHS.iter().position(|m| *m == parts[1]) {
+        Some(month) => (parts[2], month as u32 + 1, parts[0]),
+        None => (parts[0], parts[1].parse().ok()?, parts[2]),
+    };
+    let year: i64 = year.parse().ok()?;
+    let day: u32 = day.parse().ok()?;
+    if year < 1970 || !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+    Some((year, month, day))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_nginx_index() {
+        let html = r#"<html>
+<head><title>Index of /data/</title></head>
+<body>
+<h1>Index of /data/</h1><hr><pre><a href="../">../</a>
+<a href="sub/">sub/</a>                                               18-Oct-2023 10:00                   -
+<a href="a%20b.csv">a b.csv</a>                                           18-Oct-2023 10:01                1234
+</pre><hr></body>
+</html>"#;
+        assert_eq!(
+            parse_index(html, "data/"),
+            vec![
+                IndexEntry {
+                    name: "sub/".to_string(),
+                    size: None,
+                    modified: Some(1697623200),
+                },
+                IndexEntry {
+                    name: "a b.csv".to_string(),
+                    size: Some(1234),
+                    modified: Some(1697623260),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_apache_index() {
+        let html = r#"<table>
+<tr><th><a href="?C=N;O=D">Name</a></th><th><a href="?C=M;O=A">Last modified</a></th></tr>
+<tr><td valign="top"><img src="/icons/back.gif" alt="[PARENTDIR]"></td><td><a href="/">Parent Directory</a></td><td>&nbsp;</td></tr>
+<tr><td valign="top"><img src="/icons/text.gif" alt="[TXT]"></td><td><a href="/data/b.txt">b.txt</a></td><td align="right">2023-10-18 10:00  </td><td align="right">1.2K</td></tr>
+</table>"#;
+        assert_eq!(
+            parse_index(html, "data/"),
+            vec![IndexEntry {
+                name: "b.txt".to_string(),
+                size: None,
+                modified: Some(1697623200),
+            }]
+        );
+    }
+}
@@ -1,11 +1,13 @@
 use std::collections::HashMap;
 use std::error::Error;
+use std::io::Write;
 use std::str::FromStr;
 
 use bytes::Bytes;
+use hyper::body::HttpBody;
 use hyper::client::HttpConnector;
 use hyper::header::{HeaderName, HeaderValue};
-use hyper::{Body, Client, Request};
+use hyper::{Body, Client, Method, Request, Response};
 use hyper_tls::HttpsConnector;
 use native_tls::TlsConnector as NativeTlsConnector;
 use tokio_native_tls::TlsConnector;
@@ -13,6 +15,8 @@ use url::Url;
 
 type HttpResult = Result<(Bytes, u16, HashMap<String, String>), Box<dyn Error>>;
 type HttpResultWithoutHeaders = Result<(Bytes, u16), Box<dyn Error>>;
+type HttpResultWithoutBody =
+    Result<(u16, HashMap<String, String>), Box<dyn Error>>;
 
 pub async fn http_get_request(
     url: &str,
@@ -26,10 +30,93 @@ pub async fn http_get_request_with_headers(
     url: &str,
     headers: &HashMap<String, String>,
 ) -> HttpResult {
+    http_get_request_if(url, headers, |status, _| (200..300).contains(&status))
+        .await
+}
+
+// GET request that only reads the body if read_body accepts the status and
+// headers of the response, so e.g. a large body of an unexpected content
+// type is not downloaded. Otherwise the body is empty.
+pub async fn http_get_request_if<F>(
+    url: &str,
+    headers: &HashMap<String, String>,
+    read_body: F,
+) -> HttpResult
+where
+    F: Fn(u16, &HashMap<String, String>) -> bool,
+{
+    let response = http_request("GET", url, headers, Body::empty()).await?;
+
+    let status = response.status().as_u16();
+    let headers_map = parse_response_headers(&response);
+
+    if !read_body(status, &headers_map) {
+        return Ok((Bytes::new(), status, headers_map));
+    }
+    let body_bytes = hyper::body::to_bytes(response.into_body()).await?;
+
+    Ok((body_bytes, status, headers_map))
+}
+
+// GET request that writes the body to writer chunk by chunk as it arrives,
+// instead of collecting it in an intermediate buffer first. The body of a
+// response that is not a success is not written.
+pub async fn http_get_request_to_writer(
+    url: &str,
+    headers: &HashMap<String, String>,
+    writer: &mut dyn Write,
+) -> HttpResultWithoutBody {
+    let response = http_request("GET", url, headers, Body::empty()).await?;
+
+    let status = response.status().as_u16();
+    let headers_map = parse_response_headers(&response);
+
+    if !(200..300).contains(&(status as isize)) {
+        return Ok((status, headers_map));
+    }
+    let mut body = response.into_body();
+    while let Some(chunk) = body.data().await {
+        writer.write_all(&chunk?)?;
+    }
+
+    Ok((status, headers_map))
+}
+
+pub async fn http_head_request(
+    url: &str,
+    headers: &HashMap<String, String>,
+) -> HttpResultWithoutBody {
+    let response = http_request("HEAD", url, headers, Body::empty()).await?;
+
+    let status = response.status().as_u16();
+    let headers_map = parse_response_headers(&response);
+
+    Ok((status, headers_map))
+}
+
+pub async fn http_put_request(
+    url: &str,
+    headers: &HashMap<String, String>,
+    body: Vec<u8>,
+) -> HttpResultWithoutBody {
+    let response = http_request("PUT", url, headers, Body::from(body)).await?;
+
+    let status = response.status().as_u16();
+    let headers_map = parse_response_headers(&response);
+
+    Ok((status, headers_map))
+}
+
+async fn http_request(
+    method: &str,
+    url: &str,
+    headers: &HashMap<String, String>,
+    body: Body,
+) -> Result<Response<Body>, Box<dyn Error>> {
     let url_u = Url::parse(url)?;
     let accept_invalid_certs = url_u.scheme() == "https"
         && url_u.host_str() == Some("localhost")
-        && url_u.port().map_or(true, |port| port > 0);
+        && url_u.port().is_none_or(|port| port > 0);
 
     let mut native_tls_connector_builder = NativeTlsConnector::builder();
     native_tls_connector_builder
@@ -44,7 +131,10 @@ pub async fn http_get_request_with_headers(
     let https = HttpsConnector::from((http_connector, tls_connector));
     let client = Client::builder().build::<_, Body>(https);
 
-    let mut request = Request::get(url).body(Body::empty())?;
+    let mut request = Request::builder()
+        .method(Method::from_str(method)?)
+        .uri(url)
+        .body(body)?;
     for (key, value) in headers.iter() {
         if let (Ok(header_name), Ok(header_value)) =
             (HeaderName::from_str(key), HeaderValue::from_str(value))
@@ -53,17 +143,7 @@ pub async fn http_get_request_with_headers(
         }
     }
 
-    let response = client.request(request).await?;
-
-    let status = response.status().as_u16();
-    let headers_map = parse_response_headers(&response);
-
-    if !(200..300).contains(&(status as isize)) {
-        return Ok((Bytes::new(), status, headers_map));
-    }
-    let body_bytes = hyper::body::to_bytes(response.into_body()).await?;
-
-    Ok((body_bytes, status, headers_map))
+    Ok(client.request(request).await?)
 }
 
 fn parse_response_headers(
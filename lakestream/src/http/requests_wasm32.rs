@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::io::Write;
 
 use bytes::Bytes;
 use js_sys::{ArrayBuffer, Uint8Array};
@@ -23,6 +24,20 @@ pub async fn http_get_request_with_headers(
     Ok((response_body, response_status, HashMap::new()))
 }
 
+pub async fn http_get_request_if<F>(
+    url: &str,
+    headers: &HashMap<String, String>,
+    read_body: F,
+) -> Result<(Bytes, u16, HashMap<String, String>), LakestreamError>
+where
+    F: Fn(u16, &HashMap<String, String>) -> bool,
+{
+    // TODO: check the response before the body is read -- for now forward
+    // to http_get_request_with_headers
+    let _ = read_body;
+    http_get_request_with_headers(url, headers).await
+}
+
 pub async fn http_get_request(
     url: &str,
     headers: &HashMap<String, String>,
@@ -72,3 +87,79 @@ pub async fn http_get_request(
         Err(LakestreamError::String(error_message))
     }
 }
+
+pub async fn http_get_request_to_writer(
+    url: &str,
+    headers: &HashMap<String, String>,
+    writer: &mut dyn Write,
+) -> Result<(u16, HashMap<String, String>), LakestreamError> {
+    // TODO: stream the body via ReadableStream -- for now forward to
+    // http_get_request
+    let (response_body, response_status) =
+        http_get_request(url, headers).await?;
+    writer.write_all(&response_body)?;
+    Ok((response_status, HashMap::new()))
+}
+
+pub async fn http_head_request(
+    url: &str,
+    headers: &HashMap<String, String>,
+) -> Result<(u16, HashMap<String, String>), LakestreamError> {
+    info!("http_head_request: {}", url);
+    let window = web_sys::window().ok_or("No window available")?;
+    let mut request_init = RequestInit::new();
+    request_init.method("HEAD");
+    request_init.mode(RequestMode::Cors);
+
+    let headers_map = Headers::new().map_err(LakestreamError::Js)?;
+    for (key, value) in headers.iter() {
+        headers_map.set(key, value).map_err(LakestreamError::Js)?;
+    }
+    request_init.headers(&headers_map);
+
+    let request = Request::new_with_str_and_init(url, &request_init)
+        .map_err(LakestreamError::Js)?;
+    let response_js = JsFuture::from(window.fetch_with_request(&request))
+        .await
+        .map_err(LakestreamError::Js)?;
+    let response: web_sys::Response =
+        response_js.dyn_into().map_err(LakestreamError::Js)?;
+
+    // only headers exposed via CORS are readable
+    let mut response_headers = HashMap::new();
+    for name in ["content-length", "last-modified", "etag", "content-type"] {
+        if let Ok(Some(value)) = response.headers().get(name) {
+            response_headers.insert(name.to_string(), value);
+        }
+    }
+    Ok((response.status(), response_headers))
+}
+
+pub async fn http_put_request(
+    url: &str,
+    headers: &HashMap<String, String>,
+    body: Vec<u8>,
+) -> Result<(u16, HashMap<String, String>), LakestreamError> {
+    info!("http_put_request: {}", url);
+    let window = web_sys::window().ok_or("No window available")?;
+    let mut request_init = RequestInit::new();
+    request_init.method("PUT");
+    request_init.mode(RequestMode::Cors);
+    let body = Uint8Array::from(body.as_slice());
+    request_init.body(Some(&body));
+
+    let headers_map = Headers::new().map_err(LakestreamError::Js)?;
+    for (key, value) in headers.iter() {
+        headers_map.set(key, value).map_err(LakestreamError::Js)?;
+    }
+    request_init.headers(&headers_map);
+
+    let request = Request::new_with_str_and_init(url, &request_init)
+        .map_err(LakestreamError::Js)?;
+    let response_js = JsFuture::from(window.fetch_with_request(&request))
+        .await
+        .map_err(LakestreamError::Js)?;
+    let response: web_sys::Response =
+        response_js.dyn_into().map_err(LakestreamError::Js)?;
+    Ok((response.status(), HashMap::new()))
+}
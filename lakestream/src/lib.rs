@@ -1,9 +1,15 @@
 pub(crate) mod api;
+pub(crate) mod archive;
+pub(crate) mod azure;
 pub(crate) mod base;
+pub(crate) mod cache;
+pub(crate) mod compression;
 pub(crate) mod default;
+pub(crate) mod encryption;
 pub(crate) mod error;
 pub(crate) mod http;
 pub(crate) mod localfs;
+pub(crate) mod memory;
 pub(crate) mod s3;
 pub(crate) mod utils;
 
@@ -13,9 +19,25 @@ pub use base::callback_wrapper::{
 };
 pub use base::config::Config;
 pub use base::file_object::{FileObject, FileObjectVec};
-pub use base::filters::FileObjectFilter;
+pub use base::filters::{FileObjectFilter, NameMode};
+pub use base::grep::LineMatch;
 pub use base::list_objects_result::ListObjectsResult;
-pub use base::object_store::{ObjectStore, ObjectStoreTrait, ObjectStoreVec};
+pub use base::object_store::{
+    object_stores_from_config, object_stores_from_config_with_registry,
+    ConditionalGet, CustomBucket, ObjectStore, ObjectStoreTrait,
+    ObjectStoreVec,
+};
+pub use base::object_store_registry::{
+    BackendFactory, ObjectStoreFactory, ObjectStoreRegistry,
+};
+pub use base::prefix_totals::{PrefixTotals, Totals};
+pub use base::sort::{SortKey, TopFileObjects};
+pub use compression::codec::Codec;
 // re-export all defaults
 pub use default::*;
+pub use encryption::backend::{EncryptedStore, MasterKey};
 pub use error::LakestreamError;
+pub use localfs::backend::{DiskUsage, LocalFsBucket};
+pub use memory::backend::MemoryBucket;
+pub use utils::formatters::{bytes_human_readable, format_bytes, SizeUnits};
+pub use utils::uri_parse::ParsedUri;
@@ -1,7 +1,13 @@
 use async_trait::async_trait;
 
-pub use super::bucket::{FileSystem, LocalFsBucket};
-use crate::{Config, LakestreamError, ObjectStoreBackend, ObjectStoreVec};
+pub use super::bucket::LocalFsBucket;
+use super::config::configured_roots;
+use super::mounts::local_roots;
+pub use super::mounts::DiskUsage;
+use crate::utils::blocking::spawn_blocking;
+use crate::{
+    Config, LakestreamError, ObjectStore, ObjectStoreBackend, ObjectStoreVec,
+};
 
 pub struct LocalFsBackend;
 
@@ -11,10 +17,25 @@ impl ObjectStoreBackend for LocalFsBackend {
         Ok(Self)
     }
 
+    /// Lists the roots configured in LAKESTREAM_LOCALFS_ROOTS, or else the
+    /// mounted filesystems, with their capacity and usage.
     async fn list_buckets(
-        _config: Config,
-        _object_stores: &mut ObjectStoreVec,
+        config: Config,
+        object_stores: &mut ObjectStoreVec,
     ) -> Result<(), LakestreamError> {
+        let roots = configured_roots(&config);
+        let roots = spawn_blocking(move || local_roots(roots)).await?;
+        let buckets = roots
+            .into_iter()
+            .map(|(root, usage)| {
+                LocalFsBucket::new(&root.to_string_lossy(), config.clone()).map(
+                    |bucket| {
+                        ObjectStore::LocalFsBucket(bucket.with_usage(usage))
+                    },
+                )
+            })
+            .collect::<Result<Vec<ObjectStore>, &str>>()?;
+        object_stores.extend_async(buckets).await;
         Ok(())
     }
 }
@@ -1,33 +1,27 @@
-use std::fs::{self, ReadDir};
-use std::io;
+use std::io::Write;
 use std::path::Path;
 
 use async_trait::async_trait;
 
-use super::get::get_object;
-use super::list::list_files;
+use super::config::TraversalOptions;
+use super::file_system::LocalFileSystem;
+use super::get::{get_object, get_object_to_writer};
+use super::list::Lister;
+use super::mounts::DiskUsage;
+use super::put::put_object;
 use crate::base::config::Config;
 use crate::{
     FileObjectFilter, FileObjectVec, LakestreamError, ObjectStoreTrait,
 };
 
-pub struct LocalFileSystem;
-
-pub trait FileSystem {
-    fn read_dir(&self, path: &Path) -> io::Result<ReadDir>;
-}
-
-impl FileSystem for LocalFileSystem {
-    fn read_dir(&self, path: &Path) -> io::Result<ReadDir> {
-        fs::read_dir(path)
-    }
-}
-
+/// Bucket of a directory on the local file system. File system calls run
+/// on the blocking thread pool when used within a Tokio runtime, and inline
+/// otherwise.
 #[derive(Clone)]
 pub struct LocalFsBucket {
     name: String,
-    #[allow(dead_code)]
     config: Config,
+    usage: Option<DiskUsage>,
 }
 
 impl LocalFsBucket {
@@ -38,8 +32,19 @@ impl LocalFsBucket {
         Ok(LocalFsBucket {
             name: name.to_string(),
             config,
+            usage: None,
         })
     }
+
+    /// Capacity and usage of the filesystem, for buckets from list_buckets.
+    pub fn usage(&self) -> Option<&DiskUsage> {
+        self.usage.as_ref()
+    }
+
+    pub(super) fn with_usage(mut self, usage: Option<DiskUsage>) -> Self {
+        self.usage = usage;
+        self
+    }
 }
 
 #[async_trait(?Send)]
@@ -60,21 +65,20 @@ impl ObjectStoreTrait for LocalFsBucket {
         filter: &Option<FileObjectFilter>,
         file_objects: &mut FileObjectVec,
     ) -> Result<(), LakestreamError> {
+        let root = Path::new(&self.name);
+        // without trailing slash, so a prefix may also point to a file
         let path = match prefix {
-            Some(prefix) => Path::new(&self.name).join(prefix),
-            None => Path::new(&self.name).to_path_buf(),
+            Some(prefix) => root.join(prefix.trim_end_matches('/')),
+            None => root.to_path_buf(),
+        };
+        let lister = Lister {
+            fs: &LocalFileSystem,
+            root,
+            options: TraversalOptions::from_config(&self.config)?,
         };
-        let file_system = LocalFileSystem;
-        list_files(
-            &file_system,
-            &path,
-            max_keys,
-            recursive,
-            filter,
-            file_objects,
-        )
-        .await;
-        Ok(())
+        lister
+            .list_files(&path, max_keys, recursive, filter, file_objects)
+            .await
     }
 
     async fn get_object(
@@ -83,6 +87,24 @@ impl ObjectStoreTrait for LocalFsBucket {
         data: &mut Vec<u8>,
     ) -> Result<(), LakestreamError> {
         let path = Path::new(&self.name);
-        get_object(path, key, data).await
+        get_object(&LocalFileSystem, path, key, data).await
+    }
+
+    async fn get_object_to_writer(
+        &self,
+        key: &str,
+        writer: &mut dyn Write,
+    ) -> Result<(), LakestreamError> {
+        let path = Path::new(&self.name);
+        get_object_to_writer(&LocalFileSystem, path, key, writer).await
+    }
+
+    async fn put_object(
+        &self,
+        key: &str,
+        data: &[u8],
+    ) -> Result<(), LakestreamError> {
+        let path = Path::new(&self.name);
+        put_object(&LocalFileSystem, path, key, data).await
     }
 }
@@ -0,0 +1,92 @@
+use std::env;
+use std::path::PathBuf;
+
+use crate::{Config, LakestreamError};
+
+const SYMLINKS_KEY: &str = "LAKESTREAM_LOCALFS_SYMLINKS";
+const ONE_FILESYSTEM_KEY: &str = "LAKESTREAM_LOCALFS_ONE_FILESYSTEM";
+const KEEP_GOING_KEY: &str = "LAKESTREAM_LOCALFS_KEEP_GOING";
+const METADATA_KEY: &str = "LAKESTREAM_LOCALFS_METADATA";
+const XATTRS_KEY: &str = "LAKESTREAM_LOCALFS_XATTRS";
+const ROOTS_KEY: &str = "LAKESTREAM_LOCALFS_ROOTS";
+const NO_IGNORE_KEY: &str = "LAKESTREAM_LOCALFS_NO_IGNORE";
+
+/// How a recursive listing treats symbolic links.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SymlinkPolicy {
+    /// list the file or directory the link points to, under the name of
+    /// the link
+    Follow,
+    /// leave links out of the listing
+    Skip,
+    /// list the link itself, with its target in the "SymlinkTarget" tag
+    List,
+}
+
+pub struct TraversalOptions {
+    pub symlinks: SymlinkPolicy,
+    /// do not descend into directories on other filesystems (mount points)
+    pub one_filesystem: bool,
+    /// log directories that cannot be read and continue, instead of
+    /// failing the listing
+    pub keep_going: bool,
+    /// add owner, group, mode and inode as tags
+    pub metadata: bool,
+    /// add extended attributes in the "user." namespace as tags
+    pub xattrs: bool,
+    /// read exclude patterns from a .lakestreamignore file in the listed
+    /// directory
+    pub ignore_file: bool,
+}
+
+impl TraversalOptions {
+    pub fn from_config(
+        config: &Config,
+    ) -> Result<TraversalOptions, LakestreamError> {
+        let setting = |key: &str| setting(config, key);
+
+        let symlinks = match setting(SYMLINKS_KEY).as_deref() {
+            None | Some("follow") => SymlinkPolicy::Follow,
+            Some("skip") => SymlinkPolicy::Skip,
+            Some("list") => SymlinkPolicy::List,
+            Some(value) => {
+                return Err(LakestreamError::ConfigError(format!(
+                    "Invalid {}: '{}' (expected follow, skip or list)",
+                    SYMLINKS_KEY, value
+                )))
+            }
+        };
+        let bool_setting = |key: &str| match setting(key).as_deref() {
+            None | Some("false") | Some("0") => Ok(false),
+            Some("true") | Some("1") => Ok(true),
+            Some(value) => Err(LakestreamError::ConfigError(format!(
+                "Invalid {}: '{}' (expected true or false)",
+                key, value
+            ))),
+        };
+
+        Ok(TraversalOptions {
+            symlinks,
+            one_filesystem: bool_setting(ONE_FILESYSTEM_KEY)?,
+            keep_going: bool_setting(KEEP_GOING_KEY)?,
+            metadata: bool_setting(METADATA_KEY)?,
+            xattrs: bool_setting(XATTRS_KEY)?,
+            ignore_file: !bool_setting(NO_IGNORE_KEY)?,
+        })
+    }
+}
+
+/// Directories to list as buckets, separated like PATH (':' on unix). None
+/// if not configured.
+pub fn configured_roots(config: &Config) -> Option<Vec<PathBuf>> {
+    setting(config, ROOTS_KEY).map(|roots| {
+        env::split_paths(&roots)
+            .filter(|root| !root.as_os_str().is_empty())
+            .collect()
+    })
+}
+
+// from the Config first, then the environment
+fn setting(config: &Config, key: &str) -> Option<String> {
+    config.get(key).cloned().or_else(|| env::var(key).ok())
+}
@@ -0,0 +1,223 @@
+use std::fs::{self, File, Metadata};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+
+use async_trait::async_trait;
+
+use crate::utils::blocking::spawn_blocking;
+
+/// Directory entry, with the metadata that is read along with it.
+pub struct DirEntry {
+    pub path: PathBuf,
+    /// metadata of the entry itself, not following symlinks
+    pub metadata: Metadata,
+    /// for symlinks: the path it points to, and the metadata of that path
+    /// unless the link is dangling
+    pub symlink: Option<(PathBuf, Option<Metadata>)>,
+    /// extended attributes, if requested
+    pub xattrs: Vec<(String, String)>,
+}
+
+#[async_trait(?Send)]
+pub trait FileSystem {
+    /// entries of a directory, each of which may fail on its own
+    async fn read_dir(
+        &self,
+        path: &Path,
+        xattrs: bool,
+    ) -> io::Result<Vec<io::Result<DirEntry>>>;
+    /// extended attributes in the "user." namespace, following symlinks
+    async fn xattrs(&self, path: &Path) -> io::Result<Vec<(String, String)>>;
+    /// metadata of a path, following symlinks
+    async fn metadata(&self, path: &Path) -> io::Result<Metadata>;
+    async fn read(&self, path: &Path) -> io::Result<Vec<u8>>;
+    /// Read a file into `writer` in chunks, so it does not have to fit in
+    /// memory. The default reads it at once.
+    async fn read_to_writer(
+        &self,
+        path: &Path,
+        writer: &mut dyn Write,
+    ) -> io::Result<()> {
+        writer.write_all(&self.read(path).await?)
+    }
+    async fn write(&self, path: &Path, data: &[u8]) -> io::Result<()>;
+}
+
+const READ_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Local filesystem. Each (blocking) std::fs call runs on the blocking
+/// thread pool of the runtime, so slow disks or network mounts do not stall
+/// the executor.
+pub struct LocalFileSystem;
+
+#[async_trait(?Send)]
+impl FileSystem for LocalFileSystem {
+    async fn read_dir(
+        &self,
+        path: &Path,
+        xattrs: bool,
+    ) -> io::Result<Vec<io::Result<DirEntry>>> {
+        let path = path.to_owned();
+        spawn_blocking(move || {
+            let entries = fs::read_dir(&path)?
+                .map(|entry| {
+                    let entry = entry.map_err(|err| with_path(err, &path))?;
+                    read_entry(entry.path(), xattrs)
+                })
+                // entries that disappear while reading are left out
+                .filter(|entry| {
+                    !matches!(entry, Err(err) if err.kind() == io::ErrorKind::NotFound)
+                })
+                .collect();
+            Ok(entries)
+        })
+        .await
+    }
+
+    async fn metadata(&self, path: &Path) -> io::Result<Metadata> {
+        let path = path.to_owned();
+        spawn_blocking(move || fs::metadata(path)).await
+    }
+
+    async fn xattrs(&self, path: &Path) -> io::Result<Vec<(String, String)>> {
+        let path = path.to_owned();
+        spawn_blocking(move || read_xattrs(&path)).await
+    }
+
+    async fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        let path = path.to_owned();
+        spawn_blocking(move || {
+            check_is_file(&path)?;
+            fs::read(path)
+        })
+        .await
+    }
+
+    async fn read_to_writer(
+        &self,
+        path: &Path,
+        writer: &mut dyn Write,
+    ) -> io::Result<()> {
+        let path = path.to_owned();
+        let mut file = spawn_blocking(move || {
+            check_is_file(&path)?;
+            File::open(path)
+        })
+        .await?;
+        let mut buffer = vec![0; READ_CHUNK_SIZE];
+        loop {
+            // file and buffer move to the blocking thread and back
+            let (returned_file, returned_buffer, length) =
+                spawn_blocking(move || {
+                    let length = file.read(&mut buffer)?;
+                    Ok((file, buffer, length))
+                })
+                .await?;
+            if length == 0 {
+                return Ok(());
+            }
+            writer.write_all(&returned_buffer[..length])?;
+            (file, buffer) = (returned_file, returned_buffer);
+        }
+    }
+
+    async fn write(&self, path: &Path, data: &[u8]) -> io::Result<()> {
+        let path = path.to_owned();
+        let data = data.to_vec();
+        spawn_blocking(move || {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(path, data)
+        })
+        .await
+    }
+}
+
+// directories and such are not objects
+fn check_is_file(path: &Path) -> io::Result<()> {
+    if !fs::metadata(path)?.is_file() {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("{} is not a file", path.display()),
+        ));
+    }
+    Ok(())
+}
+
+fn read_entry(path: PathBuf, xattrs: bool) -> io::Result<DirEntry> {
+    let metadata =
+        fs::symlink_metadata(&path).map_err(|err| with_path(err, &path))?;
+    let symlink = if metadata.is_symlink() {
+        let target =
+            fs::read_link(&path).map_err(|err| with_path(err, &path))?;
+        Some((target, fs::metadata(&path).ok()))
+    } else {
+        None
+    };
+    // dangling symlinks have no attributes to read
+    let xattrs = match &symlink {
+        Some((_, None)) => Vec::new(),
+        _ if xattrs => read_xattrs(&path)?,
+        _ => Vec::new(),
+    };
+    Ok(DirEntry {
+        path,
+        metadata,
+        symlink,
+        xattrs,
+    })
+}
+
+#[cfg(unix)]
+fn read_xattrs(path: &Path) -> io::Result<Vec<(String, String)>> {
+    let names = match xattr::list(path) {
+        Ok(names) => names,
+        // filesystem without extended attributes
+        Err(err) if err.kind() == io::ErrorKind::Unsupported => {
+            return Ok(Vec::new())
+        }
+        Err(err) => return Err(with_path(err, path)),
+    };
+    let mut xattrs = Vec::new();
+    for name in names {
+        let Some(name) = name.to_str().filter(|n| n.starts_with("user."))
+        else {
+            continue;
+        };
+        // None if removed since it was listed
+        if let Some(value) =
+            xattr::get(path, name).map_err(|err| with_path(err, path))?
+        {
+            xattrs.push((
+                name.to_string(),
+                String::from_utf8_lossy(&value).to_string(),
+            ));
+        }
+    }
+    Ok(xattrs)
+}
+
+#[cfg(not(unix))]
+fn read_xattrs(_path: &Path) -> io::Result<Vec<(String, String)>> {
+    Ok(Vec::new())
+}
+
+/// Adds the path to the message of an error, keeping its kind.
+pub fn with_path(err: io::Error, path: &Path) -> io::Error {
+    io::Error::new(err.kind(), format!("{}: {}", path.display(), err))
+}
+
+/// Device and inode number, which identify a directory no matter through
+/// which path (or symlink) it is reached.
+#[cfg(unix)]
+pub fn file_id(metadata: &Metadata) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+    Some((metadata.dev(), metadata.ino()))
+}
+
+// std has no stable equivalent on other platforms
+#[cfg(not(unix))]
+pub fn file_id(_metadata: &Metadata) -> Option<(u64, u64)> {
+    None
+}
@@ -1,40 +1,53 @@
 // localfs/get.rs
 
-use std::fs;
-use std::io::Read;
+use std::io::{self, Write};
 use std::path::Path;
 
+use super::file_system::FileSystem;
+use super::put::object_path;
 use crate::LakestreamError;
 
 pub async fn get_object(
+    fs: &dyn FileSystem,
     path: &Path,
     key: &str,
     data: &mut Vec<u8>,
 ) -> Result<(), LakestreamError> {
-    let object_path = path.join(key);
-
-    if object_path.is_file() {
-        let mut file = fs::File::open(&object_path).map_err(|err| {
-            LakestreamError::InternalError(format!(
-                "Failed to open file {}: {}",
-                object_path.display(),
-                err
-            ))
-        })?;
+    let object_path = object_path(path, key)?;
+    let result = fs.read(&object_path).await.map(|contents| {
+        *data = contents;
+    });
+    map_read_error(result, &object_path, key)
+}
 
-        file.read_to_end(data).map_err(|err| {
-            LakestreamError::InternalError(format!(
-                "Failed to read file {}: {}",
-                object_path.display(),
-                err
-            ))
-        })?;
+pub async fn get_object_to_writer(
+    fs: &dyn FileSystem,
+    path: &Path,
+    key: &str,
+    writer: &mut dyn Write,
+) -> Result<(), LakestreamError> {
+    let object_path = object_path(path, key)?;
+    let result = fs.read_to_writer(&object_path, writer).await;
+    map_read_error(result, &object_path, key)
+}
 
-        Ok(())
-    } else {
-        Err(LakestreamError::NotFound(format!(
-            "Object not found for key: {}",
-            key
-        )))
+fn map_read_error(
+    result: io::Result<()>,
+    object_path: &Path,
+    key: &str,
+) -> Result<(), LakestreamError> {
+    match result {
+        Ok(()) => Ok(()),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {
+            Err(LakestreamError::NotFound(format!(
+                "Object not found for key: {}",
+                key
+            )))
+        }
+        Err(err) => Err(LakestreamError::InternalError(format!(
+            "Failed to read file {}: {}",
+            object_path.display(),
+            err
+        ))),
     }
 }
@@ -1,90 +1,333 @@
-use std::fs;
-use std::path::Path;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fs::Metadata;
+use std::io;
+use std::path::{Component, Path, PathBuf};
 
-use super::bucket::FileSystem;
-use crate::{FileObject, FileObjectFilter, FileObjectVec};
+use futures::future::join_all;
+use log::{error, info, warn};
 
-pub async fn list_files(
-    fs: &dyn FileSystem,
-    path: &Path,
-    max_keys: Option<u32>,
-    recursive: bool,
-    filter: &Option<FileObjectFilter>,
-    file_objects: &mut FileObjectVec,
-) {
-    list_files_next(fs, path, max_keys, recursive, filter, file_objects).await;
-}
+use super::config::{SymlinkPolicy, TraversalOptions};
+use super::file_system::{file_id, with_path, DirEntry, FileSystem};
+use super::metadata::PosixTags;
+use crate::base::ignore_rules::IgnoreRules;
+use crate::{FileObject, FileObjectFilter, FileObjectVec, LakestreamError};
 
-async fn list_files_next(
-    fs: &dyn FileSystem,
-    path: &Path,
-    max_keys: Option<u32>,
-    recursive: bool,
-    filter: &Option<FileObjectFilter>,
-    file_objects: &mut FileObjectVec,
-) {
-    let mut directory_stack = vec![path.to_owned()];
+// number of directories that are read in parallel
+const READ_DIR_CONCURRENCY: usize = 16;
 
-    while let Some(current_path) = directory_stack.pop() {
-        let mut temp_file_objects = Vec::new();
+// exclude patterns for the directory it is in, with gitignore semantics
+const IGNORE_FILE: &str = ".lakestreamignore";
 
-        if let Ok(entries) = fs.read_dir(&current_path) {
-            for entry in entries.flatten() {
-                if let Some(max_keys) = max_keys {
-                    if file_objects.len() >= max_keys as usize {
-                        break;
+pub struct Lister<'a> {
+    pub fs: &'a dyn FileSystem,
+    /// directory of the bucket, keys are relative to it
+    pub root: &'a Path,
+    pub options: TraversalOptions,
+}
+
+impl Lister<'_> {
+    pub async fn list_files(
+        &self,
+        path: &Path,
+        max_keys: Option<u32>,
+        recursive: bool,
+        filter: &Option<FileObjectFilter>,
+        file_objects: &mut FileObjectVec,
+    ) -> Result<(), LakestreamError> {
+        let max_keys = max_keys.map(|keys| keys as usize);
+        let path_metadata =
+            self.fs
+                .metadata(path)
+                .await
+                .map_err(|err| match err.kind() {
+                    io::ErrorKind::NotFound => {
+                        LakestreamError::NotFound(path.display().to_string())
                     }
-                }
+                    _ => LakestreamError::Io(with_path(err, path)),
+                })?;
 
-                let metadata = match entry.metadata() {
-                    Ok(md) => md,
-                    Err(_) => continue,
-                };
+        // filters on owner, group or mode need the metadata tags
+        let posix_tags = (self.options.metadata
+            || filter
+                .as_ref()
+                .is_some_and(FileObjectFilter::uses_posix_metadata))
+        .then(PosixTags::new);
+        let posix_tags = posix_tags.as_ref();
+
+        if !path_metadata.is_dir() {
+            // the URI points to a single file
+            if !path_metadata.is_file() {
+                return Ok(());
+            }
+            let xattrs = if self.options.xattrs {
+                self.fs
+                    .xattrs(path)
+                    .await
+                    .map_err(|err| LakestreamError::Io(with_path(err, path)))?
+            } else {
+                Vec::new()
+            };
+            let tags = metadata_tags(posix_tags, &path_metadata, xattrs);
+            let file_object = object_key(self.root, path)
+                .and_then(|key| handle_file(key, &path_metadata, tags, filter));
+            file_objects.extend_async(file_object).await;
+            return Ok(());
+        }
+
+        let ignore_rules = self.ignore_rules(path).await?;
+        let mut error_count = 0;
+        let root_device = file_id(&path_metadata).map(|(device, _)| device);
+
+        // directories seen so far, to not list them twice when symlinks
+        // (or bind mounts) form a loop
+        let mut visited: HashSet<(u64, u64)> =
+            file_id(&path_metadata).into_iter().collect();
+        let mut directory_queue = VecDeque::from([path.to_owned()]);
+
+        while !directory_queue.is_empty() {
+            let count = directory_queue.len().min(READ_DIR_CONCURRENCY);
+            let directories: Vec<PathBuf> =
+                directory_queue.drain(..count).collect();
+            let results = join_all(directories.iter().map(|directory| {
+                self.fs.read_dir(directory, self.options.xattrs)
+            }))
+            .await;
 
-                if metadata.is_file() {
-                    let file_object = handle_file(&entry, filter);
-                    if let Some(file_object) = file_object {
-                        temp_file_objects.push(file_object);
+            for (directory, entries) in directories.iter().zip(results) {
+                let entries = match entries {
+                    Ok(entries) => entries,
+                    // removed since it was listed
+                    Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                        continue
                     }
-                } else if metadata.is_dir() {
-                    let dir_name = entry.path().to_string_lossy().to_string();
-
-                    // Only add directory object when no filter is provided
-                    if filter.is_none() {
-                        let dir_object =
-                            FileObject::new(dir_name, 0, None, None);
-                        temp_file_objects.push(dir_object);
+                    Err(err) => {
+                        self.handle_error(
+                            with_path(err, directory),
+                            &mut error_count,
+                        )?;
+                        continue;
                     }
+                };
+                let mut temp_file_objects = Vec::new();
+
+                for entry in entries {
+                    let mut entry = match entry {
+                        Ok(entry) => entry,
+                        Err(err) => {
+                            self.handle_error(err, &mut error_count)?;
+                            continue;
+                        }
+                    };
+                    let Some(key) = object_key(self.root, &entry.path) else {
+                        warn!(
+                            "Skipping path that is not valid UTF-8: {}",
+                            entry.path.display()
+                        );
+                        continue;
+                    };
+                    // ignore file patterns are relative to the listed path
+                    let ignored = |is_dir: bool| {
+                        ignore_rules.as_ref().is_some_and(|rules| {
+                            object_key(path, &entry.path).is_some_and(
+                                |relative| rules.is_excluded(&relative, is_dir),
+                            )
+                        })
+                    };
 
-                    if recursive {
-                        directory_stack.push(entry.path());
+                    let metadata = match &entry.symlink {
+                        None => &entry.metadata,
+                        Some((target, target_metadata)) => {
+                            match (self.options.symlinks, target_metadata) {
+                                (SymlinkPolicy::Skip, _) => continue,
+                                (SymlinkPolicy::List, _) => {
+                                    if ignored(false) {
+                                        continue;
+                                    }
+                                    let file_object = handle_symlink(
+                                        key, &entry, target, posix_tags,
+                                    );
+                                    if filter.as_ref().is_none_or(|filter| {
+                                        filter.matches(&file_object)
+                                    }) {
+                                        temp_file_objects.push(file_object);
+                                    }
+                                    continue;
+                                }
+                                (SymlinkPolicy::Follow, Some(metadata)) => {
+                                    metadata
+                                }
+                                (SymlinkPolicy::Follow, None) => {
+                                    warn!(
+                                        "Skipping dangling symlink: {}",
+                                        entry.path.display()
+                                    );
+                                    continue;
+                                }
+                            }
+                        }
+                    };
+
+                    // excluded directories are not descended into
+                    if ignored(metadata.is_dir())
+                        || (metadata.is_dir()
+                            && filter.as_ref().is_some_and(|filter| {
+                                filter.excludes_directory(&key)
+                            }))
+                    {
+                        continue;
                     }
+
+                    let tags = metadata_tags(
+                        posix_tags,
+                        metadata,
+                        std::mem::take(&mut entry.xattrs),
+                    );
+                    if metadata.is_file() {
+                        let file_object =
+                            handle_file(key, metadata, tags, filter);
+                        if let Some(file_object) = file_object {
+                            temp_file_objects.push(file_object);
+                        }
+                    } else if metadata.is_dir() {
+                        // Only add directory object when no filter is provided
+                        if filter.is_none() {
+                            let dir_object = FileObject::new(
+                                format!("{}/", key),
+                                0,
+                                None,
+                                tags,
+                            );
+                            temp_file_objects.push(dir_object);
+                        }
+
+                        if recursive
+                            && self.descend(
+                                &entry,
+                                metadata,
+                                root_device,
+                                &mut visited,
+                            )
+                        {
+                            directory_queue.push_back(entry.path);
+                        }
+                    }
+                }
+
+                if let Some(max_keys) = max_keys {
+                    temp_file_objects
+                        .truncate(max_keys.saturating_sub(file_objects.len()));
+                }
+                file_objects.extend_async(temp_file_objects).await;
+
+                if max_keys
+                    .is_some_and(|max_keys| file_objects.len() >= max_keys)
+                {
+                    return Ok(());
                 }
             }
         }
-        file_objects.extend_async(temp_file_objects).await;
+
+        if error_count > 0 {
+            error!(
+                "Listing {} skipped {} path(s) that could not be read",
+                path.display(),
+                error_count
+            );
+        }
+        Ok(())
+    }
+
+    // rules from the ignore file in the listed directory, if there is one
+    async fn ignore_rules(
+        &self,
+        path: &Path,
+    ) -> Result<Option<IgnoreRules>, LakestreamError> {
+        if !self.options.ignore_file {
+            return Ok(None);
+        }
+        let ignore_file = path.join(IGNORE_FILE);
+        let contents = match self.fs.read(&ignore_file).await {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                return Ok(None)
+            }
+            Err(err) => {
+                return Err(LakestreamError::Io(with_path(err, &ignore_file)))
+            }
+        };
+        IgnoreRules::parse(&String::from_utf8_lossy(&contents))
+            .map(Some)
+            .map_err(|err| {
+                LakestreamError::ConfigError(format!(
+                    "{}: {}",
+                    ignore_file.display(),
+                    err
+                ))
+            })
+    }
+
+    // fails the listing, or with keep_going, reports and counts the error
+    fn handle_error(
+        &self,
+        err: io::Error,
+        error_count: &mut usize,
+    ) -> Result<(), LakestreamError> {
+        if !self.options.keep_going {
+            return Err(LakestreamError::Io(err));
+        }
+        error!("{}", err);
+        *error_count += 1;
+        Ok(())
+    }
+
+    // whether to list the contents of a directory in a recursive listing
+    fn descend(
+        &self,
+        entry: &DirEntry,
+        metadata: &Metadata,
+        root_device: Option<u64>,
+        visited: &mut HashSet<(u64, u64)>,
+    ) -> bool {
+        let Some((device, inode)) = file_id(metadata) else {
+            // without a way to detect loops, do not follow directory links
+            return entry.symlink.is_none();
+        };
+        if self.options.one_filesystem && Some(device) != root_device {
+            info!("Not crossing filesystem at: {}", entry.path.display());
+            return false;
+        }
+        if !visited.insert((device, inode)) {
+            warn!("Skipping directory loop at: {}", entry.path.display());
+            return false;
+        }
+        true
     }
 }
 
+// path relative to root, with '/' as separator like the keys of other
+// object stores. None if the path is not valid UTF-8.
+fn object_key(root: &Path, path: &Path) -> Option<String> {
+    let relative_path = path.strip_prefix(root).unwrap_or(path);
+    let components = relative_path
+        .components()
+        .filter_map(|component| match component {
+            Component::Normal(name) => Some(name.to_str()),
+            _ => None,
+        })
+        .collect::<Option<Vec<&str>>>()?;
+    Some(components.join("/"))
+}
+
 fn handle_file(
-    entry: &fs::DirEntry,
+    file_name: String,
+    metadata: &Metadata,
+    tags: Option<HashMap<String, String>>,
     filter: &Option<FileObjectFilter>,
 ) -> Option<FileObject> {
-    let metadata = match entry.metadata() {
-        Ok(md) => md,
-        Err(_) => return None,
-    };
-
-    let file_name = entry.path().to_string_lossy().to_string();
     let file_size = metadata.len();
-    let modified = metadata.modified().ok().map(|mtime| {
-        mtime
-            .duration_since(std::time::UNIX_EPOCH)
-            .map(|duration| duration.as_secs())
-            .unwrap_or(0)
-    });
+    let modified = modified(metadata);
 
-    let file_object = FileObject::new(file_name, file_size, modified, None);
+    let file_object = FileObject::new(file_name, file_size, modified, tags);
 
     // Check if the file_object satisfies the filter conditions
     if let Some(ref filter) = filter {
@@ -95,3 +338,46 @@ fn handle_file(
 
     Some(file_object)
 }
+
+// the link itself, with the path it points to as tag
+fn handle_symlink(
+    key: String,
+    entry: &DirEntry,
+    target: &Path,
+    posix_tags: Option<&PosixTags>,
+) -> FileObject {
+    let mut tags = metadata_tags(posix_tags, &entry.metadata, Vec::new())
+        .unwrap_or_default();
+    tags.insert(
+        "SymlinkTarget".to_string(),
+        target.to_string_lossy().to_string(),
+    );
+    FileObject::new(
+        key,
+        entry.metadata.len(),
+        modified(&entry.metadata),
+        Some(tags),
+    )
+}
+
+// POSIX metadata and extended attributes, as far as requested
+fn metadata_tags(
+    posix_tags: Option<&PosixTags>,
+    metadata: &Metadata,
+    xattrs: Vec<(String, String)>,
+) -> Option<HashMap<String, String>> {
+    let mut tags = posix_tags
+        .map(|posix_tags| posix_tags.tags(metadata))
+        .unwrap_or_default();
+    tags.extend(xattrs);
+    (!tags.is_empty()).then_some(tags)
+}
+
+fn modified(metadata: &Metadata) -> Option<u64> {
+    metadata.modified().ok().map(|mtime| {
+        mtime
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or(0)
+    })
+}
@@ -0,0 +1,56 @@
+use std::collections::HashMap;
+use std::fs::Metadata;
+
+/// Owner, group, permission bits and inode of files as tags. User and
+/// group names are looked up once per id.
+pub struct PosixTags {
+    #[cfg(unix)]
+    users: uzers::UsersCache,
+}
+
+impl PosixTags {
+    pub fn new() -> PosixTags {
+        PosixTags {
+            #[cfg(unix)]
+            users: uzers::UsersCache::new(),
+        }
+    }
+
+    #[cfg(unix)]
+    pub fn tags(&self, metadata: &Metadata) -> HashMap<String, String> {
+        use std::os::unix::fs::MetadataExt;
+        use uzers::{Groups, Users};
+
+        let uid = metadata.uid();
+        let gid = metadata.gid();
+        // ids without a name (e.g. from another system) are shown as number
+        let owner = self
+            .users
+            .get_user_by_uid(uid)
+            .map(|user| user.name().to_string_lossy().to_string())
+            .unwrap_or_else(|| uid.to_string());
+        let group = self
+            .users
+            .get_group_by_gid(gid)
+            .map(|group| group.name().to_string_lossy().to_string())
+            .unwrap_or_else(|| gid.to_string());
+
+        [
+            ("Owner", owner),
+            ("Uid", uid.to_string()),
+            ("Group", group),
+            ("Gid", gid.to_string()),
+            ("Mode", format!("{:04o}", metadata.mode() & 0o7777)),
+            ("Inode", metadata.ino().to_string()),
+        ]
+        .into_iter()
+        .map(|(key, value)| (key.to_string(), value))
+        .collect()
+    }
+
+    // no POSIX ownership or permissions on other platforms
+    #[cfg(not(unix))]
+    pub fn tags(&self, _metadata: &Metadata) -> HashMap<String, String> {
+        HashMap::new()
+    }
+}
@@ -1,5 +1,10 @@
 // expose to library via backend mod
 pub mod backend;
 mod bucket;
+mod config;
+mod file_system;
 mod get;
 mod list;
+mod metadata;
+mod mounts;
+mod put;
@@ -0,0 +1,212 @@
+use std::io;
+use std::path::{Path, PathBuf};
+
+use log::{info, warn};
+
+/// Capacity and usage of the filesystem a bucket is on, in bytes.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DiskUsage {
+    /// filesystem type, for buckets listed from the mount table
+    pub fs_type: Option<String>,
+    pub total: u64,
+    pub used: u64,
+    pub available: u64,
+}
+
+/// Directories to list as buckets: the configured roots, or else the
+/// mounted filesystems. Pseudo filesystems (proc, sysfs, cgroup, ...) have
+/// no capacity, and network filesystems (nfs, cifs, sshfs, ...) can hang
+/// when stale, so both are left out without being queried.
+pub fn local_roots(
+    roots: Option<Vec<PathBuf>>,
+) -> io::Result<Vec<(PathBuf, Option<DiskUsage>)>> {
+    if let Some(roots) = roots {
+        return Ok(roots
+            .into_iter()
+            .filter(|root| {
+                let is_dir = root.is_dir();
+                if !is_dir {
+                    warn!("Skipping root that is not a directory: {:?}", root);
+                }
+                is_dir
+            })
+            .map(|root| {
+                let usage = disk_usage(&root, None).ok();
+                (root, usage)
+            })
+            .collect());
+    }
+
+    let mut roots: Vec<(PathBuf, Option<DiskUsage>)> = Vec::new();
+    for (mount_point, fs_type) in mounts()? {
+        if is_skipped_fs_type(&fs_type) {
+            continue;
+        }
+        let usage = match disk_usage(&mount_point, Some(fs_type)) {
+            Ok(usage) if usage.total > 0 => usage,
+            Ok(_) => continue,
+            Err(err) => {
+                info!("Skipping mount point {:?}: {}", mount_point, err);
+                continue;
+            }
+        };
+        // a later mount on the same path hides the earlier one
+        roots.retain(|(root, _)| *root != mount_point);
+        roots.push((mount_point, Some(usage)));
+    }
+    Ok(roots)
+}
+
+#[cfg(unix)]
+fn disk_usage(path: &Path, fs_type: Option<String>) -> io::Result<DiskUsage> {
+    let stat = rustix::fs::statvfs(path)?;
+    let block_size = stat.f_frsize;
+    Ok(DiskUsage {
+        fs_type,
+        total: stat.f_blocks * block_size,
+        used: stat.f_blocks.saturating_sub(stat.f_bfree) * block_size,
+        available: stat.f_bavail * block_size,
+    })
+}
+
+#[cfg(not(unix))]
+fn disk_usage(_path: &Path, _fs_type: Option<String>) -> io::Result<DiskUsage> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "disk usage is not supported on this platform",
+    ))
+}
+
+// filesystem types of the mount table that are not listed: a statvfs on a
+// stale network mount blocks until the server responds, and on an automount
+// point it triggers the mount
+const SKIPPED_FS_TYPES: &[&str] = &[
+    // network
+    "nfs",
+    "nfs4",
+    "cifs",
+    "smb3",
+    "smbfs",
+    "ceph",
+    "glusterfs",
+    "9p",
+    "afs",
+    "fuse.sshfs",
+    "fuse.rclone",
+    "fuse.s3fs",
+    "fuse.glusterfs",
+    "fuse.gvfsd-fuse",
+    // pseudo
+    "autofs",
+    "binfmt_misc",
+    "bpf",
+    "cgroup",
+    "cgroup2",
+    "configfs",
+    "debugfs",
+    "devpts",
+    "efivarfs",
+    "fusectl",
+    "fuse.portal",
+    "hugetlbfs",
+    "mqueue",
+    "nsfs",
+    "proc",
+    "pstore",
+    "rpc_pipefs",
+    "securityfs",
+    "selinuxfs",
+    "sysfs",
+    "tracefs",
+];
+
+fn is_skipped_fs_type(fs_type: &str) -> bool {
+    SKIPPED_FS_TYPES.contains(&fs_type)
+}
+
+// mount points with their filesystem type
+#[cfg(target_os = "linux")]
+fn mounts() -> io::Result<Vec<(PathBuf, String)>> {
+    Ok(parse_mounts(&std::fs::read_to_string("/proc/self/mounts")?))
+}
+
+// without a mount table, only configured roots are listed
+#[cfg(not(target_os = "linux"))]
+fn mounts() -> io::Result<Vec<(PathBuf, String)>> {
+    Ok(Vec::new())
+}
+
+// lines of "device mount_point fs_type options dump pass"
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_mounts(contents: &str) -> Vec<(PathBuf, String)> {
+    contents
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let _device = fields.next()?;
+            let mount_point = unescape(fields.next()?);
+            let fs_type = fields.next()?.to_string();
+            Some((PathBuf::from(mount_point), fs_type))
+        })
+        .collect()
+}
+
+// spaces, tabs, newlines and backslashes in a mount point are written as
+// octal escapes, e.g. "\040" for a space
+fn unescape(field: &str) -> String {
+    let bytes = field.as_bytes();
+    let mut unescaped = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        let escaped = bytes
+            .get(index + 1..index + 4)
+            .filter(|_| bytes[index] == b'\\')
+            .and_then(|octal| std::str::from_utf8(octal).ok())
+            .and_then(|octal| u8::from_str_radix(octal, 8).ok());
+        match escaped {
+            Some(byte) => {
+                unescaped.push(byte);
+                index += 4;
+            }
+            None => {
+                unescaped.push(bytes[index]);
+                index += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&unescaped).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_mounts() {
+        let contents = "\
+/dev/sda1 / ext4 rw,relatime 0 0
+proc /proc proc rw,nosuid,nodev,noexec 0 0
+/dev/sdb1 /mnt/backup\\040disk xfs rw 0 0
+";
+        assert_eq!(
+            parse_mounts(contents),
+            vec![
+                (PathBuf::from("/"), "ext4".to_string()),
+                (PathBuf::from("/proc"), "proc".to_string()),
+                (PathBuf::from("/mnt/backup disk"), "xfs".to_string()),
+            ]
+        );
+        assert_eq!(unescape("a\\134b\\011"), "a\\b\t");
+        assert_eq!(unescape("trailing\\04"), "trailing\\04");
+    }
+
+    #[test]
+    fn test_is_skipped_fs_type() {
+        for fs_type in ["nfs4", "cifs", "fuse.sshfs", "proc", "cgroup2"] {
+            assert!(is_skipped_fs_type(fs_type), "{}", fs_type);
+        }
+        for fs_type in ["ext4", "xfs", "btrfs", "tmpfs", "fuseblk"] {
+            assert!(!is_skipped_fs_type(fs_type), "{}", fs_type);
+        }
+    }
+}
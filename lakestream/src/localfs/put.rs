@@ -0,0 +1,40 @@
+// localfs/put.rs
+
+use std::path::{Component, Path, PathBuf};
+
+use super::file_system::FileSystem;
+use crate::LakestreamError;
+
+pub async fn put_object(
+    fs: &dyn FileSystem,
+    path: &Path,
+    key: &str,
+    data: &[u8],
+) -> Result<(), LakestreamError> {
+    let object_path = object_path(path, key)?;
+
+    // parent directories are created as needed
+    fs.write(&object_path, data).await.map_err(|err| {
+        LakestreamError::InternalError(format!(
+            "Failed to write file {}: {}",
+            object_path.display(),
+            err
+        ))
+    })
+}
+
+/// Path of an object in the bucket directory. Keys are relative paths
+/// within it, so keys with '..' or a root would reach outside of the bucket
+/// and are rejected.
+pub fn object_path(path: &Path, key: &str) -> Result<PathBuf, LakestreamError> {
+    let is_within_bucket = Path::new(key).components().all(|component| {
+        matches!(component, Component::Normal(_) | Component::CurDir)
+    });
+    if !is_within_bucket {
+        return Err(LakestreamError::ConfigError(format!(
+            "Invalid key {}: keys are paths within the bucket",
+            key
+        )));
+    }
+    Ok(path.join(key))
+}
@@ -0,0 +1,31 @@
+use async_trait::async_trait;
+
+pub use super::bucket::MemoryBucket;
+use super::store::bucket_names;
+use crate::{
+    Config, LakestreamError, ObjectStore, ObjectStoreBackend, ObjectStoreVec,
+};
+
+pub struct MemoryBackend;
+
+#[async_trait(?Send)]
+impl ObjectStoreBackend for MemoryBackend {
+    fn new(_config: Config) -> Result<Self, LakestreamError> {
+        Ok(Self)
+    }
+
+    async fn list_buckets(
+        config: Config,
+        object_stores: &mut ObjectStoreVec,
+    ) -> Result<(), LakestreamError> {
+        let buckets = bucket_names()
+            .into_iter()
+            .map(|name| {
+                MemoryBucket::new(&name, config.clone())
+                    .map(ObjectStore::MemoryBucket)
+            })
+            .collect::<Result<Vec<ObjectStore>, LakestreamError>>()?;
+        object_stores.extend_async(buckets).await;
+        Ok(())
+    }
+}
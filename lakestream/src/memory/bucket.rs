@@ -0,0 +1,123 @@
+use async_trait::async_trait;
+
+use super::list::list_files;
+use super::store::{store, MemoryObject};
+use crate::base::config::Config;
+use crate::utils::time::system_time_in_seconds;
+use crate::{
+    FileObjectFilter, FileObjectVec, LakestreamError, ObjectStoreTrait,
+};
+
+/// Bucket in an in-process store that is shared by all `MemoryBucket`
+/// instances with the same name. Intended for tests and examples.
+#[derive(Clone)]
+pub struct MemoryBucket {
+    name: String,
+    config: Config,
+}
+
+impl MemoryBucket {
+    pub fn new(
+        name: &str,
+        config: Config,
+    ) -> Result<MemoryBucket, LakestreamError> {
+        if name.is_empty() || name.contains('/') {
+            return Err(LakestreamError::ConfigError(format!(
+                "Invalid memory bucket name: {}",
+                name
+            )));
+        }
+        Ok(MemoryBucket {
+            name: name.to_string(),
+            config,
+        })
+    }
+
+    /// Store an object, the bucket is created if it does not exist yet.
+    pub fn put_object(&self, key: &str, data: &[u8]) {
+        self.put_object_with_modified(key, data, system_time_in_seconds());
+    }
+
+    /// Store an object with an explicit modification time (epoch seconds).
+    pub fn put_object_with_modified(
+        &self,
+        key: &str,
+        data: &[u8],
+        modified: u64,
+    ) {
+        store()
+            .entry(self.name.clone())
+            .or_default()
+            .insert(key.to_string(), MemoryObject::new(data, modified));
+    }
+
+    pub fn delete_object(&self, key: &str) -> Result<(), LakestreamError> {
+        store()
+            .get_mut(&self.name)
+            .and_then(|objects| objects.remove(key))
+            .map(|_| ())
+            .ok_or_else(|| {
+                LakestreamError::NotFound(format!(
+                    "Object not found for key: {}",
+                    key
+                ))
+            })
+    }
+
+    /// Remove the bucket and all of its objects.
+    pub fn clear(&self) {
+        store().remove(&self.name);
+    }
+}
+
+#[async_trait(?Send)]
+impl ObjectStoreTrait for MemoryBucket {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn config(&self) -> &Config {
+        &self.config
+    }
+
+    async fn list_files(
+        &self,
+        prefix: Option<&str>,
+        recursive: bool,
+        max_keys: Option<u32>,
+        filter: &Option<FileObjectFilter>,
+        file_objects: &mut FileObjectVec,
+    ) -> Result<(), LakestreamError> {
+        list_files(self, prefix, recursive, max_keys, filter, file_objects)
+            .await
+    }
+
+    async fn get_object(
+        &self,
+        key: &str,
+        data: &mut Vec<u8>,
+    ) -> Result<(), LakestreamError> {
+        let object = store()
+            .get(&self.name)
+            .and_then(|objects| objects.get(key))
+            .cloned()
+            .ok_or_else(|| {
+                LakestreamError::NotFound(format!(
+                    "Object not found for key: {}",
+                    key
+                ))
+            })?;
+        data.clear();
+        data.extend_from_slice(&object.data);
+        Ok(())
+    }
+
+    async fn put_object(
+        &self,
+        key: &str,
+        data: &[u8],
+    ) -> Result<(), LakestreamError> {
+        MemoryBucket::put_object(self, key, data);
+        Ok(())
+    }
+}
@@ -0,0 +1,54 @@
+use super::bucket::MemoryBucket;
+use super::store::store;
+use crate::base::sorted_listing;
+use crate::{
+    FileObject, FileObjectFilter, FileObjectVec, LakestreamError,
+    ObjectStoreTrait,
+};
+
+pub async fn list_files(
+    memory_bucket: &MemoryBucket,
+    prefix: Option<&str>,
+    recursive: bool,
+    max_keys: Option<u32>,
+    filter: &Option<FileObjectFilter>,
+    file_objects: &mut FileObjectVec,
+) -> Result<(), LakestreamError> {
+    sorted_listing::list_files(
+        prefix,
+        recursive,
+        max_keys,
+        filter,
+        file_objects,
+        |prefix| list_prefix(memory_bucket.name(), prefix),
+    )
+    .await
+}
+
+// the store is only locked while a single level is listed
+fn list_prefix(
+    bucket_name: &str,
+    prefix: &str,
+) -> Result<Vec<FileObject>, LakestreamError> {
+    let store = store();
+    let objects = store.get(bucket_name).ok_or_else(|| {
+        LakestreamError::NotFound(format!("Bucket not found: {}", bucket_name))
+    })?;
+
+    Ok(sorted_listing::list_prefix(
+        objects,
+        prefix,
+        |key, object| {
+            FileObject::new(
+                key.clone(),
+                object.data.len() as u64,
+                Some(object.modified),
+                Some(
+                    [("ETag".to_string(), object.etag.clone())]
+                        .into_iter()
+                        .collect(),
+                ),
+            )
+        },
+    ))
+}
@@ -0,0 +1,5 @@
+// expose to library via backend mod
+pub mod backend;
+mod bucket;
+mod list;
+mod store;
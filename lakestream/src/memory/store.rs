@@ -0,0 +1,40 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock};
+
+use sha2::{Digest, Sha256};
+
+#[derive(Clone)]
+pub struct MemoryObject {
+    pub data: Arc<Vec<u8>>,
+    pub modified: u64,
+    pub etag: String,
+}
+
+impl MemoryObject {
+    pub fn new(data: &[u8], modified: u64) -> Self {
+        MemoryObject {
+            data: Arc::new(data.to_vec()),
+            modified,
+            etag: format!("{:x}", Sha256::digest(data)),
+        }
+    }
+}
+
+// keys are kept sorted, so listings are in lexicographic order like S3
+type Buckets = HashMap<String, BTreeMap<String, MemoryObject>>;
+
+static STORE: OnceLock<Mutex<Buckets>> = OnceLock::new();
+
+pub fn store() -> MutexGuard<'static, Buckets> {
+    STORE
+        .get_or_init(|| Mutex::new(HashMap::new()))
+        .lock()
+        // a panic while holding the lock can not leave the map half-updated
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+pub fn bucket_names() -> Vec<String> {
+    let mut names: Vec<String> = store().keys().cloned().collect();
+    names.sort();
+    names
+}
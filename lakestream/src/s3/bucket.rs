@@ -1,11 +1,18 @@
+use std::io::Write;
+
 use async_trait::async_trait;
 
-use super::get::get_object;
+use super::get::{
+    get_object, get_object_if_none_match, get_object_to_writer,
+    get_object_with_content_encoding,
+};
 use super::list::list_files;
+use super::put::put_object;
 use crate::base::config::Config;
 use crate::s3::config::validate_config;
 use crate::{
-    FileObjectFilter, FileObjectVec, LakestreamError, ObjectStoreTrait,
+    ConditionalGet, FileObjectFilter, FileObjectVec, LakestreamError,
+    ObjectStoreTrait,
 };
 
 #[derive(Clone)]
@@ -95,6 +102,39 @@ impl ObjectStoreTrait for S3Bucket {
     ) -> Result<(), LakestreamError> {
         get_object(self, key, data).await
     }
+
+    async fn get_object_to_writer(
+        &self,
+        key: &str,
+        writer: &mut dyn Write,
+    ) -> Result<(), LakestreamError> {
+        get_object_to_writer(self, key, writer).await
+    }
+
+    async fn put_object(
+        &self,
+        key: &str,
+        data: &[u8],
+    ) -> Result<(), LakestreamError> {
+        put_object(self, key, data).await
+    }
+
+    async fn get_object_if_none_match(
+        &self,
+        key: &str,
+        etag: Option<&str>,
+        data: &mut Vec<u8>,
+    ) -> Result<ConditionalGet, LakestreamError> {
+        get_object_if_none_match(self, key, etag, data).await
+    }
+
+    async fn get_object_with_content_encoding(
+        &self,
+        key: &str,
+        data: &mut Vec<u8>,
+    ) -> Result<Option<String>, LakestreamError> {
+        get_object_with_content_encoding(self, key, data).await
+    }
 }
 
 pub fn configure_bucket_url(
@@ -19,6 +19,11 @@ pub trait Headers {
         &mut self,
         object_key: &str,
     ) -> Result<HashMap<String, String>, LakestreamError>;
+    fn generate_put_object_headers(
+        &mut self,
+        object_key: &str,
+        payload_hash: &str,
+    ) -> Result<HashMap<String, String>, LakestreamError>;
     fn create_list_objects_query_string(
         &self,
         prefix: Option<&str>,
@@ -79,6 +84,22 @@ impl Headers for S3Client {
         )
     }
 
+    fn generate_put_object_headers(
+        &mut self,
+        object_key: &str,
+        payload_hash: &str,
+    ) -> Result<HashMap<String, String>, LakestreamError> {
+        self.resource = Some(object_key.to_string());
+        let method = "PUT";
+        self.request_builder.generate_headers(
+            self.config(),
+            method,
+            self.resource.as_deref(),
+            self.query_string.as_deref(),
+            Some(payload_hash),
+        )
+    }
+
     fn create_list_objects_query_string(
         &self,
         prefix: Option<&str>,
@@ -1,33 +1,107 @@
+use std::collections::HashMap;
+use std::io::Write;
+
 use log::info;
 
 use super::bucket::S3Bucket;
 use super::client_headers::Headers;
 use super::list::create_s3_client;
-use super::request_handler::http_get_with_redirect_handling;
-use crate::{LakestreamError, ObjectStoreTrait};
+use super::request_handler::http_get_with_redirect_handling_to_writer;
+use crate::{ConditionalGet, LakestreamError, ObjectStoreTrait};
 
 pub async fn get_object(
     s3_bucket: &S3Bucket,
     object_key: &str,
     data: &mut Vec<u8>,
 ) -> Result<(), LakestreamError> {
+    get_object_if_none_match(s3_bucket, object_key, None, data).await?;
+    Ok(())
+}
+
+pub async fn get_object_to_writer(
+    s3_bucket: &S3Bucket,
+    object_key: &str,
+    writer: &mut dyn Write,
+) -> Result<(), LakestreamError> {
+    get_object_with_headers(s3_bucket, object_key, None, writer).await?;
+    Ok(())
+}
+
+pub async fn get_object_if_none_match(
+    s3_bucket: &S3Bucket,
+    object_key: &str,
+    etag: Option<&str>,
+    data: &mut Vec<u8>,
+) -> Result<ConditionalGet, LakestreamError> {
+    // data is left untouched if the object is not modified
+    let mut body = Vec::new();
+    let headers =
+        get_object_with_headers(s3_bucket, object_key, etag, &mut body).await?;
+    Ok(match headers {
+        Some(headers) => {
+            *data = body;
+            ConditionalGet::Modified(headers.get("etag").cloned())
+        }
+        None => ConditionalGet::NotModified,
+    })
+}
+
+pub async fn get_object_with_content_encoding(
+    s3_bucket: &S3Bucket,
+    object_key: &str,
+    data: &mut Vec<u8>,
+) -> Result<Option<String>, LakestreamError> {
+    data.clear();
+    let headers =
+        get_object_with_headers(s3_bucket, object_key, None, data).await?;
+    Ok(headers.and_then(|headers| headers.get("content-encoding").cloned()))
+}
+
+// writes the object to writer as it arrives, and returns the response
+// headers, or None if the object still matches etag
+async fn get_object_with_headers(
+    s3_bucket: &S3Bucket,
+    object_key: &str,
+    etag: Option<&str>,
+    writer: &mut dyn Write,
+) -> Result<Option<HashMap<String, String>>, LakestreamError> {
     let s3_client =
         create_s3_client(s3_bucket.config(), Some(s3_bucket.name()));
 
     info!("Getting object: {}", object_key);
-    let (body_bytes, _updated_s3_client) =
-        http_get_with_redirect_handling(&s3_client, |s3_client| {
-            s3_client.generate_get_object_headers(object_key)
-        })
+    let (status, headers, _updated_s3_client) =
+        http_get_with_redirect_handling_to_writer(
+            &s3_client,
+            |s3_client| {
+                let mut headers =
+                    s3_client.generate_get_object_headers(object_key)?;
+                // not part of the signature, SigV4 does not require it
+                if let Some(etag) = etag {
+                    headers
+                        .insert("If-None-Match".to_string(), etag.to_string());
+                }
+                Ok(headers)
+            },
+            writer,
+        )
         .await?;
-    info!(
-        "Got object: {} of size {} bytes",
-        object_key,
-        body_bytes.len()
-    );
-    // Write response body directly into the provided Vec<u8>
-    data.clear();
-    data.extend_from_slice(&body_bytes);
 
-    Ok(())
+    match status {
+        304 => {
+            info!("Object not modified: {}", object_key);
+            Ok(None)
+        }
+        200..=299 => {
+            info!("Got object: {}", object_key);
+            Ok(Some(headers))
+        }
+        404 => Err(LakestreamError::NotFound(format!(
+            "Object not found for key: {}",
+            object_key
+        ))),
+        _ => Err(LakestreamError::InternalError(format!(
+            "Getting object {} failed with status {}",
+            object_key, status
+        ))),
+    }
 }
@@ -56,7 +56,6 @@ async fn list_files_next(
     file_objects: &mut FileObjectVec,
 ) -> Result<(), LakestreamError> {
     let mut directory_stack = std::collections::VecDeque::new();
-    let mut temp_file_objects = Vec::new();
 
     directory_stack.push_back(params.prefix.clone());
 
@@ -84,6 +83,7 @@ async fn list_files_next(
             }
 
             let body = String::from_utf8_lossy(&body_bytes).to_string();
+            let mut temp_file_objects = Vec::new();
             params.continuation_token = process_response_body(
                 &body,
                 params.recursive,
@@ -92,22 +92,24 @@ async fn list_files_next(
                 &mut virtual_directories,
             );
 
+            // pass on each page, so a large prefix is not held in memory
+            if let Some(max_keys) = params.max_keys {
+                temp_file_objects.truncate(
+                    (max_keys as usize).saturating_sub(file_objects.len()),
+                );
+            }
+            file_objects.extend_async(temp_file_objects).await;
+
             if params.continuation_token.is_none()
-                || file_objects.len()
-                    >= params.max_keys.unwrap_or(AWS_MAX_LIST_OBJECTS) as usize
+                || max_keys_reached(params.max_keys, file_objects)
             {
                 break;
             }
         }
 
-        // Extend file_objects with temp_file_objects and clear temp_file_objects
-        file_objects.extend_async(temp_file_objects.drain(..)).await;
-
         if params.recursive {
             for virtual_directory in virtual_directories.drain(..) {
-                if file_objects.len()
-                    == params.max_keys.unwrap_or(AWS_MAX_LIST_OBJECTS) as usize
-                {
+                if max_keys_reached(params.max_keys, file_objects) {
                     break;
                 }
                 directory_stack.push_back(Some(virtual_directory));
@@ -119,6 +121,14 @@ async fn list_files_next(
     Ok(())
 }
 
+// without max_keys, all objects are listed
+fn max_keys_reached(
+    max_keys: Option<u32>,
+    file_objects: &FileObjectVec,
+) -> bool {
+    max_keys.is_some_and(|max_keys| file_objects.len() >= max_keys as usize)
+}
+
 fn process_file_object(
     file_object: FileObject,
     recursive: bool,
@@ -127,6 +137,13 @@ fn process_file_object(
     temp_file_objects: &mut Vec<FileObject>,
 ) {
     if file_object.name().ends_with('/') {
+        // excluded prefixes are not descended into
+        if filter
+            .as_ref()
+            .is_some_and(|filter| filter.excludes_directory(file_object.name()))
+        {
+            return;
+        }
         if recursive {
             virtual_directories.push(file_object.name().to_owned());
         }
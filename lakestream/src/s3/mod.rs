@@ -7,5 +7,6 @@ mod config;
 mod get;
 mod list;
 mod parse_http_response;
+mod put;
 mod request_builder;
 mod request_handler;
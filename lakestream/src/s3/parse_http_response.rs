@@ -2,8 +2,9 @@ use std::collections::HashMap;
 
 use serde::Deserialize;
 
+use super::bucket::S3Bucket;
 use crate::utils::time::rfc3339_to_epoch;
-use crate::{Config, FileObject, ObjectStore};
+use crate::{Config, FileObject, LakestreamError, ObjectStore};
 
 // allow non snake case for the XML response
 #[allow(non_snake_case)]
@@ -65,9 +66,9 @@ pub fn parse_bucket_objects(
         .map(|bucket| {
             let name = bucket.Name.clone();
             let config = config.clone().unwrap_or_default();
-            ObjectStore::new(&format!("s3://{}", name), config).unwrap()
+            S3Bucket::new(&name, config).map(ObjectStore::S3Bucket)
         })
-        .collect();
+        .collect::<Result<Vec<ObjectStore>, LakestreamError>>()?;
     Ok(object_stores)
 }
 
@@ -0,0 +1,41 @@
+use log::info;
+use sha2::{Digest, Sha256};
+
+use super::bucket::S3Bucket;
+use super::client_headers::Headers;
+use super::list::create_s3_client;
+use super::request_handler::http_put_with_redirect_handling;
+use crate::{LakestreamError, ObjectStoreTrait};
+
+pub async fn put_object(
+    s3_bucket: &S3Bucket,
+    object_key: &str,
+    data: &[u8],
+) -> Result<(), LakestreamError> {
+    let s3_client =
+        create_s3_client(s3_bucket.config(), Some(s3_bucket.name()));
+
+    let payload_hash = format!("{:x}", Sha256::digest(data));
+
+    info!(
+        "Putting object: {} of size {} bytes",
+        object_key,
+        data.len()
+    );
+    let status = http_put_with_redirect_handling(
+        &s3_client,
+        |s3_client| {
+            s3_client.generate_put_object_headers(object_key, &payload_hash)
+        },
+        data,
+    )
+    .await?;
+
+    match status {
+        200..=299 => Ok(()),
+        _ => Err(LakestreamError::InternalError(format!(
+            "Putting object {} failed with status {}",
+            object_key, status
+        ))),
+    }
+}
@@ -99,7 +99,7 @@ impl RequestBuilder {
     ) -> String {
         let mut canonical_headers = String::new();
         let mut headers_vec: Vec<(&String, &String)> = headers.iter().collect();
-        headers_vec.sort_by(|a, b| a.0.to_lowercase().cmp(&b.0.to_lowercase()));
+        headers_vec.sort_by_key(|a| a.0.to_lowercase());
 
         for (header_name, header_value) in headers_vec {
             let header_name = header_name.trim().to_lowercase();
@@ -174,7 +174,7 @@ impl RequestBuilder {
         &self,
         query_string: Option<&str>,
     ) -> Result<String, LakestreamError> {
-        if query_string.as_ref().map_or(true, |s| s.is_empty()) {
+        if query_string.as_ref().is_none_or(|s| s.is_empty()) {
             Ok(String::new())
         } else {
             let mut parts: Vec<(String, String)> = match query_string.as_ref() {
@@ -211,5 +211,5 @@ fn sign(key: &[u8], msg: &[u8]) -> Vec<u8> {
         .expect("HMAC can take key of any size");
     hmac.update(msg);
     let result = hmac.finalize();
-    result.into_bytes().as_slice().to_vec()
+    result.into_bytes().to_vec()
 }
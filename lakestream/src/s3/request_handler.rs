@@ -1,8 +1,9 @@
 use std::collections::HashMap;
+use std::io::Write;
 
 use bytes::Bytes;
 
-use crate::http::requests::http_get_request_with_headers;
+use crate::http::requests::{http_get_request_to_writer, http_put_request};
 use crate::s3::client::{S3Client, S3ClientConfig};
 use crate::LakestreamError;
 
@@ -21,6 +22,27 @@ pub async fn http_get_with_redirect_handling<F>(
     s3_client: &S3Client,
     generate_headers: F,
 ) -> Result<(Bytes, Option<S3Client>), LakestreamError>
+where
+    F: Fn(&mut S3Client) -> Result<HashMap<String, String>, LakestreamError>,
+{
+    let mut body = Vec::new();
+    let (_, _, updated_s3_client) = http_get_with_redirect_handling_to_writer(
+        s3_client,
+        generate_headers,
+        &mut body,
+    )
+    .await?;
+    Ok((Bytes::from(body), updated_s3_client))
+}
+
+// same as http_get_with_redirect_handling, but writes the body of the
+// (final) response to writer as it arrives, and returns its status and
+// headers. Only the body of a success is written.
+pub async fn http_get_with_redirect_handling_to_writer<F>(
+    s3_client: &S3Client,
+    generate_headers: F,
+    writer: &mut dyn Write,
+) -> Result<(u16, HashMap<String, String>, Option<S3Client>), LakestreamError>
 where
     F: Fn(&mut S3Client) -> Result<HashMap<String, String>, LakestreamError>,
 {
@@ -28,37 +50,64 @@ where
 
     loop {
         let headers = generate_headers(&mut current_s3_client)?;
-        let result =
-            http_get_request_with_headers(&current_s3_client.url(), &headers)
-                .await;
+        // a redirect has no body to write
+        let (status, response_headers) = http_get_request_to_writer(
+            &current_s3_client.url(),
+            &headers,
+            writer,
+        )
+        .await
+        .map_err(LakestreamError::from)?;
 
-        match result {
-            Ok((body_bytes, status, response_headers)) => {
-                if status == 301 {
-                    if let Some(new_region) =
-                        response_headers.get("x-amz-bucket-region")
-                    {
-                        current_s3_client =
-                            handle_redirect(&current_s3_client, new_region)
-                                .await;
-                    } else {
-                        let error = "Error: Redirect without \
-                                     x-amz-bucket-region header";
-                        return Err(LakestreamError::from(error));
-                    }
+        if status != 301 {
+            return Ok((
+                status,
+                response_headers,
+                if current_s3_client.region() != s3_client.region() {
+                    Some(current_s3_client)
                 } else {
-                    // TODO: Handle non-200 status codes
-                    return Ok((
-                        body_bytes,
-                        if current_s3_client.region() != s3_client.region() {
-                            Some(current_s3_client)
-                        } else {
-                            None
-                        },
-                    ));
-                }
-            }
-            Err(e) => return Err(LakestreamError::from(e)),
+                    None
+                },
+            ));
+        }
+        if let Some(new_region) = response_headers.get("x-amz-bucket-region") {
+            current_s3_client =
+                handle_redirect(&current_s3_client, new_region).await;
+        } else {
+            let error = "Error: Redirect without x-amz-bucket-region header";
+            return Err(LakestreamError::from(error));
+        }
+    }
+}
+
+// PUT request that follows redirects to the region of the bucket, like
+// http_get_with_redirect_handling. Returns the status of the final response.
+pub async fn http_put_with_redirect_handling<F>(
+    s3_client: &S3Client,
+    generate_headers: F,
+    data: &[u8],
+) -> Result<u16, LakestreamError>
+where
+    F: Fn(&mut S3Client) -> Result<HashMap<String, String>, LakestreamError>,
+{
+    let mut current_s3_client = s3_client.clone();
+
+    loop {
+        let headers = generate_headers(&mut current_s3_client)?;
+        let (status, response_headers) =
+            http_put_request(&current_s3_client.url(), &headers, data.to_vec())
+                .await
+                .map_err(LakestreamError::from)?;
+
+        if status != 301 {
+            return Ok(status);
+        }
+        if let Some(new_region) = response_headers.get("x-amz-bucket-region") {
+            current_s3_client =
+                handle_redirect(&current_s3_client, new_region).await;
+        } else {
+            let error = "Error: Redirect without x-amz-bucket-region header";
+            return Err(LakestreamError::from(error));
         }
     }
 }
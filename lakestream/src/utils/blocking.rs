@@ -0,0 +1,28 @@
+use std::io;
+
+/// Runs blocking I/O on the blocking thread pool of the Tokio runtime.
+/// Outside of a Tokio runtime, e.g. in `futures::executor::block_on`, it
+/// runs inline instead, blocking the executor.
+#[cfg(not(target_arch = "wasm32"))]
+pub async fn spawn_blocking<T, F>(f: F) -> io::Result<T>
+where
+    F: FnOnce() -> io::Result<T> + Send + 'static,
+    T: Send + 'static,
+{
+    match tokio::runtime::Handle::try_current() {
+        Ok(runtime) => runtime
+            .spawn_blocking(f)
+            .await
+            .map_err(|err| io::Error::other(err.to_string()))?,
+        Err(_) => f(),
+    }
+}
+
+// no threads to block on wasm32
+#[cfg(target_arch = "wasm32")]
+pub async fn spawn_blocking<T, F>(f: F) -> io::Result<T>
+where
+    F: FnOnce() -> io::Result<T>,
+{
+    f()
+}
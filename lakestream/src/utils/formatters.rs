@@ -4,40 +4,70 @@ pub fn time_human_readable(epoch_time: u64) -> String {
     epoch_to_rfc3339(epoch_time).unwrap()
 }
 
+/// Units of human readable sizes: powers of 1024 (KiB, MiB, ...), or
+/// powers of 1000 (kB, MB, ...) as on most billing dashboards.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SizeUnits {
+    Iec,
+    Si,
+}
+
+impl SizeUnits {
+    pub fn from_name(name: &str) -> Result<SizeUnits, String> {
+        match name {
+            "iec" => Ok(SizeUnits::Iec),
+            "si" => Ok(SizeUnits::Si),
+            _ => Err(format!(
+                "Invalid size units: '{}' (expected si or iec)",
+                name
+            )),
+        }
+    }
+}
+
 pub fn bytes_human_readable(size: u64) -> String {
-    let exponent: u32;
-    let symbol: char;
+    format_bytes(size, SizeUnits::Iec)
+}
 
-    if size < 1024 {
+/// A size with one decimal and the largest unit it has at least one of,
+/// e.g. "1.5GiB" or "1.6GB". Sizes below 1 kB are in bytes.
+pub fn format_bytes(size: u64, units: SizeUnits) -> String {
+    let (base, symbols) = match units {
+        SizeUnits::Iec => (1024f64, ["KiB", "MiB", "GiB", "TiB", "PiB", "EiB"]),
+        SizeUnits::Si => (1000f64, ["kB", "MB", "GB", "TB", "PB", "EB"]),
+    };
+    if (size as f64) < base {
         return size.to_string();
-    } else if size < 1024u64.pow(2) {
-        exponent = 1;
-        symbol = 'k';
-    } else if size < 1024u64.pow(3) {
-        exponent = 2;
-        symbol = 'M';
-    } else if size < 1024u64.pow(4) {
-        exponent = 3;
-        symbol = 'G';
-    } else if size < 1024u64.pow(5) {
-        exponent = 4;
-        symbol = 'T';
-    } else if size < 1024u64.pow(6) {
-        exponent = 5;
-        symbol = 'P';
-    } else if size < 1024u64.pow(7) {
-        exponent = 6;
-        symbol = 'E';
-    } else if size < 1024u64.pow(8) {
-        exponent = 7;
-        symbol = 'Z';
-    } else {
-        return "Inf".to_string();
     }
+    let mut value = size as f64 / base;
+    let mut index = 0;
+    // a value that rounds up to the base, e.g. 1023.96, is in the next unit
+    while value >= base - 0.05 && index + 1 < symbols.len() {
+        value /= base;
+        index += 1;
+    }
+    format!("{:.1}{}", value, symbols[index])
+}
 
-    format!(
-        "{:.1}{}",
-        (size as f64 / 1024f64.powi(exponent as i32)),
-        symbol
-    )
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_bytes() {
+        let cases = [
+            (512, "512", "512"),
+            (1536, "1.5KiB", "1.5kB"),
+            (1_000_000, "976.6KiB", "1.0MB"),
+            (999_999, "976.6KiB", "1.0MB"),
+            ((1 << 20) - 1, "1.0MiB", "1.0MB"),
+            (5 << 30, "5.0GiB", "5.4GB"),
+            (3 << 50, "3.0PiB", "3.4PB"),
+            (u64::MAX, "16.0EiB", "18.4EB"),
+        ];
+        for (size, iec, si) in cases {
+            assert_eq!(format_bytes(size, SizeUnits::Iec), iec);
+            assert_eq!(format_bytes(size, SizeUnits::Si), si);
+        }
+    }
 }
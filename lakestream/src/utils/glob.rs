@@ -0,0 +1,148 @@
+/// Translates a glob pattern into an (anchored) regular expression.
+///
+/// `*` and `?` match within a path component, `**` across components,
+/// `[abc]`/`[!abc]` a character class and `{a,b}` either alternative.
+/// The pattern matches the end of a key from a component boundary, e.g.
+/// `*.csv` and `year=2023/*.csv` both match "data/year=2023/part-0.csv".
+/// A leading '/' anchors the pattern to the start of the key.
+pub fn glob_to_regex(pattern: &str) -> Result<String, String> {
+    let invalid = |reason: &str| {
+        Err(format!("Invalid glob pattern '{}': {}", pattern, reason))
+    };
+
+    let (mut regex, glob) = match pattern.strip_prefix('/') {
+        Some(glob) => (String::from("^"), glob),
+        None => (String::from("(?:^|/)"), pattern),
+    };
+    let chars: Vec<char> = glob.chars().collect();
+    let mut alternatives = 0;
+    let mut index = 0;
+
+    while index < chars.len() {
+        match chars[index] {
+            '*' if chars.get(index + 1) == Some(&'*') => {
+                index += 1;
+                if chars.get(index + 1) == Some(&'/') {
+                    // "**/" also matches no directory at all
+                    index += 1;
+                    regex.push_str("(?:.*/)?");
+                } else {
+                    regex.push_str(".*");
+                }
+            }
+            '*' => regex.push_str("[^/]*"),
+            '?' => regex.push_str("[^/]"),
+            '[' => {
+                let Some(end) =
+                    chars[index + 1..].iter().skip(1).position(|&c| c == ']')
+                else {
+                    return invalid("unclosed '['");
+                };
+                // the first character may be a literal ']'
+                let class = &chars[index + 1..index + 2 + end];
+                let (negate, class) = match class.split_first() {
+                    Some(('!', rest)) | Some(('^', rest)) => (true, rest),
+                    _ => (false, class),
+                };
+                if class.is_empty() {
+                    return invalid("empty '[]'");
+                }
+                regex.push('[');
+                if negate {
+                    regex.push('^');
+                }
+                for &c in class {
+                    // keep ranges (a-z), escape everything else
+                    if c == '-' {
+                        regex.push('-');
+                    } else {
+                        regex.push_str(&regex::escape(&c.to_string()));
+                    }
+                }
+                regex.push(']');
+                index += end + 2;
+            }
+            '{' => {
+                alternatives += 1;
+                regex.push_str("(?:");
+            }
+            ',' if alternatives > 0 => regex.push('|'),
+            '}' if alternatives > 0 => {
+                alternatives -= 1;
+                regex.push(')');
+            }
+            '\\' => match chars.get(index + 1) {
+                Some(&c) => {
+                    index += 1;
+                    regex.push_str(&regex::escape(&c.to_string()));
+                }
+                None => return invalid("trailing '\\'"),
+            },
+            c => regex.push_str(&regex::escape(&c.to_string())),
+        }
+        index += 1;
+    }
+
+    if alternatives > 0 {
+        return invalid("unclosed '{'");
+    }
+    regex.push('$');
+    Ok(regex)
+}
+
+#[cfg(test)]
+mod tests {
+    use regex::Regex;
+
+    use super::*;
+
+    fn matches(pattern: &str, name: &str) -> bool {
+        Regex::new(&glob_to_regex(pattern).unwrap())
+            .unwrap()
+            .is_match(name)
+    }
+
+    #[test]
+    fn test_glob_to_regex() {
+        let cases = [
+            ("*.parquet", "data/x.parquet", true),
+            ("*.parquet", "x.parquet.tmp", false),
+            ("part-?.csv", "part-1.csv", true),
+            ("part-?.csv", "part-10.csv", false),
+            ("year=2023/**/part-*.csv", "year=2023/part-0.csv", true),
+            (
+                "year=2023/**/part-*.csv",
+                "year=2023/m=1/d=2/part-0.csv",
+                true,
+            ),
+            ("year=2023/**/part-*.csv", "year=2022/m=1/part-0.csv", false),
+            ("year=2023/*.csv", "year=2023/m=1/part-0.csv", false),
+            ("year=2023/*.csv", "data/year=2023/part-0.csv", true),
+            ("ear=2023/*.csv", "data/year=2023/part-0.csv", false),
+            ("/year=2023/*.csv", "data/year=2023/part-0.csv", false),
+            ("/data/**/*.csv", "data/year=2023/part-0.csv", true),
+            ("logs/**", "logs/2023/x.log", true),
+            ("*.{csv,json}", "a.json", true),
+            ("*.{csv,json}", "a.txt", false),
+            ("[a-c]*.txt", "b.txt", true),
+            ("[!a-c]*.txt", "b.txt", false),
+            ("[]]", "]", true),
+            ("a+b(1).txt", "a+b(1).txt", true),
+            ("\\*.txt", "*.txt", true),
+            ("\\*.txt", "a.txt", false),
+        ];
+        for (pattern, name, expected) in cases {
+            assert_eq!(
+                matches(pattern, name),
+                expected,
+                "pattern: {}, name: {}",
+                pattern,
+                name
+            );
+        }
+
+        for pattern in ["[abc", "{a,b", "a\\", "[]"] {
+            assert!(glob_to_regex(pattern).is_err(), "pattern: {}", pattern);
+        }
+    }
+}
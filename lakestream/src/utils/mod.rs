@@ -1,4 +1,6 @@
+pub mod blocking;
 pub mod formatters;
+pub mod glob;
 pub mod time;
 pub mod time_parse;
 pub mod uri_parse;
@@ -1,9 +1,15 @@
 use std::time::{SystemTime, UNIX_EPOCH};
 
 pub use super::time_parse_ext::{
-    datetime_utc, epoch_to_rfc3339, rfc3339_to_epoch,
+    datetime_utc, http_date_to_epoch, rfc3339_to_epoch,
 };
 
+const WEEKDAYS: [&str; 7] = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+const MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct",
+    "Nov", "Dec",
+];
+
 impl UtcTimeNow {
     pub fn new() -> UtcTimeNow {
         let (year, month, day, hour, minute, second) = datetime_utc();
@@ -40,6 +46,84 @@ impl UtcTimeNow {
             self.second
         )
     }
+
+    // RFC 1123 date as used in HTTP headers, e.g. x-ms-date
+    pub fn http_date(&self) -> String {
+        format!(
+            "{}, {:02} {} {:04} {:02}:{:02}:{:02} GMT",
+            WEEKDAYS[day_of_week(self.year, self.month, self.day)],
+            self.day,
+            MONTHS[(self.month - 1) as usize],
+            self.year,
+            self.hour,
+            self.minute,
+            self.second
+        )
+    }
+}
+
+// Sakamoto's method, returns 0 for Sunday
+fn day_of_week(year: u32, month: u8, day: u8) -> usize {
+    const OFFSETS: [u32; 12] = [0, 3, 2, 5, 0, 3, 5, 1, 4, 6, 2, 4];
+    let year = if month < 3 { year - 1 } else { year };
+    ((year + year / 4 - year / 100
+        + year / 400
+        + OFFSETS[(month - 1) as usize]
+        + day as u32)
+        % 7) as usize
+}
+
+// days since 1970-01-01, after Howard Hinnant's days_from_civil
+pub fn days_from_civil(year: i64, month: u32, day: u32) -> Option<u64> {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month = month as i64;
+    let day_of_year =
+        (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5
+            + day as i64
+            - 1;
+    let day_of_era =
+        year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    u64::try_from(era * 146097 + day_of_era - 719468).ok()
+}
+
+// year, month and day of a number of days since 1970-01-01, after Howard
+// Hinnant's civil_from_days
+fn civil_from_days(days: u64) -> (u64, u32, u32) {
+    let days = days + 719468;
+    let era = days / 146097;
+    let day_of_era = days - era * 146097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524
+        - day_of_era / 146096)
+        / 365;
+    let day_of_year =
+        day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month_index + 2) / 5 + 1) as u32;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    } as u32;
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+    (year, month, day)
+}
+
+/// RFC3339 timestamp in UTC of seconds since the epoch, e.g.
+/// "2023-05-02T10:00:00Z".
+pub fn epoch_to_rfc3339_utc(epoch: u64) -> String {
+    let (year, month, day) = civil_from_days(epoch / 86400);
+    let seconds = epoch % 86400;
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
 }
 
 pub fn system_time_in_seconds() -> u64 {
@@ -48,3 +132,22 @@ pub fn system_time_in_seconds() -> u64 {
         .expect("Time went backwards")
         .as_secs()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_epoch_to_rfc3339_utc() {
+        let cases = [
+            (0, "1970-01-01T00:00:00Z"),
+            (951_782_400, "2000-02-29T00:00:00Z"),
+            (1_683_021_600, "2023-05-02T10:00:00Z"),
+            (4_102_444_799, "2099-12-31T23:59:59Z"),
+        ];
+        for (epoch, expected) in cases {
+            assert_eq!(epoch_to_rfc3339_utc(epoch), expected);
+            assert_eq!(rfc3339_to_epoch(expected).unwrap(), epoch);
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use time::format_description::well_known::Rfc3339;
+use time::format_description::well_known::{Rfc2822, Rfc3339};
 use time::OffsetDateTime;
 
 pub fn rfc3339_to_epoch(timestamp: &str) -> Result<u64, time::Error> {
@@ -6,6 +6,11 @@ pub fn rfc3339_to_epoch(timestamp: &str) -> Result<u64, time::Error> {
     Ok(datetime.unix_timestamp() as u64)
 }
 
+pub fn http_date_to_epoch(timestamp: &str) -> Result<u64, time::Error> {
+    let datetime = OffsetDateTime::parse(timestamp, &Rfc2822)?;
+    Ok(datetime.unix_timestamp() as u64)
+}
+
 pub fn epoch_to_rfc3339(timestamp: u64) -> Result<String, time::Error> {
     let datetime = OffsetDateTime::from_unix_timestamp(timestamp as i64)?;
     Ok(datetime.to_string())
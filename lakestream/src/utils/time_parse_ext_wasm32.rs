@@ -6,6 +6,19 @@ pub fn rfc3339_to_epoch(timestamp: &str) -> Result<u64, JsValue> {
     Ok((date.get_time() / 1000.0) as u64)
 }
 
+pub fn http_date_to_epoch(timestamp: &str) -> Result<u64, JsValue> {
+    let date = Date::new(&JsValue::from_str(timestamp));
+    // an invalid date has NaN as time
+    let time = date.get_time();
+    if time.is_nan() {
+        return Err(JsValue::from_str(&format!(
+            "Invalid HTTP date: {}",
+            timestamp
+        )));
+    }
+    Ok((time / 1000.0) as u64)
+}
+
 pub fn epoch_to_rfc3339(timestamp: u64) -> Result<String, JsValue> {
     let date = Date::new(&JsValue::from_f64(timestamp as f64 * 1000.0));
     let date_string = date.to_iso_string().as_string().unwrap();
@@ -1,5 +1,7 @@
 use regex::Regex;
 
+use crate::archive::backend::split_archive_path;
+
 pub struct ParsedUri {
     pub scheme: Option<String>,
     pub bucket: Option<String>,
@@ -16,7 +18,7 @@ impl ParsedUri {
             };
         }
 
-        let re = Regex::new(r"^(?P<scheme>[a-z0-9]+)://").unwrap();
+        let re = Regex::new(r"^(?P<scheme>[a-z][a-z0-9+.\-]*)://").unwrap();
         let scheme_match = re.captures(uri);
 
         scheme_match.map_or_else(
@@ -38,6 +40,15 @@ impl ParsedUri {
                         bucket: None,
                         path: None,
                     }
+                } else if scheme == "archive" || scheme.starts_with("archive+")
+                {
+                    let (bucket, path) =
+                        parse_archive_path(&uri_without_scheme, append_slash);
+                    ParsedUri {
+                        scheme: Some(scheme.to_string()),
+                        bucket,
+                        path,
+                    }
                 } else {
                     let (bucket, path) = parse_uri_path(
                         Some(scheme),
@@ -70,20 +81,10 @@ fn parse_uri_path(
     let mut parts = cleaned_uri.splitn(2, '/');
     let bucket = parts.next().map(|s| s.to_string());
 
-    let path = parts.next().filter(|s| !s.is_empty()).map(|s| {
-        let cleaned_path = s.replace("./", "");
-        if cleaned_path.ends_with('/') {
-            if append_slash {
-                cleaned_path
-            } else {
-                cleaned_path.trim_end_matches('/').to_string()
-            }
-        } else if append_slash {
-            format!("{}/", cleaned_path)
-        } else {
-            cleaned_path
-        }
-    });
+    let path = parts
+        .next()
+        .filter(|s| !s.is_empty())
+        .map(|s| clean_path(s, append_slash));
 
     // If there is no path, treat the input as a path instead of a bucket
     // bucket is currenth path on LocalFs
@@ -102,3 +103,35 @@ fn parse_uri_path(
 
     (Some(".".to_string()), None)
 }
+
+// the bucket of an archive URI is the archive itself, which may be nested
+// in directories, e.g. "archive://path/data.zip/inner/prefix"
+fn parse_archive_path(
+    uri_path: &str,
+    append_slash: bool,
+) -> (Option<String>, Option<String>) {
+    match split_archive_path(uri_path) {
+        Some((archive, path)) => (
+            Some(archive.to_string()),
+            Some(path)
+                .filter(|s| !s.is_empty())
+                .map(|s| clean_path(s, append_slash)),
+        ),
+        None => (Some(uri_path.to_string()), None),
+    }
+}
+
+fn clean_path(path: &str, append_slash: bool) -> String {
+    let cleaned_path = path.replace("./", "");
+    if cleaned_path.ends_with('/') {
+        if append_slash {
+            cleaned_path
+        } else {
+            cleaned_path.trim_end_matches('/').to_string()
+        }
+    } else if append_slash {
+        format!("{}/", cleaned_path)
+    } else {
+        cleaned_path
+    }
+}
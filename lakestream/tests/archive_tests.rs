@@ -0,0 +1,144 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+
+use lakestream::{
+    Config, FileObject, FileObjectFilter, ListObjectsResult, ObjectStoreHandler,
+};
+use tempfile::tempdir;
+
+const MEMBERS: [(&str, &[u8]); 3] = [
+    ("a.txt", b"hello"),
+    ("logs/x.log", &[0u8; 2048]),
+    ("logs/2023/y.log", b"y"),
+];
+
+fn create_zip(path: &Path) {
+    let mut writer = zip::ZipWriter::new(File::create(path).unwrap());
+    for (name, data) in MEMBERS {
+        writer
+            .start_file(name, zip::write::FileOptions::default())
+            .unwrap();
+        writer.write_all(data).unwrap();
+    }
+    writer.finish().unwrap();
+}
+
+fn create_tar(path: &Path) {
+    let mut builder = tar::Builder::new(File::create(path).unwrap());
+    for (name, data) in MEMBERS {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(data.len() as u64);
+        header.set_mtime(1_600_000_000);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder.append_data(&mut header, name, data).unwrap();
+    }
+    builder.finish().unwrap();
+}
+
+fn create_tar_gz(path: &Path) {
+    let tar_path = path.with_extension("tar");
+    create_tar(&tar_path);
+    let mut encoder = flate2::write::GzEncoder::new(
+        File::create(path).unwrap(),
+        flate2::Compression::default(),
+    );
+    encoder
+        .write_all(&std::fs::read(tar_path).unwrap())
+        .unwrap();
+    encoder.finish().unwrap();
+}
+
+async fn list(
+    uri: &str,
+    recursive: bool,
+    filter: &Option<FileObjectFilter>,
+) -> Vec<FileObject> {
+    let config = Config::new(HashMap::new());
+    let handler = ObjectStoreHandler::new(None);
+
+    let result = handler
+        .list_objects(uri, &config, recursive, None, filter, None)
+        .await
+        .unwrap();
+
+    match result {
+        Some(ListObjectsResult::FileObjects(fo)) => fo,
+        _ => panic!("Unexpected result type"),
+    }
+}
+
+#[tokio::test]
+async fn test_list_archives() {
+    let temp_dir = tempdir().expect("Failed to create temp dir");
+    let zip_path = temp_dir.path().join("data.zip");
+    let tar_path = temp_dir.path().join("data.tar");
+    create_zip(&zip_path);
+    create_tar(&tar_path);
+
+    for path in [&zip_path, &tar_path] {
+        let uri = format!("archive://{}", path.display());
+        let names: Vec<String> = list(&uri, false, &None)
+            .await
+            .iter()
+            .map(|fo| fo.name().to_string())
+            .collect();
+        assert_eq!(names, vec!["a.txt", "logs/"]);
+
+        let uri = format!("archive://{}/logs/", path.display());
+        let names: Vec<String> = list(&uri, true, &None)
+            .await
+            .iter()
+            .map(|fo| fo.name().to_string())
+            .collect();
+        assert_eq!(names, vec!["logs/x.log", "logs/2023/", "logs/2023/y.log"]);
+
+        let filter =
+            Some(FileObjectFilter::new(None, Some("+1K"), None).unwrap());
+        let file_objects = list(&uri, true, &filter).await;
+        assert_eq!(file_objects.len(), 1);
+        assert_eq!(file_objects[0].name(), "logs/x.log");
+        assert_eq!(file_objects[0].size(), 2048);
+
+        let filter = FileObjectFilter::new(None, None, None)
+            .and_then(|filter| filter.with_exclude("2023/"))
+            .ok();
+        let names: Vec<String> = list(&uri, true, &filter)
+            .await
+            .iter()
+            .map(|fo| fo.name().to_string())
+            .collect();
+        assert_eq!(names, vec!["logs/x.log"]);
+    }
+
+    let uri = format!("archive://{}", tar_path.display());
+    let file_objects = list(&uri, false, &None).await;
+    assert_eq!(file_objects[0].modified(), Some(1_600_000_000));
+}
+
+#[tokio::test]
+async fn test_get_object() {
+    let temp_dir = tempdir().expect("Failed to create temp dir");
+    let zip_path = temp_dir.path().join("data.zip");
+    let tar_path = temp_dir.path().join("data.tar");
+    let tgz_path = temp_dir.path().join("data.tgz");
+    create_zip(&zip_path);
+    create_tar(&tar_path);
+    create_tar_gz(&tgz_path);
+
+    let config = Config::new(HashMap::new());
+    let handler = ObjectStoreHandler::new(None);
+
+    for path in [&zip_path, &tar_path, &tgz_path] {
+        for (name, expected) in MEMBERS {
+            let uri = format!("archive://{}/{}", path.display(), name);
+            let data = handler.get_object(&uri, &config, None).await.unwrap();
+            assert_eq!(data, Some(expected.to_vec()));
+        }
+
+        let uri = format!("archive://{}/missing.txt", path.display());
+        assert!(handler.get_object(&uri, &config, None).await.is_err());
+    }
+}
@@ -0,0 +1,224 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use lakestream::{
+    ConditionalGet, Config, CustomBucket, FileObjectFilter, FileObjectVec,
+    LakestreamError, ObjectStore, ObjectStoreBackend, ObjectStoreHandler,
+    ObjectStoreTrait, ObjectStoreVec,
+};
+use tempfile::tempdir;
+
+#[derive(Default)]
+struct RemoteState {
+    // key -> (data, etag)
+    objects: HashMap<String, (Vec<u8>, String)>,
+    downloads: usize,
+}
+
+// remote object store that supports conditional requests and counts the
+// number of full downloads
+struct RemoteBucket {
+    config: Config,
+    state: Arc<Mutex<RemoteState>>,
+}
+
+#[async_trait(?Send)]
+impl ObjectStoreTrait for RemoteBucket {
+    fn name(&self) -> &str {
+        "remote"
+    }
+
+    fn config(&self) -> &Config {
+        &self.config
+    }
+
+    async fn list_files(
+        &self,
+        _prefix: Option<&str>,
+        _recursive: bool,
+        _max_keys: Option<u32>,
+        _filter: &Option<FileObjectFilter>,
+        _file_objects: &mut FileObjectVec,
+    ) -> Result<(), LakestreamError> {
+        Ok(())
+    }
+
+    async fn get_object(
+        &self,
+        key: &str,
+        data: &mut Vec<u8>,
+    ) -> Result<(), LakestreamError> {
+        self.get_object_if_none_match(key, None, data).await?;
+        Ok(())
+    }
+
+    async fn get_object_if_none_match(
+        &self,
+        key: &str,
+        etag: Option<&str>,
+        data: &mut Vec<u8>,
+    ) -> Result<ConditionalGet, LakestreamError> {
+        let mut state = self.state.lock().unwrap();
+        let (object_data, object_etag) = state
+            .objects
+            .get(key)
+            .cloned()
+            .ok_or_else(|| LakestreamError::NotFound(key.to_string()))?;
+        if etag == Some(object_etag.as_str()) {
+            return Ok(ConditionalGet::NotModified);
+        }
+        state.downloads += 1;
+        *data = object_data;
+        Ok(ConditionalGet::Modified(Some(object_etag)))
+    }
+}
+
+struct RemoteBackend;
+
+#[async_trait(?Send)]
+impl ObjectStoreBackend for RemoteBackend {
+    fn new(_config: Config) -> Result<Self, LakestreamError> {
+        Ok(RemoteBackend)
+    }
+
+    async fn list_buckets(
+        _config: Config,
+        _object_stores: &mut ObjectStoreVec,
+    ) -> Result<(), LakestreamError> {
+        Ok(())
+    }
+}
+
+fn handler(state: &Arc<Mutex<RemoteState>>) -> ObjectStoreHandler {
+    let state = state.clone();
+    let mut handler = ObjectStoreHandler::new(None);
+    handler.register_backend::<RemoteBackend, _>("remote", move |_, config| {
+        Ok(ObjectStore::Custom(CustomBucket::new(
+            "remote",
+            RemoteBucket {
+                config,
+                state: state.clone(),
+            },
+        )))
+    });
+    handler
+}
+
+fn cache_config(cache_dir: &Path, max_size: &str) -> Config {
+    let mut config = Config::default();
+    config.insert(
+        "LAKESTREAM_CACHE_DIR".to_string(),
+        cache_dir.display().to_string(),
+    );
+    config.insert(
+        "LAKESTREAM_CACHE_MAX_SIZE".to_string(),
+        max_size.to_string(),
+    );
+    config
+}
+
+fn put(state: &Arc<Mutex<RemoteState>>, key: &str, data: &[u8], etag: &str) {
+    state
+        .lock()
+        .unwrap()
+        .objects
+        .insert(key.to_string(), (data.to_vec(), etag.to_string()));
+}
+
+fn cached_entries(cache_dir: &Path) -> usize {
+    fs::read_dir(cache_dir)
+        .unwrap()
+        .filter(|entry| {
+            entry.as_ref().unwrap().path().extension() == Some("entry".as_ref())
+        })
+        .count()
+}
+
+#[tokio::test]
+async fn test_cache_revalidation() {
+    let cache_dir = tempdir().expect("Failed to create temp dir");
+    let config = cache_config(cache_dir.path(), "1M");
+    let state = Arc::new(Mutex::new(RemoteState::default()));
+    let handler = handler(&state);
+    put(&state, "a.txt", b"hello", "\"v1\"");
+
+    for _ in 0..3 {
+        let data = handler
+            .get_object("remote://bucket/a.txt", &config, None)
+            .await
+            .unwrap();
+        assert_eq!(data, Some(b"hello".to_vec()));
+    }
+    assert_eq!(state.lock().unwrap().downloads, 1);
+    assert_eq!(cached_entries(cache_dir.path()), 1);
+
+    // a changed object is downloaded again
+    put(&state, "a.txt", b"hello again", "\"v2\"");
+    let data = handler
+        .get_object("remote://bucket/a.txt", &config, None)
+        .await
+        .unwrap();
+    assert_eq!(data, Some(b"hello again".to_vec()));
+    assert_eq!(state.lock().unwrap().downloads, 2);
+}
+
+#[tokio::test]
+async fn test_cache_eviction() {
+    let cache_dir = tempdir().expect("Failed to create temp dir");
+    // an entry takes 13 bytes with its ETag
+    let config = cache_config(cache_dir.path(), "20");
+    let state = Arc::new(Mutex::new(RemoteState::default()));
+    let handler = handler(&state);
+    put(&state, "a.txt", b"aaaaaa", "\"a\"");
+    put(&state, "b.txt", b"bbbbbb", "\"b\"");
+
+    for key in ["a.txt", "b.txt"] {
+        handler
+            .get_object(&format!("remote://bucket/{}", key), &config, None)
+            .await
+            .unwrap();
+    }
+    // both do not fit, so the least recently used one is evicted
+    assert_eq!(cached_entries(cache_dir.path()), 1);
+
+    handler
+        .get_object("remote://bucket/b.txt", &config, None)
+        .await
+        .unwrap();
+    assert_eq!(state.lock().unwrap().downloads, 2);
+}
+
+#[tokio::test]
+async fn test_cache_concurrent_writes() {
+    let cache_dir = tempdir().expect("Failed to create temp dir");
+    let config = cache_config(cache_dir.path(), "1MiB");
+    let state = Arc::new(Mutex::new(RemoteState::default()));
+    let handler = handler(&state);
+    put(&state, "a.txt", b"hello", "\"v1\"");
+
+    // the first GETs all miss, and write the same entry at once
+    let results =
+        futures::future::join_all((0..8).map(|_| {
+            handler.get_object("remote://bucket/a.txt", &config, None)
+        }))
+        .await;
+    for result in results {
+        assert_eq!(result.unwrap(), Some(b"hello".to_vec()));
+    }
+    let files: Vec<String> = fs::read_dir(cache_dir.path())
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into())
+        .collect();
+    // ETag and data are in a single file
+    assert_eq!(files.len(), 1, "{:?}", files);
+    assert!(files[0].ends_with(".entry"), "{:?}", files);
+
+    let config = cache_config(cache_dir.path(), "1X");
+    let result = handler
+        .get_object("remote://bucket/a.txt", &config, None)
+        .await;
+    assert!(matches!(result, Err(LakestreamError::ConfigError(_))));
+}
@@ -0,0 +1,210 @@
+// HTTP server for tests, on a thread of its own, that answers requests
+// with a handler and records them
+#![allow(dead_code)]
+
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use url::Url;
+
+#[derive(Clone)]
+pub struct Request {
+    pub method: String,
+    /// path and query string
+    pub target: String,
+    pub headers: HashMap<String, String>,
+    pub body: Vec<u8>,
+}
+
+impl Request {
+    pub fn path(&self) -> &str {
+        self.target.split('?').next().unwrap()
+    }
+
+    pub fn query(&self) -> HashMap<String, String> {
+        Url::parse(&format!("http://localhost{}", self.target))
+            .unwrap()
+            .query_pairs()
+            .into_owned()
+            .collect()
+    }
+}
+
+pub struct Response {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl Response {
+    pub fn new(status: u16, body: impl Into<Vec<u8>>) -> Response {
+        Response {
+            status,
+            headers: Vec::new(),
+            body: body.into(),
+        }
+    }
+
+    pub fn with_header(mut self, name: &str, value: &str) -> Response {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+}
+
+pub struct MockServer {
+    pub url: String,
+    // "METHOD target" of each request, in order
+    requests: Arc<Mutex<Vec<String>>>,
+}
+
+impl MockServer {
+    pub fn start<F>(handler: F) -> MockServer
+    where
+        F: Fn(&Request) -> Response + Send + 'static,
+    {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&requests);
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(mut stream) = stream else {
+                    continue;
+                };
+                let Some(request) = read_request(&mut stream) else {
+                    continue;
+                };
+                recorded
+                    .lock()
+                    .unwrap()
+                    .push(format!("{} {}", request.method, request.target));
+                let response = handler(&request);
+                let mut head = format!(
+                    "HTTP/1.1 {} Mock\r\nContent-Length: {}\r\n\
+                     Connection: close\r\n",
+                    response.status,
+                    response.body.len()
+                );
+                for (name, value) in &response.headers {
+                    head.push_str(&format!("{}: {}\r\n", name, value));
+                }
+                head.push_str("\r\n");
+                let _ = stream.write_all(head.as_bytes());
+                if request.method != "HEAD" {
+                    let _ = stream.write_all(&response.body);
+                }
+            }
+        });
+        MockServer { url, requests }
+    }
+
+    pub fn requests(&self) -> Vec<String> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+fn read_request(stream: &mut std::net::TcpStream) -> Option<Request> {
+    let mut reader = BufReader::new(stream);
+    let mut line = String::new();
+    reader.read_line(&mut line).ok()?;
+    let mut parts = line.split_whitespace();
+    let method = parts.next()?.to_string();
+    let target = parts.next()?.to_string();
+
+    let mut headers = HashMap::new();
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line).ok()?;
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        let (name, value) = line.split_once(':')?;
+        headers.insert(name.to_lowercase(), value.trim().to_string());
+    }
+    let length = headers
+        .get("content-length")
+        .and_then(|length| length.parse().ok())
+        .unwrap_or(0);
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body).ok()?;
+    Some(Request {
+        method,
+        target,
+        headers,
+        body,
+    })
+}
+
+/// A ListObjectsV2 response for the (sorted) keys of a bucket, with the
+/// prefix, max-keys and continuation-token (an index here) of the request.
+/// Keys are grouped into common prefixes by "/".
+pub fn s3_list_objects_response(
+    keys: &[String],
+    request: &Request,
+) -> Response {
+    let query = request.query();
+    let prefix = query.get("prefix").map(String::as_str).unwrap_or("");
+    let max_keys: usize = query
+        .get("max-keys")
+        .map_or(1000, |keys| keys.parse().unwrap());
+    let start: usize = query
+        .get("continuation-token")
+        .map_or(0, |token| token.parse().unwrap());
+
+    // keys and common prefixes, in order
+    let mut entries: Vec<(String, bool)> = Vec::new();
+    for key in keys.iter().filter(|key| key.starts_with(prefix)) {
+        match key[prefix.len()..].find('/') {
+            Some(index) => {
+                let common_prefix = key[..prefix.len() + index + 1].to_string();
+                if entries.last() != Some(&(common_prefix.clone(), true)) {
+                    entries.push((common_prefix, true));
+                }
+            }
+            None => entries.push((key.clone(), false)),
+        }
+    }
+
+    let end = entries.len().min(start + max_keys);
+    let mut body = String::from("<ListBucketResult>");
+    for (name, is_prefix) in &entries[start..end] {
+        if *is_prefix {
+            body.push_str(&format!(
+                "<CommonPrefixes><Prefix>{}</Prefix></CommonPrefixes>",
+                name
+            ));
+        } else {
+            body.push_str(&format!(
+                "<Contents><Key>{}</Key>\
+                 <LastModified>2023-01-01T00:00:00.000Z</LastModified>\
+                 <Size>1</Size><ETag>\"etag\"</ETag></Contents>",
+                name
+            ));
+        }
+    }
+    if end < entries.len() {
+        body.push_str(&format!(
+            "<NextContinuationToken>{}</NextContinuationToken>",
+            end
+        ));
+    }
+    body.push_str("</ListBucketResult>");
+    Response::new(200, body)
+}
+
+/// Config for an S3 bucket served by a mock server.
+pub fn s3_config(server: &MockServer) -> lakestream::Config {
+    lakestream::Config::new(HashMap::from([
+        ("AWS_REGION".to_string(), "us-east-1".to_string()),
+        ("AWS_ACCESS_KEY_ID".to_string(), "access-key".to_string()),
+        (
+            "AWS_SECRET_ACCESS_KEY".to_string(),
+            "secret-key".to_string(),
+        ),
+        ("S3_ENDPOINT_URL".to_string(), server.url.clone()),
+    ]))
+}
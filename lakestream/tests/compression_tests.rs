@@ -0,0 +1,74 @@
+use lakestream::{Codec, Config, MemoryBucket, ObjectStoreHandler};
+
+const DATA: &[u8] = b"{\"id\": 1}\n{\"id\": 2}\n";
+
+#[tokio::test]
+async fn test_put_and_get_compressed() {
+    let bucket =
+        MemoryBucket::new("compression-put-get", Config::default()).unwrap();
+    bucket.clear();
+
+    let config = Config::default();
+    let handler = ObjectStoreHandler::new(None);
+
+    for key in ["x.json.gz", "x.json.zst", "x.json.bz2"] {
+        let uri = format!("memory://compression-put-get/{}", key);
+        handler
+            .put_object_compressed(&uri, &config, None, DATA)
+            .await
+            .unwrap();
+
+        let stored = handler.get_object(&uri, &config, None).await.unwrap();
+        assert_ne!(stored.as_deref(), Some(DATA));
+
+        let data = handler
+            .get_object_decompressed(&uri, &config, None, None)
+            .await
+            .unwrap();
+        assert_eq!(data.as_deref(), Some(DATA));
+    }
+
+    // without extension, the codec is detected from the contents
+    let uri = "memory://compression-put-get/x.json";
+    handler
+        .put_object_compressed(uri, &config, Some(Codec::Zstd), DATA)
+        .await
+        .unwrap();
+    let data = handler
+        .get_object_decompressed(uri, &config, None, None)
+        .await
+        .unwrap();
+    assert_eq!(data.as_deref(), Some(DATA));
+
+    // uncompressed objects are returned as is
+    bucket.put_object("plain.json", DATA);
+    let uri = "memory://compression-put-get/plain.json";
+    let data = handler
+        .get_object_decompressed(uri, &config, None, None)
+        .await
+        .unwrap();
+    assert_eq!(data.as_deref(), Some(DATA));
+
+    // a compressed PUT needs a codec
+    assert!(handler
+        .put_object_compressed(uri, &config, None, DATA)
+        .await
+        .is_err());
+}
+
+#[test]
+fn test_detect_codec() {
+    let gzip = Codec::Gzip.compress(DATA).unwrap();
+
+    assert_eq!(
+        Codec::detect("x.json", Some("gzip"), DATA),
+        Some(Codec::Gzip)
+    );
+    assert_eq!(Codec::detect("x.json.bz2", None, DATA), Some(Codec::Bzip2));
+    assert_eq!(Codec::detect("x.json", None, &gzip), Some(Codec::Gzip));
+    assert_eq!(Codec::detect("x.json", None, DATA), None);
+
+    assert_eq!(Codec::from_name("zst").unwrap(), Codec::Zstd);
+    assert!(Codec::from_name("lz4").is_err());
+    assert!(Codec::Gzip.decompress(DATA).is_err());
+}
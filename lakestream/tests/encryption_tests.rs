@@ -0,0 +1,230 @@
+use std::cell::Cell;
+use std::fs;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use lakestream::{
+    Config, EncryptedStore, FileObject, FileObjectFilter, FileObjectVec,
+    LakestreamError, MasterKey, MemoryBucket, ObjectStoreTrait,
+};
+use tempfile::tempdir;
+
+fn encrypted_store(
+    bucket_name: &str,
+    key: &[u8; 32],
+) -> EncryptedStore<MemoryBucket> {
+    let bucket = MemoryBucket::new(bucket_name, Config::default()).unwrap();
+    EncryptedStore::new(bucket, MasterKey::from_bytes(key).unwrap())
+}
+
+#[tokio::test]
+async fn test_put_and_get() {
+    let store = encrypted_store("encrypted-put-get", &[7u8; 32]);
+    store.inner().clear();
+    store.put_object("a.txt", b"secret data").await.unwrap();
+
+    // only ciphertext reaches the wrapped store
+    let mut stored = Vec::new();
+    store
+        .inner()
+        .get_object("a.txt", &mut stored)
+        .await
+        .unwrap();
+    assert!(!stored.windows(6).any(|window| window == b"secret"));
+
+    let mut data = Vec::new();
+    store.get_object("a.txt", &mut data).await.unwrap();
+    assert_eq!(data, b"secret data");
+
+    // listing hides the sidecar and reports the size of the plaintext
+    let mut file_objects = FileObjectVec::new(None);
+    store
+        .list_files(None, true, None, &None, &mut file_objects)
+        .await
+        .unwrap();
+    let file_objects = file_objects.into_inner();
+    assert_eq!(file_objects.len(), 1);
+    assert_eq!(file_objects[0].name(), "a.txt");
+    assert_eq!(file_objects[0].size(), 11);
+}
+
+#[tokio::test]
+async fn test_wrong_key() {
+    let store = encrypted_store("encrypted-wrong-key", &[7u8; 32]);
+    store.inner().clear();
+    store.put_object("a.txt", b"secret data").await.unwrap();
+
+    let other_store = encrypted_store("encrypted-wrong-key", &[8u8; 32]);
+    let mut data = Vec::new();
+    assert!(other_store.get_object("a.txt", &mut data).await.is_err());
+}
+
+// in-memory bucket that fails to put objects, but not their sidecars, once
+// fail_puts is set
+struct FailingBucket {
+    inner: MemoryBucket,
+    fail_puts: Cell<bool>,
+}
+
+#[async_trait(?Send)]
+impl ObjectStoreTrait for FailingBucket {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn config(&self) -> &Config {
+        self.inner.config()
+    }
+
+    async fn list_files(
+        &self,
+        prefix: Option<&str>,
+        recursive: bool,
+        max_keys: Option<u32>,
+        filter: &Option<FileObjectFilter>,
+        file_objects: &mut FileObjectVec,
+    ) -> Result<(), LakestreamError> {
+        self.inner
+            .list_files(prefix, recursive, max_keys, filter, file_objects)
+            .await
+    }
+
+    async fn get_object(
+        &self,
+        key: &str,
+        data: &mut Vec<u8>,
+    ) -> Result<(), LakestreamError> {
+        self.inner.get_object(key, data).await
+    }
+
+    async fn put_object(
+        &self,
+        key: &str,
+        data: &[u8],
+    ) -> Result<(), LakestreamError> {
+        if self.fail_puts.get() && !key.contains(".lakestream-key") {
+            return Err(LakestreamError::from("put failed"));
+        }
+        ObjectStoreTrait::put_object(&self.inner, key, data).await
+    }
+}
+
+#[tokio::test]
+async fn test_failed_overwrite() {
+    let bucket = FailingBucket {
+        inner: MemoryBucket::new("encrypted-overwrite", Config::default())
+            .unwrap(),
+        fail_puts: Cell::new(false),
+    };
+    bucket.inner.clear();
+    let store =
+        EncryptedStore::new(bucket, MasterKey::from_bytes(&[7u8; 32]).unwrap());
+    store.put_object("a.txt", b"version 1").await.unwrap();
+
+    store.inner().fail_puts.set(true);
+    assert!(store.put_object("a.txt", b"version 2").await.is_err());
+
+    // the object that is stored can still be decrypted
+    let mut data = Vec::new();
+    store.get_object("a.txt", &mut data).await.unwrap();
+    assert_eq!(data, b"version 1");
+}
+
+#[tokio::test]
+async fn test_overwrite() {
+    let store = encrypted_store("encrypted-overwrite-sidecar", &[7u8; 32]);
+    store.inner().clear();
+    for version in ["version 1", "version 2", "version 3"] {
+        store.put_object("a.txt", version.as_bytes()).await.unwrap();
+    }
+
+    let mut data = Vec::new();
+    store.get_object("a.txt", &mut data).await.unwrap();
+    assert_eq!(data, b"version 3");
+
+    // a single sidecar is kept per object
+    let mut file_objects = FileObjectVec::new(None);
+    store
+        .inner()
+        .list_files(None, true, None, &None, &mut file_objects)
+        .await
+        .unwrap();
+    let mut names: Vec<&str> = file_objects
+        .iter()
+        .map(|file_object| file_object.name())
+        .collect();
+    names.sort();
+    assert_eq!(names, ["a.txt", "a.txt.lakestream-key"]);
+}
+
+#[tokio::test]
+async fn test_list_with_callback() {
+    let store = encrypted_store("encrypted-list", &[7u8; 32]);
+    store.inner().clear();
+    for key in ["a.txt", "b.txt", "c.txt"] {
+        store.put_object(key, b"secret data").await.unwrap();
+    }
+    store.put_object("a.txt", b"secret").await.unwrap();
+    store.put_object("logs/d.txt", b"secret").await.unwrap();
+
+    let listed = Arc::new(Mutex::new(Vec::new()));
+    let callback = {
+        let listed = Arc::clone(&listed);
+        Box::new(move |batch: &[FileObject]| {
+            listed
+                .lock()
+                .unwrap()
+                .extend(batch.iter().map(|file_object| {
+                    (file_object.name().to_string(), file_object.size())
+                }));
+            Box::pin(async {})
+                as Pin<Box<dyn Future<Output = ()> + Send + 'static>>
+        })
+    };
+
+    // excludes are passed on to the underlying listing
+    let filter = FileObjectFilter::new(None, None, None)
+        .and_then(|filter| filter.with_exclude("logs/"))
+        .ok();
+    let mut file_objects = FileObjectVec::new(Some(callback));
+    store
+        .list_files(None, true, None, &filter, &mut file_objects)
+        .await
+        .unwrap();
+    let mut names = listed.lock().unwrap().clone();
+    names.sort();
+    assert_eq!(
+        names,
+        [
+            ("a.txt".to_string(), 6),
+            ("b.txt".to_string(), 11),
+            ("c.txt".to_string(), 11)
+        ]
+    );
+
+    let mut file_objects = FileObjectVec::new(None);
+    store
+        .list_files(None, true, Some(2), &None, &mut file_objects)
+        .await
+        .unwrap();
+    assert_eq!(file_objects.len(), 2);
+}
+
+#[test]
+fn test_keyfile() {
+    let temp_dir = tempdir().expect("Failed to create temp dir");
+
+    let hex_path = temp_dir.path().join("key.hex");
+    fs::write(&hex_path, format!("{}\n", "ab".repeat(32))).unwrap();
+    assert!(MasterKey::from_keyfile(&hex_path).is_ok());
+
+    let raw_path = temp_dir.path().join("key.bin");
+    fs::write(&raw_path, [1u8; 32]).unwrap();
+    assert!(MasterKey::from_keyfile(&raw_path).is_ok());
+
+    let short_path = temp_dir.path().join("key.short");
+    fs::write(&short_path, "abcd").unwrap();
+    assert!(MasterKey::from_keyfile(&short_path).is_err());
+}
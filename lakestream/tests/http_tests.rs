@@ -0,0 +1,132 @@
+mod common;
+
+use std::collections::HashMap;
+
+use common::{MockServer, Response};
+use lakestream::{Config, ListObjectsResult, ObjectStoreHandler};
+
+const INDEX: &str = r#"<html><body><pre><a href="../">../</a>
+<a href="sub/">sub/</a>      18-Oct-2023 10:00       -
+<a href="a.csv">a.csv</a>    18-Oct-2023 10:01    1234
+</pre></body></html>"#;
+
+fn index(names: &[String]) -> Response {
+    let links: String = names
+        .iter()
+        .map(|name| format!("<a href=\"{}\">{}</a>\n", name, name))
+        .collect();
+    Response::new(
+        200,
+        format!("<html><body><pre>{}</pre></body></html>", links),
+    )
+    .with_header("Content-Type", "text/html")
+}
+
+fn start_server() -> MockServer {
+    MockServer::start(|request| match request.path() {
+        "/many/" => {
+            index(&(0..100).map(|dir| format!("d{}/", dir)).collect::<Vec<_>>())
+        }
+        "/loop/" => index(&["a/".to_string(), "b.csv".to_string()]),
+        "/loop/a/" => index(&["back/".to_string()]),
+        // a directory that is the parent under another name
+        "/loop/a/back/" => {
+            Response::new(302, "").with_header("Location", "/loop/")
+        }
+        "/data/" => {
+            Response::new(200, INDEX).with_header("Content-Type", "text/html")
+        }
+        "/data/sub/" => Response::new(200, "<html></html>")
+            .with_header("Content-Type", "text/html"),
+        "/old/" => Response::new(301, "").with_header("Location", "/data/"),
+        // a server that ignores the trailing slash of a file
+        "/big.bin" | "/big.bin/" => Response::new(200, vec![7; 100_000])
+            .with_header("Content-Type", "application/octet-stream"),
+        _ => Response::new(404, ""),
+    })
+}
+
+async fn list_names(handler: &ObjectStoreHandler, uri: &str) -> Vec<String> {
+    list_names_with(handler, uri, false, None).await
+}
+
+async fn list_names_with(
+    handler: &ObjectStoreHandler,
+    uri: &str,
+    recursive: bool,
+    max_files: Option<u32>,
+) -> Vec<String> {
+    let config = Config::new(HashMap::new());
+    match handler
+        .list_objects(uri, &config, recursive, max_files, &None, None)
+        .await
+        .unwrap()
+    {
+        Some(ListObjectsResult::FileObjects(file_objects)) => file_objects
+            .iter()
+            .map(|file_object| file_object.name().to_string())
+            .collect(),
+        _ => panic!("expected file objects"),
+    }
+}
+
+#[tokio::test]
+async fn test_list_directory() {
+    let server = start_server();
+    let handler = ObjectStoreHandler::new(None);
+
+    // without trailing slash, and through a redirect
+    for path in ["data", "data/", "old"] {
+        let uri = format!("{}/{}", server.url, path);
+        assert_eq!(
+            list_names(&handler, &uri).await,
+            ["data/sub/", "data/a.csv"],
+            "{}",
+            path
+        );
+    }
+    assert!(server.requests().contains(&"GET /old/".to_string()));
+
+    // a file is not downloaded to find out it is not an index page
+    let uri = format!("{}/big.bin", server.url);
+    assert_eq!(list_names(&handler, &uri).await, ["big.bin"]);
+    let requests = server.requests();
+    assert_eq!(requests[requests.len() - 1], "HEAD /big.bin");
+}
+
+#[tokio::test]
+async fn test_list_recursive() {
+    let server = start_server();
+    let handler = ObjectStoreHandler::new(None);
+
+    // sub-directories are not fetched once the limit is reached
+    let uri = format!("{}/many/", server.url);
+    let names = list_names_with(&handler, &uri, true, Some(10)).await;
+    assert_eq!(names.len(), 10);
+    assert_eq!(server.requests(), ["GET /many/"]);
+
+    // a directory that redirects back to a listed one is not listed again
+    let uri = format!("{}/loop/", server.url);
+    let mut names = list_names_with(&handler, &uri, true, None).await;
+    names.sort();
+    assert_eq!(names, ["loop/a/", "loop/a/back/", "loop/b.csv"]);
+}
+
+#[tokio::test]
+async fn test_get_object_to_writer() {
+    let server = start_server();
+    let handler = ObjectStoreHandler::new(None);
+    let config = Config::new(HashMap::new());
+
+    let mut data = Vec::new();
+    let uri = format!("{}/big.bin", server.url);
+    handler
+        .get_object_to_writer(&uri, &config, &mut data)
+        .await
+        .unwrap();
+    assert_eq!(data, vec![7; 100_000]);
+
+    let uri = format!("{}/missing.bin", server.url);
+    let result = handler.get_object_to_writer(&uri, &config, &mut data).await;
+    assert!(result.is_err());
+}
@@ -1,7 +1,13 @@
 use std::collections::HashMap;
 use std::fs::File;
+use std::future::Future;
+use std::task::Poll;
 
-use lakestream::{Config, ListObjectsResult, ObjectStoreHandler};
+use futures::StreamExt;
+use lakestream::{
+    Codec, Config, FileObject, FileObjectFilter, LakestreamError,
+    ListObjectsResult, ObjectStore, ObjectStoreHandler,
+};
 use tempfile::tempdir;
 
 #[tokio::test]
@@ -15,6 +21,7 @@ async fn test_list_objects() {
     let file_path2 = temp_dir_path.join("file2.txt");
     File::create(&file_path1).unwrap();
     File::create(&file_path2).unwrap();
+    std::fs::create_dir(temp_dir_path.join("subdir")).unwrap();
 
     let settings = HashMap::new();
     let config = Config::new(settings);
@@ -41,9 +48,541 @@ async fn test_list_objects() {
         .map(|name| name.to_string())
         .collect();
 
-    let file_path1_str = file_path1.to_string_lossy().into_owned();
-    let file_path2_str = file_path2.to_string_lossy().into_owned();
+    // keys are relative to the bucket, which is "/" for an absolute path,
+    // and directories end with a slash
+    let key = |name: &str| {
+        format!("{}/{}", temp_dir_path.display(), name)
+            .trim_start_matches('/')
+            .to_string()
+    };
+    assert_eq!(filenames.len(), 3);
+    assert!(filenames.contains(&key("file1.txt")));
+    assert!(filenames.contains(&key("file2.txt")));
+    assert!(filenames.contains(&key("subdir/")));
+}
+
+#[tokio::test]
+async fn test_grep_objects() {
+    let temp_dir = tempdir().expect("Failed to create temp dir");
+    let temp_dir_path = temp_dir.path().to_path_buf();
+
+    // larger than the chunks a file is read in
+    let data: String =
+        (0..20_000).map(|line| format!("line {}\n", line)).collect();
+    std::fs::write(temp_dir_path.join("a.log"), &data).unwrap();
+    let compressed = Codec::Zstd.compress(data.as_bytes()).unwrap();
+    std::fs::write(temp_dir_path.join("b.log.zst"), compressed).unwrap();
+
+    let config = Config::new(HashMap::new());
+    let handler = ObjectStoreHandler::new(None);
+    let uri = format!("localfs://{}", temp_dir_path.display());
+
+    let line_matches = handler
+        .grep_objects(&uri, &config, "^line 1999[0-9]$", &None, 2, None)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(line_matches.len(), 20);
+    for line_match in &line_matches {
+        assert_eq!(
+            line_match.line,
+            format!("line {}", line_match.line_number - 1)
+        );
+    }
+    for key in ["a.log", "b.log.zst"] {
+        let count = line_matches
+            .iter()
+            .filter(|line_match| line_match.key.ends_with(key))
+            .count();
+        assert_eq!(count, 10, "{}", key);
+    }
+}
+
+#[test]
+fn test_list_objects_without_runtime() {
+    let temp_dir = tempdir().expect("Failed to create temp dir");
+    File::create(temp_dir.path().join("file1.txt")).unwrap();
+
+    let config = Config::new(HashMap::new());
+    let handler = ObjectStoreHandler::new(None);
+    let uri = format!("localfs://{}", temp_dir.path().display());
+    let listing = handler.list_objects(&uri, &config, true, None, &None, None);
+
+    // file system calls run inline, so it completes in a single poll
+    let mut context =
+        std::task::Context::from_waker(futures::task::noop_waker_ref());
+    let result = std::pin::pin!(listing).poll(&mut context);
+    match result {
+        Poll::Ready(Ok(Some(ListObjectsResult::FileObjects(file_objects)))) => {
+            assert_eq!(file_objects.len(), 1)
+        }
+        _ => panic!("Unexpected result"),
+    }
+}
+
+#[tokio::test]
+async fn test_put_object() {
+    let temp_dir = tempdir().expect("Failed to create temp dir");
+    let bucket_path = temp_dir.path().join("bucket");
+    std::fs::create_dir(&bucket_path).unwrap();
+
+    let config = Config::new(HashMap::new());
+    let handler = ObjectStoreHandler::new(None);
+    let uri = format!("localfs://{}/dir/a.txt", bucket_path.display());
+    handler.put_object(&uri, &config, b"data").await.unwrap();
+    assert_eq!(
+        std::fs::read(bucket_path.join("dir/a.txt")).unwrap(),
+        b"data"
+    );
+
+    // keys cannot reach outside of the bucket
+    let bucket = ObjectStore::new(
+        &format!("localfs://{}", bucket_path.display()),
+        config,
+    )
+    .unwrap();
+    for key in ["../escape.txt", "dir/../../escape.txt", "/escape.txt"] {
+        assert!(bucket.put_object(key, b"data").await.is_err(), "{}", key);
+    }
+    assert!(!temp_dir.path().join("escape.txt").exists());
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_list_objects_non_utf8() {
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+
+    let temp_dir = tempdir().expect("Failed to create temp dir");
+    let temp_dir_path = temp_dir.path().to_path_buf();
+    File::create(temp_dir_path.join("valid.txt")).unwrap();
+    File::create(temp_dir_path.join(OsStr::from_bytes(b"invalid\xff.txt")))
+        .unwrap();
+
+    let config = Config::new(HashMap::new());
+    let handler = ObjectStoreHandler::new(None);
+    let uri = format!("localfs://{}", temp_dir_path.display());
+    let result = handler
+        .list_objects(&uri, &config, false, None, &None, None)
+        .await
+        .unwrap();
+    let file_objects = match result {
+        Some(ListObjectsResult::FileObjects(fo)) => fo,
+        _ => panic!("Unexpected result type"),
+    };
+
+    // paths that are not valid UTF-8 are skipped, not mangled
+    assert_eq!(file_objects.len(), 1);
+    assert!(file_objects[0].name().ends_with("/valid.txt"));
+}
+
+#[tokio::test]
+async fn test_list_objects_recursive() {
+    let temp_dir = tempdir().expect("Failed to create temp dir");
+    let temp_dir_path = temp_dir.path().to_path_buf();
+
+    // more directories than are read in parallel
+    for i in 0..20 {
+        let dir_path = temp_dir_path.join(format!("dir{}", i)).join("sub");
+        std::fs::create_dir_all(&dir_path).unwrap();
+        File::create(dir_path.join("file.txt")).unwrap();
+    }
+
+    let config = Config::new(HashMap::new());
+    let handler = ObjectStoreHandler::new(None);
+    let uri = format!("localfs://{}", temp_dir_path.display());
+
+    let list = |max_files| {
+        let handler = handler.clone();
+        let config = config.clone();
+        let uri = uri.clone();
+        async move {
+            match handler
+                .list_objects(&uri, &config, true, max_files, &None, None)
+                .await
+                .unwrap()
+            {
+                Some(ListObjectsResult::FileObjects(fo)) => fo,
+                _ => panic!("Unexpected result type"),
+            }
+        }
+    };
+
+    // 20 dirs, 20 subdirs and 20 files
+    assert_eq!(list(None).await.len(), 60);
+    assert_eq!(list(Some(25)).await.len(), 25);
+}
+
+#[tokio::test]
+async fn test_list_objects_stream() {
+    let temp_dir = tempdir().expect("Failed to create temp dir");
+    let temp_dir_path = temp_dir.path().to_path_buf();
+
+    // a batch per directory
+    for i in 0..5 {
+        let dir_path = temp_dir_path.join(format!("dir{}", i));
+        std::fs::create_dir(&dir_path).unwrap();
+        File::create(dir_path.join("a.txt")).unwrap();
+        File::create(dir_path.join("b.txt")).unwrap();
+    }
+
+    let config = Config::new(HashMap::new());
+    let handler = ObjectStoreHandler::new(None);
+    let uri = format!("localfs://{}", temp_dir_path.display());
+
+    let stream = handler.list_objects_stream(&uri, &config, true, None, &None);
+    let file_objects: Vec<FileObject> =
+        stream.map(|result| result.unwrap()).collect().await;
+    // 5 dirs and 10 files
+    assert_eq!(file_objects.len(), 15);
+
+    let stream = handler.list_objects_stream(&uri, &config, true, None, &None);
+    let first: Vec<_> = stream.take(3).collect().await;
+    assert_eq!(first.len(), 3);
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_list_objects_symlinks() {
+    use std::os::unix::fs::symlink;
+
+    let temp_dir = tempdir().expect("Failed to create temp dir");
+    let temp_dir_path = temp_dir.path().to_path_buf();
+    std::fs::create_dir(temp_dir_path.join("dir")).unwrap();
+    File::create(temp_dir_path.join("dir/file.txt")).unwrap();
+    symlink("file.txt", temp_dir_path.join("dir/link.txt")).unwrap();
+    // loop back to the parent directory
+    symlink("..", temp_dir_path.join("dir/parent")).unwrap();
+
+    let handler = ObjectStoreHandler::new(None);
+    let uri = format!("localfs://{}", temp_dir_path.display());
+    let prefix = temp_dir_path.display().to_string();
+    let prefix = prefix.trim_start_matches('/');
+
+    for (policy, expected) in [
+        ("skip", vec!["dir/", "dir/file.txt"]),
+        (
+            "list",
+            vec!["dir/", "dir/file.txt", "dir/link.txt", "dir/parent"],
+        ),
+        (
+            "follow",
+            vec!["dir/", "dir/file.txt", "dir/link.txt", "dir/parent/"],
+        ),
+        // links are followed when no policy is set
+        (
+            "",
+            vec!["dir/", "dir/file.txt", "dir/link.txt", "dir/parent/"],
+        ),
+    ] {
+        let config = match policy {
+            "" => Config::default(),
+            _ => Config::with_setting(
+                "LAKESTREAM_LOCALFS_SYMLINKS".to_string(),
+                policy.to_string(),
+            ),
+        };
+        let result = handler
+            .list_objects(&uri, &config, true, None, &None, None)
+            .await
+            .unwrap();
+        let file_objects = match result {
+            Some(ListObjectsResult::FileObjects(fo)) => fo,
+            _ => panic!("Unexpected result type"),
+        };
+
+        let mut names: Vec<String> = file_objects
+            .iter()
+            .map(|fo| fo.name().trim_start_matches(prefix).to_string())
+            .map(|name| name.trim_start_matches('/').to_string())
+            .collect();
+        names.sort();
+        assert_eq!(names, expected, "symlinks: {}", policy);
+
+        if policy == "list" {
+            let link = file_objects
+                .iter()
+                .find(|fo| fo.name().ends_with("link.txt"))
+                .unwrap();
+            let target = link.tags().as_ref().unwrap().get("SymlinkTarget");
+            assert_eq!(target.map(String::as_str), Some("file.txt"));
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_list_objects_errors() {
+    let temp_dir = tempdir().expect("Failed to create temp dir");
+    let temp_dir_path = temp_dir.path().to_path_buf();
+    File::create(temp_dir_path.join("file.txt")).unwrap();
+
+    let handler = ObjectStoreHandler::new(None);
+    let config = Config::default();
+
+    let uri = format!("localfs://{}/missing", temp_dir_path.display());
+    let result = handler
+        .list_objects(&uri, &config, true, None, &None, None)
+        .await;
+    assert!(matches!(result, Err(LakestreamError::NotFound(_))));
+
+    // a path to a file lists just that file
+    let uri = format!("localfs://{}/file.txt", temp_dir_path.display());
+    let result = handler
+        .list_objects(&uri, &config, true, None, &None, None)
+        .await
+        .unwrap();
+    match result {
+        Some(ListObjectsResult::FileObjects(file_objects)) => {
+            assert_eq!(file_objects.len(), 1);
+            assert!(file_objects[0].name().ends_with("file.txt"));
+        }
+        _ => panic!("Unexpected result type"),
+    }
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_list_objects_keep_going() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let temp_dir = tempdir().expect("Failed to create temp dir");
+    let temp_dir_path = temp_dir.path().to_path_buf();
+    let locked = temp_dir_path.join("locked");
+    std::fs::create_dir(&locked).unwrap();
+    File::create(locked.join("file.txt")).unwrap();
+    File::create(temp_dir_path.join("file.txt")).unwrap();
+    std::fs::set_permissions(&locked, std::fs::Permissions::from_mode(0o000))
+        .unwrap();
+
+    // permissions do not apply to root
+    if std::fs::read_dir(&locked).is_ok() {
+        return;
+    }
+
+    let handler = ObjectStoreHandler::new(None);
+    let uri = format!("localfs://{}", temp_dir_path.display());
+
+    let result = handler
+        .list_objects(&uri, &Config::default(), true, None, &None, None)
+        .await;
+    assert!(matches!(result, Err(LakestreamError::Io(_))));
+
+    let config = Config::with_setting(
+        "LAKESTREAM_LOCALFS_KEEP_GOING".to_string(),
+        "true".to_string(),
+    );
+    let result = handler
+        .list_objects(&uri, &config, true, None, &None, None)
+        .await
+        .unwrap();
+    match result {
+        // "locked/" and "file.txt"
+        Some(ListObjectsResult::FileObjects(file_objects)) => {
+            assert_eq!(file_objects.len(), 2)
+        }
+        _ => panic!("Unexpected result type"),
+    }
+
+    std::fs::set_permissions(&locked, std::fs::Permissions::from_mode(0o755))
+        .unwrap();
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_list_objects_metadata() {
+    use std::os::unix::fs::{MetadataExt, PermissionsExt};
+
+    let temp_dir = tempdir().expect("Failed to create temp dir");
+    let temp_dir_path = temp_dir.path().to_path_buf();
+    let private = temp_dir_path.join("private.txt");
+    let shared = temp_dir_path.join("shared.txt");
+    for (path, mode) in [(&private, 0o640), (&shared, 0o666)] {
+        File::create(path).unwrap();
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(mode))
+            .unwrap();
+    }
+    // not every filesystem (e.g. older tmpfs) supports user xattrs
+    let xattrs_supported =
+        xattr::set(&private, "user.checksum", b"abc123").is_ok();
+    let file_metadata = std::fs::metadata(&private).unwrap();
+    let uid = file_metadata.uid().to_string();
+
+    let handler = ObjectStoreHandler::new(None);
+    let uri = format!("localfs://{}", temp_dir_path.display());
+    let list = |config: Config, filter: Option<FileObjectFilter>| {
+        let handler = &handler;
+        let uri = &uri;
+        async move {
+            let result = handler
+                .list_objects(uri, &config, false, None, &filter, None)
+                .await
+                .unwrap();
+            match result {
+                Some(ListObjectsResult::FileObjects(file_objects)) => {
+                    let mut file_objects = file_objects;
+                    file_objects.sort_by(|a, b| a.name().cmp(b.name()));
+                    file_objects
+                }
+                _ => panic!("Unexpected result type"),
+            }
+        }
+    };
+
+    // without metadata, no tags
+    let file_objects = list(Config::default(), None).await;
+    assert!(file_objects.iter().all(|fo| fo.tags().is_none()));
+
+    let mut config = Config::with_setting(
+        "LAKESTREAM_LOCALFS_METADATA".to_string(),
+        "true".to_string(),
+    );
+    config.insert("LAKESTREAM_LOCALFS_XATTRS".to_string(), "true".to_string());
+    let file_objects = list(config, None).await;
+    let tags = file_objects[0].tags().as_ref().unwrap();
+    assert!(file_objects[0].name().ends_with("private.txt"));
+    assert_eq!(tags.get("Mode").unwrap(), "0640");
+    assert_eq!(tags.get("Uid").unwrap(), &uid);
+    assert_eq!(tags.get("Inode").unwrap(), &file_metadata.ino().to_string());
+    if xattrs_supported {
+        assert_eq!(tags.get("user.checksum").unwrap(), "abc123");
+    }
+
+    // filters on metadata add the tags themselves
+    let filter = |perm: &str| {
+        FileObjectFilter::new(None, None, None)
+            .unwrap()
+            .with_mode(perm)
+            .unwrap()
+    };
+    let file_objects = list(Config::default(), Some(filter("/022"))).await;
+    assert_eq!(file_objects.len(), 1);
+    assert!(file_objects[0].name().ends_with("shared.txt"));
+    assert_eq!(list(Config::default(), Some(filter("-600"))).await.len(), 2);
+
+    let owner = FileObjectFilter::new(None, None, None)
+        .unwrap()
+        .with_owner(&uid);
+    assert_eq!(list(Config::default(), Some(owner)).await.len(), 2);
+    let owner = FileObjectFilter::new(None, None, None)
+        .unwrap()
+        .with_owner("no-such-user");
+    assert!(list(Config::default(), Some(owner)).await.is_empty());
+}
+
+#[tokio::test]
+async fn test_list_buckets_roots() {
+    let temp_dir = tempdir().expect("Failed to create temp dir");
+    let temp_dir_path = temp_dir.path().to_path_buf();
+    let roots = [temp_dir_path.join("a"), temp_dir_path.join("b")];
+    for root in &roots {
+        std::fs::create_dir(root).unwrap();
+    }
+    // roots that do not exist are left out
+    let missing = temp_dir_path.join("missing");
+    let setting = std::env::join_paths(roots.iter().chain([&missing]))
+        .unwrap()
+        .into_string()
+        .unwrap();
+
+    let handler = ObjectStoreHandler::new(None);
+    let config =
+        Config::with_setting("LAKESTREAM_LOCALFS_ROOTS".to_string(), setting);
+    let result = handler
+        .list_buckets("localfs://", &config, None)
+        .await
+        .unwrap();
+    let buckets = match result {
+        Some(ListObjectsResult::Buckets(buckets)) => buckets,
+        _ => panic!("Unexpected result type"),
+    };
+
+    let names: Vec<&str> = buckets.iter().map(|bucket| bucket.name()).collect();
+    let expected: Vec<String> = roots
+        .iter()
+        .map(|root| root.display().to_string())
+        .collect();
+    assert_eq!(names, expected);
+
+    #[cfg(unix)]
+    for bucket in &buckets {
+        let ObjectStore::LocalFsBucket(bucket) = bucket else {
+            panic!("Unexpected object store");
+        };
+        let usage = bucket.usage().expect("disk usage");
+        assert!(usage.total > 0 && usage.available <= usage.total);
+    }
+}
+
+#[tokio::test]
+async fn test_list_objects_excludes() {
+    let temp_dir = tempdir().expect("Failed to create temp dir");
+    let table = temp_dir.path().join("table");
+    for file in [
+        "_temporary/0/part-0.csv",
+        "year=2023/part-0.parquet",
+        "year=2023/_SUCCESS",
+        ".git/config",
+        "notes.tmp",
+        "keep.tmp",
+    ] {
+        let path = table.join(file);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        File::create(path).unwrap();
+    }
+    std::fs::write(table.join(".lakestreamignore"), "*.tmp\n!keep.tmp\n")
+        .unwrap();
+
+    let handler = ObjectStoreHandler::new(None);
+    let uri = format!("localfs://{}", table.display());
+    let list = |config: Config, filter: Option<FileObjectFilter>| {
+        let handler = &handler;
+        let uri = &uri;
+        async move {
+            let result = handler
+                .list_objects(uri, &config, true, None, &filter, None)
+                .await
+                .unwrap();
+            let Some(ListObjectsResult::FileObjects(file_objects)) = result
+            else {
+                panic!("Unexpected result type");
+            };
+            let mut names: Vec<String> = file_objects
+                .iter()
+                .map(|fo| fo.name().split_once("table/").unwrap().1.into())
+                .collect();
+            names.sort();
+            names
+        }
+    };
+
+    let filter = FileObjectFilter::new(None, None, None)
+        .and_then(|filter| filter.with_exclude("_temporary/"))
+        .and_then(|filter| filter.with_exclude(".git/"))
+        .and_then(|filter| filter.with_exclude("_SUCCESS"))
+        .unwrap();
+    assert_eq!(
+        list(Config::default(), Some(filter.clone())).await,
+        [".lakestreamignore", "keep.tmp", "year=2023/part-0.parquet"]
+    );
+
+    // without the ignore file
+    let config = Config::with_setting(
+        "LAKESTREAM_LOCALFS_NO_IGNORE".to_string(),
+        "true".to_string(),
+    );
+    assert_eq!(
+        list(config, Some(filter.clone())).await,
+        [
+            ".lakestreamignore",
+            "keep.tmp",
+            "notes.tmp",
+            "year=2023/part-0.parquet"
+        ]
+    );
 
-    assert!(filenames.contains(&file_path1_str));
-    assert!(filenames.contains(&file_path2_str));
+    let filter = filter.with_include("*.parquet").unwrap();
+    assert_eq!(
+        list(Config::default(), Some(filter)).await,
+        ["year=2023/part-0.parquet"]
+    );
 }
@@ -0,0 +1,287 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use futures::StreamExt;
+use lakestream::{
+    CallbackItem, CallbackWrapper, Codec, Config, FileObject, FileObjectFilter,
+    LakestreamError, ListObjectsResult, MemoryBucket, NameMode,
+    ObjectStoreHandler, SortKey, TopFileObjects,
+};
+
+fn populate_bucket(name: &str) -> MemoryBucket {
+    let bucket = MemoryBucket::new(name, Config::default()).unwrap();
+    bucket.clear();
+    bucket.put_object_with_modified("a.txt", b"hello", 1_600_000_000);
+    bucket.put_object("logs/2023/x.log", &[0u8; 2048]);
+    bucket.put_object("logs/2023/y.log", b"y");
+    bucket.put_object("logs/z.log", b"z");
+    bucket
+}
+
+async fn list_names(
+    uri: &str,
+    recursive: bool,
+    max_files: Option<u32>,
+    filter: &Option<FileObjectFilter>,
+) -> Vec<String> {
+    let config = Config::new(HashMap::new());
+    let handler = ObjectStoreHandler::new(None);
+
+    let result = handler
+        .list_objects(uri, &config, recursive, max_files, filter, None)
+        .await
+        .unwrap();
+
+    match result {
+        Some(ListObjectsResult::FileObjects(fo)) => {
+            fo.iter().map(|fo| fo.name().to_string()).collect()
+        }
+        _ => panic!("Unexpected result type"),
+    }
+}
+
+#[tokio::test]
+async fn test_list_objects() {
+    populate_bucket("test-list");
+
+    let names = list_names("memory://test-list", false, None, &None).await;
+    assert_eq!(names, vec!["a.txt", "logs/"]);
+
+    let names =
+        list_names("memory://test-list/logs/", false, None, &None).await;
+    assert_eq!(names, vec!["logs/z.log", "logs/2023/"]);
+
+    let names = list_names("memory://test-list", true, None, &None).await;
+    assert_eq!(
+        names,
+        vec![
+            "a.txt",
+            "logs/",
+            "logs/z.log",
+            "logs/2023/",
+            "logs/2023/x.log",
+            "logs/2023/y.log"
+        ]
+    );
+
+    let names = list_names("memory://test-list", true, Some(3), &None).await;
+    assert_eq!(names.len(), 3);
+}
+
+#[tokio::test]
+async fn test_list_objects_top() {
+    populate_bucket("test-top");
+    let config = Config::default();
+    let handler = ObjectStoreHandler::new(None);
+
+    // objects passed to a callback are not kept, but still count towards
+    // max_files
+    let top = Arc::new(Mutex::new(TopFileObjects::new(SortKey::Size, 2)));
+    let callback_top = Arc::clone(&top);
+    let callback =
+        CallbackWrapper::create_sync(move |file_objects: &[FileObject]| {
+            callback_top
+                .lock()
+                .unwrap()
+                .extend(file_objects.iter().cloned());
+        });
+    let result = handler
+        .list_objects(
+            "memory://test-top",
+            &config,
+            true,
+            Some(5),
+            &None,
+            Some(callback),
+        )
+        .await
+        .unwrap();
+    assert!(result.is_none());
+
+    let top = Arc::try_unwrap(top).ok().unwrap().into_inner().unwrap();
+    let names: Vec<String> = top
+        .into_sorted_vec()
+        .iter()
+        .map(|fo| fo.name().to_string())
+        .collect();
+    assert_eq!(names, ["logs/2023/x.log", "a.txt"]);
+}
+
+#[tokio::test]
+async fn test_list_objects_stream() {
+    populate_bucket("test-stream");
+    let config = Config::default();
+    let handler = ObjectStoreHandler::new(None);
+
+    let stream = handler.list_objects_stream(
+        "memory://test-stream",
+        &config,
+        true,
+        None,
+        &None,
+    );
+    let names: Vec<String> = stream
+        .map(|result| result.unwrap().name().to_string())
+        .collect()
+        .await;
+    assert_eq!(
+        names,
+        list_names("memory://test-stream", true, None, &None).await
+    );
+
+    // stop early
+    let mut stream = Box::pin(handler.list_objects_stream(
+        "memory://test-stream",
+        &config,
+        true,
+        None,
+        &None,
+    ));
+    assert!(stream.next().await.unwrap().is_ok());
+    drop(stream);
+
+    let mut stream = Box::pin(handler.list_objects_stream(
+        "memory://",
+        &config,
+        true,
+        None,
+        &None,
+    ));
+    assert!(matches!(
+        stream.next().await,
+        Some(Err(LakestreamError::NoBucketInUri(_)))
+    ));
+    assert!(stream.next().await.is_none());
+}
+
+#[tokio::test]
+async fn test_list_objects_with_filter() {
+    populate_bucket("test-filter");
+
+    let filter = Some(FileObjectFilter::new(None, Some("+1k"), None).unwrap());
+    let names = list_names("memory://test-filter", true, None, &filter).await;
+    assert_eq!(names, vec!["logs/2023/x.log"]);
+
+    let filter = Some(FileObjectFilter::new(None, None, Some("+1D")).unwrap());
+    let names = list_names("memory://test-filter", true, None, &filter).await;
+    assert_eq!(names, vec!["a.txt"]);
+
+    let filter =
+        Some(FileObjectFilter::new(Some("y\\.log$"), None, None).unwrap());
+    let names = list_names("memory://test-filter", true, None, &filter).await;
+    assert_eq!(names, vec!["logs/2023/y.log"]);
+
+    let glob = |pattern: &str| {
+        FileObjectFilter::new(None, None, None)
+            .unwrap()
+            .with_name(pattern, NameMode::Glob)
+    };
+    let filter = Some(glob("*.log").unwrap());
+    let names = list_names("memory://test-filter", true, None, &filter).await;
+    assert_eq!(
+        names,
+        vec!["logs/z.log", "logs/2023/x.log", "logs/2023/y.log"]
+    );
+    let filter = Some(glob("/logs/**/y.*").unwrap());
+    let names = list_names("memory://test-filter", true, None, &filter).await;
+    assert_eq!(names, vec!["logs/2023/y.log"]);
+
+    let filter = FileObjectFilter::new(None, None, None)
+        .unwrap()
+        .with_expression("name like '*.log' and not (size > 1k or name ~ z)")
+        .unwrap();
+    let names =
+        list_names("memory://test-filter", true, None, &Some(filter)).await;
+    assert_eq!(names, vec!["logs/2023/y.log"]);
+
+    // invalid patterns are an error, not a panic
+    assert!(glob("[a-").is_err());
+    assert!(FileObjectFilter::new(Some("*.log"), None, None).is_err());
+}
+
+#[tokio::test]
+async fn test_get_object() {
+    populate_bucket("test-get");
+    let config = Config::default();
+    let handler = ObjectStoreHandler::new(None);
+
+    let data = handler
+        .get_object("memory://test-get/a.txt", &config, None)
+        .await
+        .unwrap();
+    assert_eq!(data, Some(b"hello".to_vec()));
+
+    let result = handler
+        .get_object("memory://test-get/missing.txt", &config, None)
+        .await;
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn test_grep_objects() {
+    let bucket = populate_bucket("test-grep");
+    bucket.put_object("logs/2023/x.log", b"GET /\nid=42 GET /a\n");
+    let compressed = Codec::Gzip.compress(b"id=42 PUT /b\n").unwrap();
+    bucket.put_object("logs/z.log.gz", &compressed);
+    let config = Config::default();
+    let handler = ObjectStoreHandler::new(None);
+
+    let grep = |filter: Option<FileObjectFilter>| {
+        let handler = &handler;
+        let config = &config;
+        async move {
+            handler
+                .grep_objects(
+                    "memory://test-grep/logs/",
+                    config,
+                    "id=42",
+                    &filter,
+                    2,
+                    None,
+                )
+                .await
+                .unwrap()
+                .unwrap()
+                .iter()
+                .map(|line_match| line_match.println_path())
+                .collect::<Vec<_>>()
+        }
+    };
+
+    assert_eq!(
+        grep(None).await,
+        [
+            "logs/z.log.gz:1:id=42 PUT /b",
+            "logs/2023/x.log:2:id=42 GET /a"
+        ]
+    );
+    let filter = FileObjectFilter::new(None, None, None)
+        .and_then(|filter| filter.with_name("*.log", NameMode::Glob))
+        .unwrap();
+    assert_eq!(grep(Some(filter)).await, ["logs/2023/x.log:2:id=42 GET /a"]);
+
+    let result = handler
+        .grep_objects("memory://test-grep/", &config, "(", &None, 1, None)
+        .await;
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn test_list_buckets() {
+    populate_bucket("test-buckets");
+    let config = Config::default();
+    let handler = ObjectStoreHandler::new(None);
+
+    let result = handler
+        .list_buckets("memory://", &config, None)
+        .await
+        .unwrap();
+    let names: Vec<String> = match result {
+        Some(ListObjectsResult::Buckets(buckets)) => buckets
+            .iter()
+            .map(|bucket| bucket.name().to_string())
+            .collect(),
+        _ => panic!("Unexpected result type"),
+    };
+    assert!(names.contains(&"test-buckets".to_string()));
+}
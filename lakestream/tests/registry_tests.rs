@@ -0,0 +1,236 @@
+use std::collections::HashMap;
+use std::io::{Cursor, Write};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use async_trait::async_trait;
+use lakestream::{
+    object_stores_from_config, object_stores_from_config_with_registry, Config,
+    CustomBucket, EncryptedStore, FileObjectFilter, FileObjectVec,
+    LakestreamError, ListObjectsResult, MasterKey, MemoryBucket, ObjectStore,
+    ObjectStoreBackend, ObjectStoreHandler, ObjectStoreTrait, ObjectStoreVec,
+};
+use tempfile::tempdir;
+
+// number of GETs of zip files from blob buckets
+static ZIP_GETS: AtomicUsize = AtomicUsize::new(0);
+
+// object store that serves the in-memory bucket of the same name
+struct BlobBucket {
+    inner: MemoryBucket,
+}
+
+impl BlobBucket {
+    fn new(name: &str, config: Config) -> Result<Self, LakestreamError> {
+        Ok(BlobBucket {
+            inner: MemoryBucket::new(name, config)?,
+        })
+    }
+}
+
+#[async_trait(?Send)]
+impl ObjectStoreTrait for BlobBucket {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn config(&self) -> &Config {
+        self.inner.config()
+    }
+
+    async fn list_files(
+        &self,
+        prefix: Option<&str>,
+        recursive: bool,
+        max_keys: Option<u32>,
+        filter: &Option<FileObjectFilter>,
+        file_objects: &mut FileObjectVec,
+    ) -> Result<(), LakestreamError> {
+        self.inner
+            .list_files(prefix, recursive, max_keys, filter, file_objects)
+            .await
+    }
+
+    async fn get_object(
+        &self,
+        key: &str,
+        data: &mut Vec<u8>,
+    ) -> Result<(), LakestreamError> {
+        if key.ends_with(".zip") {
+            ZIP_GETS.fetch_add(1, Ordering::Relaxed);
+        }
+        self.inner.get_object(key, data).await
+    }
+}
+
+struct BlobBackend;
+
+#[async_trait(?Send)]
+impl ObjectStoreBackend for BlobBackend {
+    fn new(_config: Config) -> Result<Self, LakestreamError> {
+        Ok(BlobBackend)
+    }
+
+    async fn list_buckets(
+        config: Config,
+        object_stores: &mut ObjectStoreVec,
+    ) -> Result<(), LakestreamError> {
+        let bucket = BlobBucket::new("registry-blob", config)?;
+        object_stores.extend_async(vec![blob_store(bucket)]).await;
+        Ok(())
+    }
+}
+
+fn blob_store(bucket: BlobBucket) -> ObjectStore {
+    ObjectStore::Custom(CustomBucket::new("blob", bucket))
+}
+
+fn handler() -> ObjectStoreHandler {
+    let mut handler = ObjectStoreHandler::new(None);
+    handler.register_backend::<BlobBackend, _>("blob", |name, config| {
+        Ok(blob_store(BlobBucket::new(name, config)?))
+    });
+    handler
+}
+
+#[tokio::test]
+async fn test_registered_backend() {
+    let bucket = MemoryBucket::new("registry-blob", Config::default()).unwrap();
+    bucket.clear();
+    bucket.put_object("a.txt", b"hello");
+
+    let config = Config::new(HashMap::new());
+    let handler = handler();
+    assert!(handler.registry().schemes().contains(&"blob"));
+
+    let result = handler
+        .list_objects("blob://registry-blob", &config, true, None, &None, None)
+        .await
+        .unwrap();
+    match result {
+        Some(ListObjectsResult::FileObjects(file_objects)) => {
+            assert_eq!(file_objects.len(), 1);
+            assert_eq!(file_objects[0].name(), "a.txt");
+        }
+        _ => panic!("Unexpected result type"),
+    }
+
+    let data = handler
+        .get_object("blob://registry-blob/a.txt", &config, None)
+        .await
+        .unwrap();
+    assert_eq!(data, Some(b"hello".to_vec()));
+
+    let result = handler
+        .list_buckets("blob://", &config, None)
+        .await
+        .unwrap();
+    match result {
+        Some(ListObjectsResult::Buckets(buckets)) => {
+            assert_eq!(buckets.len(), 1);
+            assert_eq!(buckets[0].println_path(), "blob://registry-blob");
+        }
+        _ => panic!("Unexpected result type"),
+    }
+}
+
+#[tokio::test]
+async fn test_unregistered_scheme() {
+    let config = Config::new(HashMap::new());
+    let handler = ObjectStoreHandler::new(None);
+
+    let result = handler
+        .list_objects("blob://registry-blob", &config, true, None, &None, None)
+        .await;
+    assert!(matches!(result, Err(LakestreamError::ConfigError(_))));
+
+    let result = handler.list_buckets("blob://", &config, None).await;
+    assert!(matches!(result, Err(LakestreamError::ConfigError(_))));
+}
+
+#[tokio::test]
+async fn test_object_stores_from_config() {
+    let mut settings = HashMap::new();
+    settings.insert("uri".to_string(), "blob://".to_string());
+    let config = Config::new(settings);
+
+    // builtin backends only
+    let result = object_stores_from_config(config.clone(), &None).await;
+    assert!(matches!(result, Err(LakestreamError::ConfigError(_))));
+
+    let handler = handler();
+    let object_stores = object_stores_from_config_with_registry(
+        handler.registry(),
+        config,
+        &None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(object_stores.len(), 1);
+    assert_eq!(object_stores[0].println_path(), "blob://registry-blob");
+}
+
+#[tokio::test]
+async fn test_encrypted_registered_backend() {
+    let key = [7u8; 32];
+    let bucket =
+        MemoryBucket::new("registry-encrypted", Config::default()).unwrap();
+    bucket.clear();
+    EncryptedStore::new(bucket, MasterKey::from_bytes(&key).unwrap())
+        .put_object("a.txt", b"hello")
+        .await
+        .unwrap();
+
+    let temp_dir = tempdir().expect("Failed to create temp dir");
+    let key_path = temp_dir.path().join("key.bin");
+    std::fs::write(&key_path, key).unwrap();
+    let config = Config::new(HashMap::from([(
+        "LAKESTREAM_ENCRYPTION_KEYFILE".to_string(),
+        key_path.to_string_lossy().to_string(),
+    )]));
+
+    let handler = handler();
+    let data = handler
+        .get_object("blob://registry-encrypted/a.txt", &config, None)
+        .await
+        .unwrap();
+    assert_ne!(data, Some(b"hello".to_vec()));
+    // the inner object store is of the registered backend
+    let data = handler
+        .get_object("encrypted+blob://registry-encrypted/a.txt", &config, None)
+        .await
+        .unwrap();
+    assert_eq!(data, Some(b"hello".to_vec()));
+}
+
+#[tokio::test]
+async fn test_archive_in_registered_backend() {
+    let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+    for name in ["a.txt", "b.txt", "c.txt"] {
+        writer
+            .start_file(name, zip::write::FileOptions::default())
+            .unwrap();
+        writer.write_all(b"hello\n").unwrap();
+    }
+    let zip_data = writer.finish().unwrap().into_inner();
+    let bucket =
+        MemoryBucket::new("registry-archive", Config::default()).unwrap();
+    bucket.clear();
+    bucket.put_object("data.zip", &zip_data);
+
+    let config = Config::new(HashMap::new());
+    let line_matches = handler()
+        .grep_objects(
+            "archive+blob://registry-archive/data.zip/",
+            &config,
+            "hello",
+            &None,
+            2,
+            None,
+        )
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(line_matches.len(), 3);
+    // listed and read from a single download of the archive
+    assert_eq!(ZIP_GETS.load(Ordering::Relaxed), 1);
+}
@@ -0,0 +1,232 @@
+mod common;
+
+use std::sync::{Arc, Mutex};
+
+use common::{
+    s3_config, s3_list_objects_response, MockServer, Request, Response,
+};
+use futures::StreamExt;
+use lakestream::{
+    CallbackWrapper, FileObject, FileObjectFilter, ListObjectsResult,
+    ObjectStoreHandler,
+};
+
+fn keys(prefix: &str, count: usize) -> Vec<String> {
+    (0..count)
+        .map(|i| format!("{}file{:04}", prefix, i))
+        .collect()
+}
+
+fn start_bucket(keys: Vec<String>) -> MockServer {
+    MockServer::start(move |request| s3_list_objects_response(&keys, request))
+}
+
+#[tokio::test]
+async fn test_list_objects_pages() {
+    // more than a page (1000 keys) in each prefix
+    let server = start_bucket([keys("a/", 1200), keys("b/", 1200)].concat());
+    let config = s3_config(&server);
+    let handler = ObjectStoreHandler::new(None);
+
+    let list = |max_files| {
+        let handler = handler.clone();
+        let config = config.clone();
+        async move {
+            match handler
+                .list_objects(
+                    "s3://bucket",
+                    &config,
+                    true,
+                    max_files,
+                    &None,
+                    None,
+                )
+                .await
+                .unwrap()
+            {
+                Some(ListObjectsResult::FileObjects(fo)) => fo,
+                _ => panic!("Unexpected result type"),
+            }
+        }
+    };
+
+    // without max_files, all pages of all prefixes are listed
+    let file_objects = list(None).await;
+    let files = file_objects
+        .iter()
+        .filter(|fo| !fo.name().ends_with('/'))
+        .count();
+    assert_eq!(files, 2400);
+    assert_eq!(file_objects.len(), 2402);
+}
+
+#[tokio::test]
+async fn test_list_objects_excludes() {
+    let server = start_bucket(
+        [keys("table/_temporary/0/", 3), keys("table/year=2023/", 2)].concat(),
+    );
+    let config = s3_config(&server);
+    let handler = ObjectStoreHandler::new(None);
+
+    let filter = FileObjectFilter::new(None, None, None)
+        .and_then(|filter| filter.with_exclude("_temporary/"))
+        .unwrap();
+    let result = handler
+        .list_objects("s3://bucket", &config, true, None, &Some(filter), None)
+        .await
+        .unwrap();
+    let names: Vec<String> = match result {
+        Some(ListObjectsResult::FileObjects(fo)) => {
+            fo.iter().map(|fo| fo.name().to_string()).collect()
+        }
+        _ => panic!("Unexpected result type"),
+    };
+    assert_eq!(
+        names,
+        ["table/year=2023/file0000", "table/year=2023/file0001"]
+    );
+
+    // the excluded prefix is never listed
+    let requests = server.requests();
+    assert_eq!(requests.len(), 3);
+    assert!(requests
+        .iter()
+        .all(|request| !request.contains("_temporary")));
+}
+
+#[tokio::test]
+async fn test_list_objects_pages_callback() {
+    let server = start_bucket([keys("a/", 1200), keys("b/", 1200)].concat());
+    let config = s3_config(&server);
+    let handler = ObjectStoreHandler::new(None);
+
+    // a batch per page, not per prefix
+    let batches = Arc::new(Mutex::new(Vec::new()));
+    let callback_batches = Arc::clone(&batches);
+    let callback =
+        CallbackWrapper::create_sync(move |file_objects: &[FileObject]| {
+            callback_batches.lock().unwrap().push(file_objects.len());
+        });
+    let result = handler
+        .list_objects("s3://bucket", &config, true, None, &None, Some(callback))
+        .await
+        .unwrap();
+    assert!(result.is_none());
+    assert_eq!(*batches.lock().unwrap(), [2, 1000, 200, 1000, 200]);
+
+    // max_files ends the listing halfway a page
+    let result = handler
+        .list_objects("s3://bucket", &config, true, Some(1500), &None, None)
+        .await
+        .unwrap();
+    match result {
+        Some(ListObjectsResult::FileObjects(fo)) => assert_eq!(fo.len(), 1500),
+        _ => panic!("Unexpected result type"),
+    }
+    // the root, 2 pages of a/ and the first page of b/
+    assert_eq!(server.requests().len(), 5 + 4);
+}
+
+#[tokio::test]
+async fn test_list_objects_stream_pages() {
+    let server = start_bucket(keys("", 2500));
+    let config = s3_config(&server);
+    let handler = ObjectStoreHandler::new(None);
+
+    let mut stream = Box::pin(handler.list_objects_stream(
+        "s3://bucket",
+        &config,
+        false,
+        None,
+        &None,
+    ));
+    // the next page is only requested once the first is taken in full
+    for _ in 0..1000 {
+        stream.next().await.unwrap().unwrap();
+    }
+    assert_eq!(server.requests().len(), 1);
+    assert_eq!(stream.next().await.unwrap().unwrap().name(), "file1000");
+    assert_eq!(server.requests().len(), 2);
+
+    // and not at all once the stream is dropped
+    drop(stream);
+    assert_eq!(server.requests().len(), 2);
+
+    let stream =
+        handler.list_objects_stream("s3://bucket", &config, false, None, &None);
+    assert_eq!(stream.count().await, 2500);
+    assert_eq!(server.requests().len(), 2 + 3);
+}
+
+#[tokio::test]
+async fn test_grep_objects_while_listing() {
+    let keys = keys("logs/", 25);
+    let server = MockServer::start(move |request| {
+        match request.path().strip_prefix("/bucket/") {
+            Some(key) if !key.is_empty() => {
+                Response::new(200, format!("GET /\n{} id=42\n", key))
+            }
+            // pages of 10 keys, to keep the number of objects small
+            _ => {
+                let request = Request {
+                    target: request
+                        .target
+                        .replace("max-keys=1000", "max-keys=10"),
+                    ..request.clone()
+                };
+                s3_list_objects_response(&keys, &request)
+            }
+        }
+    });
+    let config = s3_config(&server);
+    let handler = ObjectStoreHandler::new(None);
+
+    let line_matches = handler
+        .grep_objects("s3://bucket/logs/", &config, "id=42", &None, 2, None)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(line_matches.len(), 25);
+    let line_match = &line_matches[20];
+    assert_eq!(line_match.key, "logs/file0020");
+    assert_eq!(line_match.line_number, 2);
+
+    // objects are fetched while the listing goes on: the second page is
+    // only requested once all but the last `concurrency` objects of the
+    // first page are fetched
+    let requests = server.requests();
+    assert!(requests[0].contains("list-type=2"));
+    let second_page = requests
+        .iter()
+        .position(|request| request.contains("continuation-token"))
+        .unwrap();
+    assert!(second_page > 8, "{:?}", requests);
+}
+
+#[tokio::test]
+async fn test_put_object_region_redirect() {
+    // the bucket is in another region than configured
+    let redirected = Arc::new(Mutex::new(false));
+    let server = MockServer::start(move |_| {
+        let mut redirected = redirected.lock().unwrap();
+        if *redirected {
+            Response::new(200, "")
+        } else {
+            *redirected = true;
+            Response::new(301, "")
+                .with_header("x-amz-bucket-region", "eu-west-1")
+        }
+    });
+    let config = s3_config(&server);
+    let handler = ObjectStoreHandler::new(None);
+
+    handler
+        .put_object("s3://bucket/a.txt", &config, b"data")
+        .await
+        .unwrap();
+    let requests = server.requests();
+    assert_eq!(requests.len(), 2);
+    assert!(requests
+        .iter()
+        .all(|request| request.starts_with("PUT /bucket/a.txt")));
+}